@@ -1,9 +1,10 @@
-use crate::utils::console_log;
-use crate::ComposantsEnum;
+use crate::utils::width_mask;
 use crate::Simulation;
 
+#[derive(Clone)]
 pub struct CircuitElement {
-    pub state: bool,
+    pub state: u64,
+    pub width: u8,
     pub inputs: Vec<CircuitElementEnum>,
     pub outputs: Vec<CircuitElementEnum>,
     pub id: usize,
@@ -12,57 +13,41 @@ pub struct CircuitElement {
 impl CircuitElement {
     pub fn new(id: usize) -> Self {
         CircuitElement {
-            state: false,
+            state: 0,
+            width: 1,
             inputs: Vec::new(),
             outputs: Vec::new(),
             id,
         }
     }
 
-    pub fn set_state(&mut self, new_state: bool) {
+    pub fn set_state(&mut self, new_state: u64) {
         if self.state == new_state {
             return;
         }
         // console_log(&format!("Set element {} to {}", self.id, new_state));
         self.state = new_state;
     }
+
+    pub fn mask(&self) -> u64 {
+        width_mask(self.width)
+    }
 }
 
+#[derive(Clone)]
 pub enum CircuitElementEnum {
     WireGroup(usize),
     Component(usize),
+    // Sortie `pin` d'un composant à plusieurs sorties
+    ComponentPin(usize, usize),
 }
 
 impl CircuitElementEnum {
-    pub fn get_state(&self, sim: &Simulation) -> bool {
+    pub fn get_state(&self, sim: &Simulation) -> u64 {
         match self {
-            CircuitElementEnum::WireGroup(id) => {
-                if let Some(group) = sim
-                    .wire_groups
-                    .iter()
-                    .find(|wg| wg.circuit_element.id == *id)
-                {
-                    group.circuit_element.state
-                } else {
-                    false
-                }
-            }
-            CircuitElementEnum::Component(id) => {
-                if let Some(composant) = sim.composants_map.get(id) {
-                    match composant {
-                        ComposantsEnum::OrGate(gate) => gate.gate.circuit_element.state,
-                        ComposantsEnum::AndGate(gate) => gate.gate.circuit_element.state,
-                        ComposantsEnum::Switch(switch) => switch.circuit_element.state,
-                        ComposantsEnum::XorGate(gate) => gate.gate.circuit_element.state,
-                        ComposantsEnum::LatchGate(gate) => gate.gate.circuit_element.state,
-                        ComposantsEnum::NotGate(gate) => gate.gate.circuit_element.state,
-                        ComposantsEnum::BufferGate(gate) => gate.gate.circuit_element.state,
-                        ComposantsEnum::TimerGate(gate) => gate.gate.circuit_element.state,
-                    }
-                } else {
-                    false
-                }
-            }
+            CircuitElementEnum::WireGroup(id) => sim.wire_group_state(*id),
+            CircuitElementEnum::Component(id) => sim.component_state(*id),
+            CircuitElementEnum::ComponentPin(id, pin) => sim.component_pin_state(*id, *pin),
         }
     }
 }
@@ -0,0 +1,64 @@
+use crate::components::MultiPinGate;
+use crate::types::{Orientation, Position};
+use crate::utils::width_mask;
+use crate::Simulation;
+
+// Additionneur de `bits` bits. Entrées : A, B (bus), CIN. Sorties : S (bus), COUT
+#[derive(Clone)]
+pub struct Adder {
+    pub gate: MultiPinGate,
+    pub bits: u8,
+}
+
+impl Adder {
+    pub fn new(id: usize, position: Position, orientation: Orientation, bits: u8) -> Self {
+        let mut gate = MultiPinGate::new(
+            id,
+            position,
+            orientation,
+            &MultiPinGate::column_offsets(-5, 3),
+            &MultiPinGate::column_offsets(0, 2),
+        );
+        gate.output_widths = vec![bits, 1];
+        Adder { gate, bits }
+    }
+
+    // Renvoie la somme tronquée à `bits` bits et la retenue sortante
+    pub fn add(bits: u8, a: u64, b: u64, carry_in: u64) -> (u64, u64) {
+        let mask = width_mask(bits);
+        let sum = (a & mask) as u128 + (b & mask) as u128 + (carry_in & 1) as u128;
+        ((sum as u64) & mask, ((sum >> bits) & 1) as u64)
+    }
+
+    pub fn compute_next_state(&self, sim: &Simulation) -> (Vec<u64>, bool) {
+        let inputs = self.gate.read_inputs(sim);
+        let (sum, carry) = Self::add(self.bits, inputs[0], inputs[1], inputs[2]);
+        let new_outputs = vec![sum, carry];
+        let is_different = self.gate.outputs_differ(&new_outputs);
+        (new_outputs, is_different)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::simulation::bench::{apply, drive_inputs, outputs};
+    use crate::types::Orientation;
+    use crate::Simulation;
+
+    #[test]
+    fn adder_propagates_carries() {
+        let mut sim = Simulation::new();
+        sim.add_adder(vec![0, 0], 8, Orientation::Right, 1);
+        // [A, B, CIN] -> [S, COUT]
+        let sources = drive_inputs(&mut sim, 1, &[8, 8]);
+        sim.compute_connections();
+        for (inputs, expected) in [
+            ([0x12, 0x34, 0], [0x46, 0]),
+            ([0xff, 0x00, 1], [0x00, 1]),
+            ([0x80, 0x80, 1], [0x01, 1]),
+        ] {
+            apply(&mut sim, &sources, &inputs);
+            assert_eq!(outputs(&sim, 1), expected);
+        }
+    }
+}
@@ -0,0 +1,187 @@
+use crate::components::{Adder, Comparator, MultiPinGate};
+use crate::types::{AluOperation, Orientation, Position};
+use crate::utils::width_mask;
+use crate::Simulation;
+
+// ALU de `bits` bits. Entrées : A, B (bus), OP (code `AluOperation` sur 3 bits).
+// Sorties : R (bus) puis les drapeaux Z, N, C, V. Pour SUB, C vaut 1 sans emprunt ;
+// pour les décalages, C est le dernier bit sorti
+#[derive(Clone)]
+pub struct Alu {
+    pub gate: MultiPinGate,
+    pub bits: u8,
+}
+
+impl Alu {
+    pub const OPERATIONS: [AluOperation; 8] = [
+        AluOperation::Add,
+        AluOperation::Sub,
+        AluOperation::And,
+        AluOperation::Or,
+        AluOperation::Xor,
+        AluOperation::ShiftLeft,
+        AluOperation::ShiftRight,
+        AluOperation::ShiftRightArithmetic,
+    ];
+
+    pub fn new(id: usize, position: Position, orientation: Orientation, bits: u8) -> Self {
+        let mut gate = MultiPinGate::new(
+            id,
+            position,
+            orientation,
+            &MultiPinGate::column_offsets(-5, 3),
+            &MultiPinGate::column_offsets(0, 5),
+        );
+        gate.output_widths = vec![bits, 1, 1, 1, 1];
+        Alu { gate, bits }
+    }
+
+    // Renvoie (résultat, retenue, débordement)
+    pub fn execute(&self, operation: AluOperation, a: u64, b: u64) -> (u64, u64, u64) {
+        let bits = self.bits;
+        let mask = width_mask(bits);
+        let (a, b) = (a & mask, b & mask);
+        let sign = |value: u64| (value >> (bits - 1)) & 1;
+        let shift = b.min(bits as u64) as u32;
+        // Au-delà de la largeur, le dernier bit sorti est un des 0 (ou bits de signe) entrés
+        let is_beyond_width = b > bits as u64;
+        let shifted_out = |value: u64, shift: u32, left: bool| -> u64 {
+            if shift == 0 || is_beyond_width {
+                0
+            } else if left {
+                (value >> (bits as u32 - shift)) & 1
+            } else {
+                (value >> (shift - 1)) & 1
+            }
+        };
+
+        match operation {
+            AluOperation::Add => {
+                let (result, carry) = Adder::add(bits, a, b, 0);
+                (result, carry, sign((a ^ result) & (b ^ result)))
+            }
+            AluOperation::Sub => {
+                let (result, carry) = Adder::add(bits, a, !b, 1);
+                (result, carry, sign((a ^ b) & (a ^ result)))
+            }
+            AluOperation::And => (a & b, 0, 0),
+            AluOperation::Or => (a | b, 0, 0),
+            AluOperation::Xor => (a ^ b, 0, 0),
+            AluOperation::ShiftLeft => {
+                let result = if shift >= 64 { 0 } else { (a << shift) & mask };
+                (result, shifted_out(a, shift, true), 0)
+            }
+            AluOperation::ShiftRight => {
+                let result = if shift >= 64 { 0 } else { a >> shift };
+                (result, shifted_out(a, shift, false), 0)
+            }
+            AluOperation::ShiftRightArithmetic => {
+                let signed = Comparator::to_signed(bits, a);
+                let result = (signed >> shift.min(63)) as u64 & mask;
+                let carry = if is_beyond_width {
+                    sign(a)
+                } else {
+                    shifted_out(a, shift, false)
+                };
+                (result, carry, 0)
+            }
+        }
+    }
+
+    pub fn compute_next_state(&self, sim: &Simulation) -> (Vec<u64>, bool) {
+        let inputs = self.gate.read_inputs(sim);
+        let operation = Self::OPERATIONS[(inputs[2] & 0b111) as usize];
+        let (result, carry, overflow) = self.execute(operation, inputs[0], inputs[1]);
+        let new_outputs = vec![
+            result,
+            (result == 0) as u64,
+            (result >> (self.bits - 1)) & 1,
+            carry,
+            overflow,
+        ];
+        let is_different = self.gate.outputs_differ(&new_outputs);
+        (new_outputs, is_different)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Alu;
+    use crate::simulation::bench::{apply, drive_inputs, outputs};
+    use crate::types::{AluOperation, Orientation};
+    use crate::Simulation;
+
+    fn alu(bits: u8) -> Alu {
+        Alu::new(1, [0, 0], Orientation::Right, bits)
+    }
+
+    #[test]
+    fn shifts_by_the_width_or_more_empty_the_operand() {
+        let alu8 = alu(8);
+        assert_eq!(alu8.execute(AluOperation::ShiftLeft, 0x81, 7), (0x80, 0, 0));
+        assert_eq!(alu8.execute(AluOperation::ShiftLeft, 0x81, 8), (0, 1, 0));
+        assert_eq!(alu8.execute(AluOperation::ShiftLeft, 0x81, 9), (0, 0, 0));
+        assert_eq!(alu8.execute(AluOperation::ShiftRight, 0x81, 8), (0, 1, 0));
+        assert_eq!(alu8.execute(AluOperation::ShiftRight, 0x81, 200), (0, 0, 0));
+        assert_eq!(
+            alu8.execute(AluOperation::ShiftRightArithmetic, 0x81, 8),
+            (0xff, 1, 0)
+        );
+        assert_eq!(
+            alu8.execute(AluOperation::ShiftRightArithmetic, 0x41, 9),
+            (0, 0, 0)
+        );
+        assert_eq!(
+            alu8.execute(AluOperation::ShiftRightArithmetic, 0x80, u64::MAX),
+            (0xff, 1, 0)
+        );
+
+        let alu64 = alu(64);
+        assert_eq!(
+            alu64.execute(AluOperation::ShiftLeft, 1, 63),
+            (1 << 63, 0, 0)
+        );
+        assert_eq!(alu64.execute(AluOperation::ShiftLeft, 1, 64), (0, 1, 0));
+        assert_eq!(
+            alu64.execute(AluOperation::ShiftRight, 1 << 63, 64),
+            (0, 1, 0)
+        );
+        assert_eq!(
+            alu64.execute(AluOperation::ShiftRightArithmetic, 1 << 63, 70),
+            (u64::MAX, 1, 0)
+        );
+    }
+
+    #[test]
+    fn arithmetic_sets_carry_and_overflow() {
+        let alu8 = alu(8);
+        assert_eq!(alu8.execute(AluOperation::Add, 0xff, 1), (0, 1, 0));
+        assert_eq!(alu8.execute(AluOperation::Add, 0x7f, 1), (0x80, 0, 1));
+        assert_eq!(alu8.execute(AluOperation::Sub, 5, 3), (2, 1, 0));
+        assert_eq!(alu8.execute(AluOperation::Sub, 3, 5), (0xfe, 0, 0));
+        assert_eq!(alu8.execute(AluOperation::Sub, 0x80, 1), (0x7f, 1, 1));
+    }
+
+    #[test]
+    fn opcode_pin_selects_the_operation_and_flags() {
+        let mut sim = Simulation::new();
+        sim.add_alu(vec![0, 0], 4, Orientation::Right, 1);
+        // [A, B, OP]
+        let sources = drive_inputs(&mut sim, 1, &[4, 4, 3]);
+        sim.compute_connections();
+        // Sorties : [R, Z, N, C, V]
+        for (op, a, b, expected) in [
+            (0, 0b0111, 0b0001, [0b1000, 0, 1, 0, 1]),
+            (1, 0b0011, 0b0011, [0, 1, 0, 1, 0]),
+            (2, 0b1100, 0b1010, [0b1000, 0, 1, 0, 0]),
+            (3, 0b1100, 0b1010, [0b1110, 0, 1, 0, 0]),
+            (4, 0b1100, 0b1010, [0b0110, 0, 0, 0, 0]),
+            (5, 0b1001, 0b0001, [0b0010, 0, 0, 1, 0]),
+            (6, 0b1001, 0b0100, [0, 1, 0, 1, 0]),
+            (7, 0b1001, 0b0001, [0b1100, 0, 1, 1, 0]),
+        ] {
+            apply(&mut sim, &sources, &[a, b, op]);
+            assert_eq!(outputs(&sim, 1), expected, "op {op}");
+        }
+    }
+}
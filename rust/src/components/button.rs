@@ -0,0 +1,96 @@
+use std::collections::VecDeque;
+
+use crate::circuit_element::CircuitElement;
+use crate::types::Position;
+
+// Bouton poussoir. Avec `hold_ticks == 0` la sortie reste haute tant que le bouton
+// est appuyé, sinon elle reste haute `hold_ticks` ticks après chaque appui. Un appui
+// est toujours vu par au moins un pas de simulation, même relâché aussitôt
+#[derive(Clone)]
+pub struct Button {
+    pub circuit_element: CircuitElement,
+    pub position: Position,
+    pub hold_ticks: u32,
+    pub is_pressed: bool,
+    pub remaining_ticks: u32,
+    // Appui pas encore simulé, et relâchement reporté à la fin du pas en cours
+    pub is_press_pending: bool,
+    pub is_release_pending: bool,
+    // (tick, appui) triés par tick
+    pub events: VecDeque<(u32, bool)>,
+}
+
+impl Button {
+    pub fn new(id: usize, position: Position, hold_ticks: u32) -> Self {
+        Button {
+            circuit_element: CircuitElement::new(id),
+            position,
+            hold_ticks,
+            is_pressed: false,
+            remaining_ticks: 0,
+            is_press_pending: false,
+            is_release_pending: false,
+            events: VecDeque::new(),
+        }
+    }
+
+    fn update_state(&mut self) {
+        let is_high = self.is_pressed || self.remaining_ticks > 0;
+        self.circuit_element.set_state(is_high as u64);
+    }
+
+    pub fn press(&mut self) {
+        if self.hold_ticks == 0 {
+            self.is_pressed = true;
+        } else {
+            self.remaining_ticks = self.hold_ticks;
+        }
+        self.is_press_pending = true;
+        self.is_release_pending = false;
+        self.update_state();
+    }
+
+    pub fn release(&mut self) {
+        if self.is_press_pending {
+            self.is_release_pending = true;
+            return;
+        }
+        self.is_pressed = false;
+        self.update_state();
+    }
+
+    pub fn schedule(&mut self, tick: u32, pressed: bool) {
+        let index = self
+            .events
+            .partition_point(|(event_tick, _)| *event_tick <= tick);
+        self.events.insert(index, (tick, pressed));
+    }
+
+    // Applique les évènements dont le tick est atteint
+    pub fn apply_events(&mut self, step_counter: u32) {
+        while let Some((tick, pressed)) = self.events.front().copied() {
+            if tick > step_counter {
+                break;
+            }
+            self.events.pop_front();
+            if pressed {
+                self.press();
+            } else {
+                self.release();
+            }
+        }
+    }
+
+    // Fin d'un pas de simulation : l'appui a été vu, un relâchement reporté s'applique
+    pub fn finish_step(&mut self) {
+        self.is_press_pending = false;
+        if self.is_release_pending {
+            self.is_release_pending = false;
+            self.is_pressed = false;
+        }
+        if self.remaining_ticks > 0 {
+            self.remaining_ticks -= 1;
+        }
+        self.update_state();
+    }
+}
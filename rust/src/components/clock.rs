@@ -0,0 +1,36 @@
+use crate::components::OneInputGate;
+use crate::Simulation;
+
+// Horloge : haute pendant `high_ticks`, basse pendant `low_ticks`, décalée de
+// `phase` ticks. L'entrée est un enable, actif si elle n'est pas connectée
+#[derive(Clone)]
+pub struct Clock {
+    pub gate: OneInputGate,
+    pub high_ticks: u32,
+    pub low_ticks: u32,
+    pub phase: u32,
+}
+
+impl Clock {
+    pub fn new(gate: OneInputGate, high_ticks: u32, low_ticks: u32, phase: u32) -> Clock {
+        Clock {
+            gate,
+            high_ticks: high_ticks.max(1),
+            low_ticks,
+            phase,
+        }
+    }
+
+    pub fn level_at(&self, clock_tick: u32) -> bool {
+        let period = self.high_ticks + self.low_ticks;
+        (clock_tick.wrapping_add(self.phase) % period) < self.high_ticks
+    }
+
+    pub fn compute_next_state(&self, sim: &Simulation) -> (u64, bool) {
+        let inputs = &self.gate.circuit_element.inputs;
+        let is_enabled = inputs.is_empty() || inputs.iter().any(|input| input.get_state(sim) != 0);
+        let new_state = (is_enabled && self.level_at(sim.clock_tick)) as u64;
+        let is_different = new_state != self.gate.circuit_element.state;
+        (new_state, is_different)
+    }
+}
@@ -0,0 +1,85 @@
+use std::cmp::Ordering;
+
+use crate::components::MultiPinGate;
+use crate::types::{Orientation, Position};
+use crate::utils::width_mask;
+use crate::Simulation;
+
+// Comparateur de `bits` bits, en complément à deux si `signed`.
+// Entrées : A, B (bus). Sorties : LT, EQ, GT
+#[derive(Clone)]
+pub struct Comparator {
+    pub gate: MultiPinGate,
+    pub bits: u8,
+    pub signed: bool,
+}
+
+impl Comparator {
+    pub fn new(
+        id: usize,
+        position: Position,
+        orientation: Orientation,
+        bits: u8,
+        signed: bool,
+    ) -> Self {
+        Comparator {
+            gate: MultiPinGate::new(
+                id,
+                position,
+                orientation,
+                &MultiPinGate::column_offsets(-5, 2),
+                &MultiPinGate::column_offsets(0, 3),
+            ),
+            bits,
+            signed,
+        }
+    }
+
+    pub fn to_signed(bits: u8, value: u64) -> i64 {
+        let shift = 64 - bits as u32;
+        ((value << shift) as i64) >> shift
+    }
+
+    pub fn compare(&self, a: u64, b: u64) -> Ordering {
+        let mask = width_mask(self.bits);
+        if self.signed {
+            Self::to_signed(self.bits, a & mask).cmp(&Self::to_signed(self.bits, b & mask))
+        } else {
+            (a & mask).cmp(&(b & mask))
+        }
+    }
+
+    pub fn compute_next_state(&self, sim: &Simulation) -> (Vec<u64>, bool) {
+        let ordering = self.compare(self.gate.input_state(sim, 0), self.gate.input_state(sim, 1));
+        let new_outputs = vec![
+            (ordering == Ordering::Less) as u64,
+            (ordering == Ordering::Equal) as u64,
+            (ordering == Ordering::Greater) as u64,
+        ];
+        let is_different = self.gate.outputs_differ(&new_outputs);
+        (new_outputs, is_different)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::simulation::bench::{apply, drive_inputs, outputs};
+    use crate::types::Orientation;
+    use crate::Simulation;
+
+    #[test]
+    fn comparator_orders_unsigned_and_signed_values() {
+        // 0xff vaut 255 non signé et -1 signé
+        for (signed, expected) in [(false, [0, 0, 1]), (true, [1, 0, 0])] {
+            let mut sim = Simulation::new();
+            sim.add_comparator(vec![0, 0], 8, signed, Orientation::Right, 1);
+            // [A, B] -> [LT, EQ, GT]
+            let sources = drive_inputs(&mut sim, 1, &[8, 8]);
+            sim.compute_connections();
+            apply(&mut sim, &sources, &[0xff, 0x01]);
+            assert_eq!(outputs(&sim, 1), expected);
+            apply(&mut sim, &sources, &[0x42, 0x42]);
+            assert_eq!(outputs(&sim, 1), [0, 1, 0]);
+        }
+    }
+}
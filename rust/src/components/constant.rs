@@ -0,0 +1,30 @@
+use crate::circuit_element::CircuitElement;
+use crate::types::Position;
+
+// Source constante : tous les bits à 1 (ConstantHigh) ou à 0 (ConstantLow).
+// Sa largeur est celle du plus large des wire groups qu'elle alimente
+#[derive(Clone)]
+pub struct Constant {
+    pub circuit_element: CircuitElement,
+    pub position: Position,
+    pub high: bool,
+}
+
+impl Constant {
+    pub fn new(id: usize, position: Position, high: bool) -> Self {
+        Constant {
+            circuit_element: CircuitElement::new(id),
+            position,
+            high,
+        }
+    }
+
+    pub fn compute_next_state(&self) -> (u64, bool) {
+        let new_state = if self.high {
+            self.circuit_element.mask()
+        } else {
+            0
+        };
+        (new_state, new_state != self.circuit_element.state)
+    }
+}
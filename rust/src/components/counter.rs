@@ -0,0 +1,144 @@
+use crate::components::MultiPinGate;
+use crate::types::{Orientation, Position};
+use crate::utils::width_mask;
+
+// Compteur de `bits` bits. Entrées : EN, DOWN, LOAD, D (bus), CLK, CLR.
+// Sorties : Q (bus) et CARRY, haute quand le compteur est à sa borne dans le sens
+// de comptage. Sans `wrap`, le compteur sature au lieu de reboucler
+#[derive(Clone)]
+pub struct Counter {
+    pub gate: MultiPinGate,
+    pub bits: u8,
+    pub wrap: bool,
+    pub value: u64,
+    pub last_clock: bool,
+}
+
+impl Counter {
+    pub const ENABLE_PIN: usize = 0;
+    pub const DOWN_PIN: usize = 1;
+    pub const LOAD_PIN: usize = 2;
+    pub const DATA_PIN: usize = 3;
+    pub const CLOCK_PIN: usize = 4;
+    pub const CLEAR_PIN: usize = 5;
+
+    pub fn new(
+        id: usize,
+        position: Position,
+        orientation: Orientation,
+        bits: u8,
+        wrap: bool,
+    ) -> Self {
+        let mut gate = MultiPinGate::new(
+            id,
+            position,
+            orientation,
+            &MultiPinGate::column_offsets(-5, 6),
+            &MultiPinGate::column_offsets(0, 2),
+        );
+        gate.output_widths = vec![bits, 1];
+        Counter {
+            gate,
+            bits,
+            wrap,
+            value: 0,
+            last_clock: false,
+        }
+    }
+
+    pub fn set_value(&mut self, value: u64) {
+        self.value = value & width_mask(self.bits);
+    }
+
+    fn is_at_limit(&self, is_down: bool) -> bool {
+        if is_down {
+            self.value == 0
+        } else {
+            self.value == width_mask(self.bits)
+        }
+    }
+
+    fn count(&mut self, is_down: bool) {
+        if self.is_at_limit(is_down) && !self.wrap {
+            return;
+        }
+        let next = if is_down {
+            self.value.wrapping_sub(1)
+        } else {
+            self.value.wrapping_add(1)
+        };
+        self.set_value(next);
+    }
+
+    // Reçoit les entrées échantillonnées pendant le tick et renvoie si les sorties ont changé
+    pub fn update_inputs(&mut self, inputs: &[u64]) -> bool {
+        let level = |pin: usize| inputs.get(pin).is_some_and(|state| state & 1 != 0);
+        let clock = level(Self::CLOCK_PIN);
+        let is_down = level(Self::DOWN_PIN);
+        let is_enabled = !self.gate.is_input_connected(Self::ENABLE_PIN) || level(Self::ENABLE_PIN);
+        if level(Self::CLEAR_PIN) {
+            self.value = 0;
+        } else if clock && !self.last_clock {
+            if level(Self::LOAD_PIN) {
+                self.set_value(inputs.get(Self::DATA_PIN).copied().unwrap_or(0));
+            } else if is_enabled {
+                self.count(is_down);
+            }
+        }
+        self.last_clock = clock;
+
+        let new_outputs = vec![self.value, self.is_at_limit(is_down) as u64];
+        let is_different = self.gate.outputs_differ(&new_outputs);
+        self.gate.set_outputs(new_outputs);
+        is_different
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::simulation::bench::{apply, drive_inputs, outputs};
+    use crate::types::Orientation;
+    use crate::Simulation;
+
+    // Compteur 1 de 2 bits ; renvoie [Q, CARRY] après chaque front montant
+    fn count(wrap: bool, inputs: &[u64], edges: usize) -> Vec<Vec<u64>> {
+        let mut sim = Simulation::new();
+        sim.add_counter(vec![0, 0], 2, wrap, Orientation::Right, 1);
+        // [EN, DOWN, LOAD, D, CLK, CLR]
+        let sources = drive_inputs(&mut sim, 1, &[1, 1, 1, 2]);
+        sim.compute_connections();
+        (0..edges)
+            .map(|_| {
+                let mut values = inputs.to_vec();
+                values[4] = 0;
+                apply(&mut sim, &sources, &values);
+                values[4] = 1;
+                apply(&mut sim, &sources, &values);
+                outputs(&sim, 1)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn counter_wraps_or_saturates() {
+        let up = [1, 0, 0, 0, 0, 0];
+        assert_eq!(
+            count(true, &up, 5),
+            vec![vec![1, 0], vec![2, 0], vec![3, 1], vec![0, 0], vec![1, 0]]
+        );
+        assert_eq!(
+            count(false, &up, 5),
+            vec![vec![1, 0], vec![2, 0], vec![3, 1], vec![3, 1], vec![3, 1]]
+        );
+        let down = [1, 1, 0, 0, 0, 0];
+        assert_eq!(count(true, &down, 2), vec![vec![3, 0], vec![2, 0]]);
+        assert_eq!(count(false, &down, 2), vec![vec![0, 1], vec![0, 1]]);
+    }
+
+    #[test]
+    fn counter_loads_holds_and_clears() {
+        assert_eq!(count(true, &[1, 0, 1, 2, 0, 0], 2), vec![vec![2, 0]; 2]);
+        assert_eq!(count(true, &[0, 0, 0, 0, 0, 0], 2), vec![vec![0, 0]; 2]);
+        assert_eq!(count(true, &[1, 0, 0, 0, 0, 1], 2), vec![vec![0, 0]; 2]);
+    }
+}
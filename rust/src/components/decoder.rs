@@ -0,0 +1,58 @@
+use crate::components::multiplexer::MAX_SELECT_BITS;
+use crate::components::MultiPinGate;
+use crate::types::{Orientation, Position};
+use crate::Simulation;
+
+// Décodeur binaire : `select_bits` entrées, `2^select_bits` sorties dont une seule est haute
+#[derive(Clone)]
+pub struct Decoder {
+    pub gate: MultiPinGate,
+    pub select_bits: u8,
+}
+
+impl Decoder {
+    pub fn new(id: usize, position: Position, orientation: Orientation, select_bits: u8) -> Self {
+        let select_bits = select_bits.clamp(1, MAX_SELECT_BITS);
+        Decoder {
+            gate: MultiPinGate::new(
+                id,
+                position,
+                orientation,
+                &MultiPinGate::column_offsets(-5, select_bits as usize),
+                &MultiPinGate::column_offsets(0, 1 << select_bits),
+            ),
+            select_bits,
+        }
+    }
+
+    pub fn compute_next_state(&self, sim: &Simulation) -> (Vec<u64>, bool) {
+        let inputs = self.gate.read_inputs(sim);
+        let select = MultiPinGate::bits_value(&inputs, 0, self.select_bits as usize) as usize;
+        let new_outputs: Vec<u64> = (0..1usize << self.select_bits)
+            .map(|output| (output == select) as u64)
+            .collect();
+        let is_different = self.gate.outputs_differ(&new_outputs);
+        (new_outputs, is_different)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::simulation::bench::{apply, drive_inputs, outputs};
+    use crate::types::Orientation;
+    use crate::Simulation;
+
+    #[test]
+    fn decoder_raises_exactly_the_selected_output() {
+        let mut sim = Simulation::new();
+        sim.add_decoder(vec![0, 0], 3, Orientation::Right, 1);
+        let sources = drive_inputs(&mut sim, 1, &[]);
+        sim.compute_connections();
+        for select in 0..8u64 {
+            let bits: Vec<u64> = (0..3).map(|bit| (select >> bit) & 1).collect();
+            apply(&mut sim, &sources, &bits);
+            let expected: Vec<u64> = (0..8).map(|output| (output == select) as u64).collect();
+            assert_eq!(outputs(&sim, 1), expected);
+        }
+    }
+}
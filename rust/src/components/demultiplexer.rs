@@ -0,0 +1,71 @@
+use crate::components::multiplexer::MAX_SELECT_BITS;
+use crate::components::MultiPinGate;
+use crate::types::{Orientation, Position};
+use crate::Simulation;
+
+// Démultiplexeur `2^select_bits` voies : entrée de données à gauche, pins de
+// sélection en bas ; la sortie sélectionnée recopie l'entrée, les autres valent 0
+#[derive(Clone)]
+pub struct Demultiplexer {
+    pub gate: MultiPinGate,
+    pub select_bits: u8,
+}
+
+impl Demultiplexer {
+    pub fn new(id: usize, position: Position, orientation: Orientation, select_bits: u8) -> Self {
+        let select_bits = select_bits.clamp(1, MAX_SELECT_BITS);
+        let ways = 1usize << select_bits;
+        let mut input_offsets = vec![[-5, 0]];
+        input_offsets.extend(MultiPinGate::row_offsets(
+            -(ways as i32) - 1,
+            select_bits as usize,
+        ));
+        Demultiplexer {
+            gate: MultiPinGate::new(
+                id,
+                position,
+                orientation,
+                &input_offsets,
+                &MultiPinGate::column_offsets(0, ways),
+            ),
+            select_bits,
+        }
+    }
+
+    pub fn compute_next_state(&self, sim: &Simulation) -> (Vec<u64>, bool) {
+        let inputs = self.gate.read_inputs(sim);
+        let select = MultiPinGate::bits_value(&inputs, 1, self.select_bits as usize) as usize;
+        let new_outputs: Vec<u64> = (0..1usize << self.select_bits)
+            .map(|way| if way == select { inputs[0] } else { 0 })
+            .collect();
+        let is_different = self.gate.outputs_differ(&new_outputs);
+        (new_outputs, is_different)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::simulation::bench::{apply, drive_inputs, outputs};
+    use crate::types::Orientation;
+    use crate::{ComposantsEnum, Simulation};
+
+    #[test]
+    fn demultiplexer_copies_its_input_to_the_selected_output() {
+        let mut sim = Simulation::new();
+        sim.add_demultiplexer(vec![0, 0], 2, Orientation::Right, 1);
+        // [D, S0, S1], données sur 4 bits
+        let sources = drive_inputs(&mut sim, 1, &[4]);
+        sim.compute_connections();
+        let ComposantsEnum::Demultiplexer(demultiplexer) = &sim.composants_map[&1] else {
+            panic!("demultiplexer expected");
+        };
+        assert_eq!(demultiplexer.gate.output_widths, vec![4; 4]);
+        for select in 0..4u64 {
+            apply(&mut sim, &sources, &[0b1011, select & 1, select >> 1]);
+            let expected: Vec<u64> = (0..4)
+                .map(|way| if way == select { 0b1011 } else { 0 })
+                .collect();
+            assert_eq!(outputs(&sim, 1), expected);
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use crate::components::MultiPinGate;
+use crate::types::{Orientation, Position};
+use crate::utils::{width_mask, MAX_WIDTH};
+
+// Banc de `bits` interrupteurs, une sortie par interrupteur (bit 0 en haut).
+// En mode `radio` un seul interrupteur peut être fermé à la fois
+#[derive(Clone)]
+pub struct DipSwitch {
+    pub gate: MultiPinGate,
+    pub bits: u8,
+    pub radio: bool,
+    pub value: u64,
+}
+
+impl DipSwitch {
+    pub fn new(
+        id: usize,
+        position: Position,
+        orientation: Orientation,
+        bits: u8,
+        radio: bool,
+    ) -> Self {
+        let bits = bits.clamp(1, MAX_WIDTH);
+        DipSwitch {
+            gate: MultiPinGate::new(
+                id,
+                position,
+                orientation,
+                &[],
+                &MultiPinGate::column_offsets(0, bits as usize),
+            ),
+            bits,
+            radio,
+            value: 0,
+        }
+    }
+
+    // En mode radio seul le bit de poids faible de `value` est conservé
+    pub fn set_value(&mut self, value: u64) {
+        let value = value & width_mask(self.bits);
+        self.value = if self.radio {
+            value & value.wrapping_neg()
+        } else {
+            value
+        };
+    }
+
+    pub fn set_bit(&mut self, bit: u8, state: bool) {
+        if bit >= self.bits {
+            return;
+        }
+        if state && self.radio {
+            self.value = 1 << bit;
+        } else if state {
+            self.value |= 1 << bit;
+        } else {
+            self.value &= !(1 << bit);
+        }
+    }
+
+    pub fn compute_next_state(&self) -> (Vec<u64>, bool) {
+        let new_outputs: Vec<u64> = (0..self.bits).map(|bit| (self.value >> bit) & 1).collect();
+        let is_different = self.gate.outputs_differ(&new_outputs);
+        (new_outputs, is_different)
+    }
+}
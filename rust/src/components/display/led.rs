@@ -0,0 +1,32 @@
+use crate::components::MultiPinGate;
+use crate::types::{DisplayData, Orientation, Position};
+use crate::Simulation;
+
+// Led allumée tant que son entrée est non nulle. La couleur (0xRRGGBB) n'est
+// utilisée que par le front
+#[derive(Clone)]
+pub struct Led {
+    pub gate: MultiPinGate,
+    pub color: u32,
+}
+
+impl Led {
+    pub fn new(id: usize, position: Position, orientation: Orientation, color: u32) -> Self {
+        Led {
+            gate: MultiPinGate::new(id, position, orientation, &[[0, 0]], &[]),
+            color,
+        }
+    }
+
+    // Un afficheur n'a pas de sortie : son changement d'état ne relance pas la propagation
+    pub fn compute_next_state(&self, sim: &Simulation) -> (u64, bool) {
+        (self.gate.input_state(sim, 0), false)
+    }
+
+    pub fn display_data(&self) -> DisplayData {
+        DisplayData::Led {
+            value: self.gate.circuit_element.state,
+            color: self.color,
+        }
+    }
+}
@@ -0,0 +1,7 @@
+mod led;
+mod pixel_matrix;
+mod seven_segment;
+
+pub use led::Led;
+pub use pixel_matrix::PixelMatrix;
+pub use seven_segment::SevenSegment;
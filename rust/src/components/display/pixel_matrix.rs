@@ -0,0 +1,107 @@
+use crate::components::MultiPinGate;
+use crate::types::{DisplayData, Orientation, PixelMatrixMode, Position};
+use crate::utils::{width_mask, MAX_WIDTH};
+
+// Matrice de pixels de `rows` lignes sur `columns` colonnes.
+// En mode `RowColumn` les entrées sont ROW puis COL (bus) : chaque ligne dont le bit
+// est haut dans ROW prend la valeur de COL, ce qui permet un affichage par balayage.
+// En mode `Framebuffer` les entrées sont ADDR, DATA, WE puis CLK : la ligne ADDR
+// prend la valeur DATA sur le front montant quand WE est haut.
+// L'état du composant est un compteur de révisions de l'image
+#[derive(Clone)]
+pub struct PixelMatrix {
+    pub gate: MultiPinGate,
+    pub mode: PixelMatrixMode,
+    pub rows: u8,
+    pub columns: u8,
+    pub pixels: Vec<u64>,
+    pub last_clock: bool,
+}
+
+impl PixelMatrix {
+    pub fn new(
+        id: usize,
+        position: Position,
+        orientation: Orientation,
+        rows: u8,
+        columns: u8,
+        mode: PixelMatrixMode,
+    ) -> Self {
+        let rows = rows.clamp(1, MAX_WIDTH);
+        let columns = columns.clamp(1, MAX_WIDTH);
+        let inputs = match mode {
+            PixelMatrixMode::RowColumn => 2,
+            PixelMatrixMode::Framebuffer => 4,
+        };
+        PixelMatrix {
+            gate: MultiPinGate::new(
+                id,
+                position,
+                orientation,
+                &MultiPinGate::column_offsets(-5, inputs),
+                &[],
+            ),
+            mode,
+            rows,
+            columns,
+            pixels: vec![0; rows as usize],
+            last_clock: false,
+        }
+    }
+
+    pub fn set_row(&mut self, row: usize, value: u64) -> bool {
+        let value = value & width_mask(self.columns);
+        match self.pixels.get_mut(row) {
+            Some(pixels) if *pixels != value => {
+                *pixels = value;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.pixels.iter_mut().for_each(|row| *row = 0);
+        self.bump_revision();
+    }
+
+    fn bump_revision(&mut self) {
+        let revision = self.gate.circuit_element.state.wrapping_add(1);
+        self.gate.circuit_element.set_state(revision);
+    }
+
+    // Reçoit les entrées échantillonnées pendant le tick. La matrice n'ayant pas
+    // de sortie, la propagation n'est jamais relancée
+    pub fn update_inputs(&mut self, inputs: &[u64]) -> bool {
+        let mut changed = false;
+        match self.mode {
+            PixelMatrixMode::RowColumn => {
+                let (rows, columns) = (inputs[0], inputs[1]);
+                for row in 0..self.rows as usize {
+                    if (rows >> row) & 1 != 0 {
+                        changed |= self.set_row(row, columns);
+                    }
+                }
+            }
+            PixelMatrixMode::Framebuffer => {
+                let clock = inputs[3] & 1 != 0;
+                if clock && !self.last_clock && inputs[2] & 1 != 0 {
+                    changed |= self.set_row(inputs[0] as usize, inputs[1]);
+                }
+                self.last_clock = clock;
+            }
+        }
+        if changed {
+            self.bump_revision();
+        }
+        false
+    }
+
+    pub fn display_data(&self) -> DisplayData {
+        DisplayData::PixelMatrix {
+            rows: self.rows,
+            columns: self.columns,
+            pixels: self.pixels.clone(),
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use crate::components::MultiPinGate;
+use crate::types::{DisplayData, Orientation, Position, SevenSegmentMode};
+use crate::Simulation;
+
+// Segments allumés pour chaque chiffre hexadécimal (bit 0 = a, ..., bit 6 = g)
+const HEX_SEGMENTS: [u8; 16] = [
+    0x3F, 0x06, 0x5B, 0x4F, 0x66, 0x6D, 0x7D, 0x07, 0x7F, 0x6F, 0x77, 0x7C, 0x39, 0x5E, 0x79, 0x71,
+];
+
+// Afficheur 7 segments. En mode `Segments` les entrées sont a..g puis DP,
+// en mode `Hex` une entrée de bus 4 bits puis DP
+#[derive(Clone)]
+pub struct SevenSegment {
+    pub gate: MultiPinGate,
+    pub mode: SevenSegmentMode,
+}
+
+impl SevenSegment {
+    pub fn new(
+        id: usize,
+        position: Position,
+        orientation: Orientation,
+        mode: SevenSegmentMode,
+    ) -> Self {
+        let inputs = match mode {
+            SevenSegmentMode::Segments => 8,
+            SevenSegmentMode::Hex => 2,
+        };
+        SevenSegment {
+            gate: MultiPinGate::new(
+                id,
+                position,
+                orientation,
+                &MultiPinGate::column_offsets(-5, inputs),
+                &[],
+            ),
+            mode,
+        }
+    }
+
+    pub fn compute_next_state(&self, sim: &Simulation) -> (u64, bool) {
+        let inputs = self.gate.read_inputs(sim);
+        let segments = match self.mode {
+            SevenSegmentMode::Segments => MultiPinGate::bits_value(&inputs, 0, 8),
+            SevenSegmentMode::Hex => {
+                HEX_SEGMENTS[(inputs[0] & 0xF) as usize] as u64 | (inputs[1] & 1) << 7
+            }
+        };
+        (segments, false)
+    }
+
+    pub fn display_data(&self) -> DisplayData {
+        DisplayData::SevenSegment {
+            segments: self.gate.circuit_element.state as u8,
+        }
+    }
+}
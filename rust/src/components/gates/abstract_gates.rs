@@ -1,6 +1,9 @@
 use crate::circuit_element::CircuitElement;
 use crate::types::{Orientation, Position};
+use crate::utils::width_mask;
+use crate::Simulation;
 
+#[derive(Clone)]
 pub struct TwoInputsGate {
     pub circuit_element: CircuitElement,
     pub output_position: Position,
@@ -38,6 +41,75 @@ impl TwoInputsGate {
     }
 }
 
+// Porte à `2..=16` entrées ; à deux entrées, même placement que `TwoInputsGate`
+#[derive(Clone)]
+pub struct MultiInputsGate {
+    pub circuit_element: CircuitElement,
+    pub output_position: Position,
+    pub input_positions: Vec<Position>,
+    pub position: Position,
+    // Wire group connecté à chaque pin d'entrée, dans l'ordre des pins
+    pub input_groups: Vec<Option<usize>>,
+}
+
+impl MultiInputsGate {
+    pub const MIN_INPUTS: u8 = 2;
+    pub const MAX_INPUTS: u8 = 16;
+
+    pub fn new(id: usize, position: Position, orientation: Orientation, inputs: u8) -> Self {
+        let inputs = inputs.clamp(Self::MIN_INPUTS, Self::MAX_INPUTS);
+        let input_positions = if inputs == 2 {
+            TwoInputsGate::new(id, position, orientation)
+                .input_positions
+                .to_vec()
+        } else {
+            MultiPinGate::column_offsets(-5, inputs as usize)
+                .iter()
+                .map(|offset| oriented_position(position, *offset, orientation))
+                .collect()
+        };
+
+        MultiInputsGate {
+            circuit_element: CircuitElement::new(id),
+            position,
+            output_position: position,
+            input_groups: vec![None; input_positions.len()],
+            input_positions,
+        }
+    }
+
+    // Vrai si chaque pin d'entrée est reliée à un wire group
+    pub fn are_all_inputs_connected(&self) -> bool {
+        self.input_groups.iter().all(Option::is_some)
+    }
+
+    // États des pins reliées, une valeur par pin même si plusieurs partagent un wire group
+    fn connected_inputs<'a>(&'a self, sim: &'a Simulation) -> impl Iterator<Item = u64> + 'a {
+        self.input_groups
+            .iter()
+            .flatten()
+            .map(|group| sim.wire_group_state(*group))
+    }
+
+    pub fn and_inputs(&self, sim: &Simulation) -> u64 {
+        if !self.are_all_inputs_connected() {
+            return 0;
+        }
+        self.connected_inputs(sim)
+            .fold(u64::MAX, |acc, state| acc & state)
+            & self.circuit_element.mask()
+    }
+
+    pub fn or_inputs(&self, sim: &Simulation) -> u64 {
+        self.connected_inputs(sim).fold(0, |acc, state| acc | state) & self.circuit_element.mask()
+    }
+
+    pub fn xor_inputs(&self, sim: &Simulation) -> u64 {
+        self.connected_inputs(sim).fold(0, |acc, state| acc ^ state) & self.circuit_element.mask()
+    }
+}
+
+#[derive(Clone)]
 pub struct OneInputGate {
     pub circuit_element: CircuitElement,
     pub output_position: Position,
@@ -62,3 +134,217 @@ impl OneInputGate {
         }
     }
 }
+
+// Calcule la position d'une pin à partir de son décalage exprimé pour
+// l'orientation `Right` (entrées à gauche, sortie à droite)
+pub fn oriented_position(
+    position: Position,
+    offset: [i32; 2],
+    orientation: Orientation,
+) -> Position {
+    match orientation {
+        Orientation::Right => [position[0] + offset[0], position[1] + offset[1]],
+        Orientation::Up => [position[0] - offset[1], position[1] + offset[0]],
+        Orientation::Down => [position[0] + offset[1], position[1] - offset[0]],
+        Orientation::Left => [position[0] - offset[0], position[1] - offset[1]],
+    }
+}
+
+#[derive(Clone)]
+pub struct MultiPinGate {
+    pub circuit_element: CircuitElement,
+    pub position: Position,
+    pub orientation: Orientation,
+    pub input_positions: Vec<Position>,
+    pub output_positions: Vec<Position>,
+    // Wire group connecté à chaque pin d'entrée, dans l'ordre des pins
+    pub input_groups: Vec<Option<usize>>,
+    pub output_widths: Vec<u8>,
+    pub output_states: Vec<u64>,
+}
+
+impl MultiPinGate {
+    pub fn new(
+        id: usize,
+        position: Position,
+        orientation: Orientation,
+        input_offsets: &[[i32; 2]],
+        output_offsets: &[[i32; 2]],
+    ) -> Self {
+        MultiPinGate {
+            circuit_element: CircuitElement::new(id),
+            position,
+            orientation,
+            input_positions: input_offsets
+                .iter()
+                .map(|offset| oriented_position(position, *offset, orientation))
+                .collect(),
+            output_positions: output_offsets
+                .iter()
+                .map(|offset| oriented_position(position, *offset, orientation))
+                .collect(),
+            input_groups: vec![None; input_offsets.len()],
+            output_widths: vec![1; output_offsets.len()],
+            output_states: vec![0; output_offsets.len()],
+        }
+    }
+
+    // Pins alignées verticalement, espacées de 2, centrées sur `y = 0`
+    pub fn column_offsets(x: i32, count: usize) -> Vec<[i32; 2]> {
+        (0..count as i32)
+            .map(|i| [x, (count as i32 - 1) - 2 * i])
+            .collect()
+    }
+
+    // Pins alignées horizontalement à la hauteur `y`, en partant du côté de la sortie
+    pub fn row_offsets(y: i32, count: usize) -> Vec<[i32; 2]> {
+        (0..count as i32).map(|i| [-1 - 2 * i, y]).collect()
+    }
+
+    // Valeur formée par les bits de poids faible des pins `start..start + count`
+    pub fn bits_value(inputs: &[u64], start: usize, count: usize) -> u64 {
+        (0..count).fold(0, |acc, bit| {
+            acc | ((inputs.get(start + bit).copied().unwrap_or(0) & 1) << bit)
+        })
+    }
+
+    pub fn input_state(&self, sim: &Simulation, pin: usize) -> u64 {
+        match self.input_groups.get(pin) {
+            Some(Some(group)) => sim.wire_group_state(*group),
+            _ => 0,
+        }
+    }
+
+    pub fn is_input_connected(&self, pin: usize) -> bool {
+        matches!(self.input_groups.get(pin), Some(Some(_)))
+    }
+
+    pub fn read_inputs(&self, sim: &Simulation) -> Vec<u64> {
+        (0..self.input_groups.len())
+            .map(|pin| self.input_state(sim, pin))
+            .collect()
+    }
+
+    pub fn output_state(&self, pin: usize) -> u64 {
+        self.output_states.get(pin).copied().unwrap_or(0)
+    }
+
+    pub fn outputs_differ(&self, new_outputs: &[u64]) -> bool {
+        new_outputs != self.output_states.as_slice()
+    }
+
+    // Met à jour les sorties ; l'état du composant est la concaténation des sorties
+    pub fn set_outputs(&mut self, new_outputs: Vec<u64>) {
+        let mut packed: u64 = 0;
+        let mut offset: u32 = 0;
+        for (value, width) in new_outputs.iter().zip(self.output_widths.iter()) {
+            if offset >= 64 {
+                break;
+            }
+            packed |= (value & width_mask(*width)) << offset;
+            offset += *width as u32;
+        }
+        self.output_states = new_outputs;
+        self.circuit_element.set_state(packed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MultiInputsGate;
+    use crate::simulation::bench::{apply, drive_pins};
+    use crate::types::{LogicGateKind, Orientation};
+    use crate::wire::Wire;
+    use crate::{ComposantsEnum, Simulation};
+
+    // Porte 1 à `inputs` entrées de largeur `width`, une source par entrée
+    fn gate_bench(kind: LogicGateKind, inputs: u8, width: u8) -> (Simulation, Vec<usize>) {
+        let mut sim = Simulation::new();
+        sim.add_logic_gate(vec![0, 0], kind, inputs, Orientation::Right, 1);
+        let pins: Vec<_> = MultiInputsGate::new(1, [0, 0], Orientation::Right, inputs)
+            .input_positions
+            .into_iter()
+            .map(|position| (position, width))
+            .collect();
+        let sources = drive_pins(&mut sim, &pins);
+        sim.wires_map
+            .insert(2, Wire::new_bus(2, vec![[0, 0]], width));
+        sim.compute_connections();
+        (sim, sources)
+    }
+
+    #[test]
+    fn n_input_gates_follow_their_truth_table() {
+        let kinds = [
+            LogicGateKind::And,
+            LogicGateKind::Or,
+            LogicGateKind::Xor,
+            LogicGateKind::Nand,
+            LogicGateKind::Nor,
+            LogicGateKind::Xnor,
+        ];
+        let expected = |kind, row: u64| match kind {
+            LogicGateKind::And => row == 0b1111,
+            LogicGateKind::Or => row != 0,
+            LogicGateKind::Xor => !row.count_ones().is_multiple_of(2),
+            LogicGateKind::Nand => row != 0b1111,
+            LogicGateKind::Nor => row == 0,
+            LogicGateKind::Xnor => row.count_ones().is_multiple_of(2),
+        };
+        for kind in kinds {
+            let (mut sim, sources) = gate_bench(kind, 4, 1);
+            for row in 0..16u64 {
+                let values: Vec<u64> = (0..4).map(|bit| (row >> bit) & 1).collect();
+                apply(&mut sim, &sources, &values);
+                assert_eq!(
+                    sim.get_component_state(1),
+                    expected(kind, row) as u64,
+                    "{kind:?} {row:04b}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn gates_work_bitwise_on_buses() {
+        let (mut sim, sources) = gate_bench(LogicGateKind::Nand, 2, 4);
+        apply(&mut sim, &sources, &[0b1100, 0b1010]);
+        assert_eq!(sim.get_component_state(1), 0b0111);
+        let (mut sim, sources) = gate_bench(LogicGateKind::Xor, 3, 4);
+        apply(&mut sim, &sources, &[0b1100, 0b1010, 0b0001]);
+        assert_eq!(sim.get_component_state(1), 0b0111);
+    }
+
+    // Switch 3 relié à la première entrée de la porte 1 par deux wires du même groupe qui
+    // se rejoignent sur la pin ; la seconde entrée n'est pas reliée
+    fn junction_on_pin(kind: LogicGateKind) -> Simulation {
+        let mut sim = Simulation::new();
+        sim.add_logic_gate(vec![0, 0], kind, 2, Orientation::Right, 1);
+        sim.add_switch(vec![-10, 1], 3);
+        sim.wires_map
+            .insert(10, Wire::new(10, vec![[-10, 1], [-5, 1]]));
+        sim.wires_map
+            .insert(11, Wire::new(11, vec![[-5, 1], [-5, 6]]));
+        sim.compute_connections();
+        sim.update_switch_state(3, true);
+        sim.compute_frame(100, 1);
+        sim
+    }
+
+    #[test]
+    fn a_pin_on_a_junction_counts_once() {
+        let sim = junction_on_pin(LogicGateKind::And);
+        let ComposantsEnum::AndGate(and_gate) = &sim.composants_map[&1] else {
+            panic!("AND gate expected");
+        };
+        assert_eq!(and_gate.gate.circuit_element.inputs.len(), 1);
+        assert!(!and_gate.gate.are_all_inputs_connected());
+        assert_eq!(sim.get_component_state(1), 0);
+
+        assert_eq!(
+            junction_on_pin(LogicGateKind::Xor).get_component_state(1),
+            1
+        );
+        assert_eq!(junction_on_pin(LogicGateKind::Or).get_component_state(1), 1);
+    }
+}
@@ -1,20 +1,14 @@
-use crate::components::TwoInputsGate;
+use crate::components::MultiInputsGate;
 use crate::Simulation;
 
+#[derive(Clone)]
 pub struct AndGate {
-    pub gate: TwoInputsGate,
+    pub gate: MultiInputsGate,
 }
 
 impl AndGate {
-    pub fn compute_next_state(&self, sim: &Simulation) -> (bool, bool) {
-        let true_count = self
-            .gate
-            .circuit_element
-            .inputs
-            .iter()
-            .filter(|input| input.get_state(sim))
-            .count();
-        let new_state = true_count >= 2;
+    pub fn compute_next_state(&self, sim: &Simulation) -> (u64, bool) {
+        let new_state = self.gate.and_inputs(sim);
         let is_different = new_state != self.gate.circuit_element.state;
         (new_state, is_different)
     }
@@ -0,0 +1,229 @@
+use crate::components::MultiPinGate;
+use crate::types::{ClockEdge, FlipFlopKind, Orientation, Position};
+
+// Bascule synchronisée sur un front d'horloge, avec preset et clear asynchrones
+// actifs à l'état haut. Entrées : données, CLK, PRE, CLR. Sorties : Q, !Q
+#[derive(Clone)]
+pub struct FlipFlop {
+    pub gate: MultiPinGate,
+    pub kind: FlipFlopKind,
+    pub edge: ClockEdge,
+    pub last_clock: bool,
+}
+
+impl FlipFlop {
+    pub fn new(
+        id: usize,
+        position: Position,
+        orientation: Orientation,
+        kind: FlipFlopKind,
+        edge: ClockEdge,
+    ) -> Self {
+        let data_pins = Self::data_pins_count(kind);
+        // Données puis horloge sur le côté gauche, PRE en haut et CLR en bas
+        let mut input_offsets = MultiPinGate::column_offsets(-5, data_pins + 1);
+        let height = data_pins as i32 + 2;
+        input_offsets.push([-2, height]);
+        input_offsets.push([-2, -height]);
+
+        let mut gate = MultiPinGate::new(
+            id,
+            position,
+            orientation,
+            &input_offsets,
+            &MultiPinGate::column_offsets(0, 2),
+        );
+        gate.set_outputs(vec![0, 1]);
+
+        FlipFlop {
+            gate,
+            kind,
+            edge,
+            last_clock: false,
+        }
+    }
+
+    pub fn data_pins_count(kind: FlipFlopKind) -> usize {
+        match kind {
+            FlipFlopKind::D | FlipFlopKind::T => 1,
+            FlipFlopKind::JK | FlipFlopKind::SR => 2,
+        }
+    }
+
+    pub fn clock_pin(&self) -> usize {
+        Self::data_pins_count(self.kind)
+    }
+
+    pub fn q(&self) -> bool {
+        self.gate.output_state(0) != 0
+    }
+
+    fn is_edge(&self, clock: bool) -> bool {
+        match self.edge {
+            ClockEdge::Rising => clock && !self.last_clock,
+            ClockEdge::Falling => !clock && self.last_clock,
+        }
+    }
+
+    fn next_q(&self, data: &[bool]) -> bool {
+        let q = self.q();
+        match self.kind {
+            FlipFlopKind::D => data[0],
+            FlipFlopKind::T => q ^ data[0],
+            FlipFlopKind::JK => match (data[0], data[1]) {
+                (false, false) => q,
+                (true, false) => true,
+                (false, true) => false,
+                (true, true) => !q,
+            },
+            // S = R = 1 est interdit : la bascule garde son état
+            FlipFlopKind::SR => match (data[0], data[1]) {
+                (true, false) => true,
+                (false, true) => false,
+                _ => q,
+            },
+        }
+    }
+
+    // Reçoit les entrées échantillonnées pendant le tick et renvoie si les sorties ont changé
+    pub fn update_inputs(&mut self, inputs: &[u64]) -> bool {
+        let level = |pin: usize| inputs.get(pin).is_some_and(|state| state & 1 != 0);
+        let clock_pin = self.clock_pin();
+        let clock = level(clock_pin);
+        let is_edge = self.is_edge(clock);
+        self.last_clock = clock;
+
+        let new_q = if level(clock_pin + 2) {
+            false
+        } else if level(clock_pin + 1) {
+            true
+        } else if is_edge {
+            let data: Vec<bool> = (0..clock_pin).map(level).collect();
+            self.next_q(&data)
+        } else {
+            self.q()
+        };
+
+        let new_outputs = vec![new_q as u64, !new_q as u64];
+        let is_different = self.gate.outputs_differ(&new_outputs);
+        self.gate.set_outputs(new_outputs);
+        is_different
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::simulation::bench::{apply, drive_inputs, outputs};
+    use crate::types::{ClockEdge, FlipFlopKind, Orientation};
+    use crate::Simulation;
+
+    // Bascule 1 dont chaque entrée (données, CLK, PRE, CLR) est pilotée par une source
+    fn bench(kind: FlipFlopKind, edge: ClockEdge) -> (Simulation, Vec<usize>) {
+        let mut sim = Simulation::new();
+        sim.add_flip_flop(vec![0, 0], kind, edge, Orientation::Right, 1);
+        let sources = drive_inputs(&mut sim, 1, &[]);
+        sim.compute_connections();
+        (sim, sources)
+    }
+
+    // Q après chaque jeu d'entrées, appliqués un par frame
+    fn q_sequence(sim: &mut Simulation, sources: &[usize], steps: &[&[u64]]) -> Vec<u64> {
+        steps
+            .iter()
+            .map(|values| {
+                apply(sim, sources, values);
+                let q = outputs(sim, 1);
+                assert_eq!(q[0] ^ q[1], 1);
+                q[0]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn d_flip_flop_samples_on_the_chosen_edge() {
+        // [D, CLK, PRE, CLR]
+        let steps: [&[u64]; 6] = [
+            &[1, 0, 0, 0],
+            &[1, 1, 0, 0],
+            &[0, 1, 0, 0],
+            &[0, 0, 0, 0],
+            &[1, 0, 0, 0],
+            &[1, 1, 0, 0],
+        ];
+        let (mut sim, sources) = bench(FlipFlopKind::D, ClockEdge::Rising);
+        assert_eq!(
+            q_sequence(&mut sim, &sources, &steps),
+            vec![0, 1, 1, 1, 1, 1]
+        );
+        let (mut sim, sources) = bench(FlipFlopKind::D, ClockEdge::Falling);
+        assert_eq!(
+            q_sequence(&mut sim, &sources, &steps),
+            vec![0, 0, 0, 0, 0, 0]
+        );
+        let steps: [&[u64]; 3] = [&[1, 1, 0, 0], &[1, 0, 0, 0], &[0, 1, 0, 0]];
+        assert_eq!(q_sequence(&mut sim, &sources, &steps), vec![0, 1, 1]);
+    }
+
+    #[test]
+    fn t_and_jk_flip_flops_toggle() {
+        let (mut sim, sources) = bench(FlipFlopKind::T, ClockEdge::Rising);
+        let steps: [&[u64]; 6] = [
+            &[1, 1, 0, 0],
+            &[1, 0, 0, 0],
+            &[1, 1, 0, 0],
+            &[0, 0, 0, 0],
+            &[0, 1, 0, 0],
+            &[1, 0, 0, 0],
+        ];
+        assert_eq!(
+            q_sequence(&mut sim, &sources, &steps),
+            vec![1, 1, 0, 0, 0, 0]
+        );
+
+        // [J, K, CLK, PRE, CLR] : set, maintien, bascule, reset
+        let (mut sim, sources) = bench(FlipFlopKind::JK, ClockEdge::Rising);
+        let steps: [&[u64]; 8] = [
+            &[1, 0, 1, 0, 0],
+            &[0, 0, 0, 0, 0],
+            &[0, 0, 1, 0, 0],
+            &[1, 1, 0, 0, 0],
+            &[1, 1, 1, 0, 0],
+            &[1, 1, 0, 0, 0],
+            &[1, 1, 1, 0, 0],
+            &[0, 1, 0, 0, 0],
+        ];
+        assert_eq!(
+            q_sequence(&mut sim, &sources, &steps),
+            vec![1, 1, 1, 1, 0, 0, 1, 1]
+        );
+        assert_eq!(q_sequence(&mut sim, &sources, &[&[0, 1, 1, 0, 0]]), vec![0]);
+    }
+
+    #[test]
+    fn sr_flip_flop_holds_on_the_forbidden_input() {
+        // [S, R, CLK, PRE, CLR]
+        let (mut sim, sources) = bench(FlipFlopKind::SR, ClockEdge::Rising);
+        let steps: [&[u64]; 4] = [
+            &[1, 0, 1, 0, 0],
+            &[1, 1, 0, 0, 0],
+            &[1, 1, 1, 0, 0],
+            &[0, 1, 0, 0, 0],
+        ];
+        assert_eq!(q_sequence(&mut sim, &sources, &steps), vec![1, 1, 1, 1]);
+        assert_eq!(q_sequence(&mut sim, &sources, &[&[0, 1, 1, 0, 0]]), vec![0]);
+    }
+
+    #[test]
+    fn preset_and_clear_ignore_the_clock() {
+        let (mut sim, sources) = bench(FlipFlopKind::D, ClockEdge::Rising);
+        let steps: [&[u64]; 5] = [
+            &[0, 0, 1, 0],
+            &[0, 0, 0, 0],
+            &[0, 0, 1, 1],
+            &[1, 1, 0, 1],
+            &[1, 0, 0, 0],
+        ];
+        // CLR l'emporte sur PRE et sur un front montant
+        assert_eq!(q_sequence(&mut sim, &sources, &steps), vec![1, 1, 0, 0, 0]);
+    }
+}
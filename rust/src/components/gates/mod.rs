@@ -1,17 +1,27 @@
 mod abstract_gates;
 mod and_gate;
 mod buffer_gate;
+mod flip_flop;
 mod latch_gate;
+mod nand_gate;
+mod nor_gate;
 mod not_gate;
 mod or_gate;
 mod timer_gate;
+mod xnor_gate;
 mod xor_gate;
 
-pub use abstract_gates::{OneInputGate, TwoInputsGate};
+pub use abstract_gates::{
+    oriented_position, MultiInputsGate, MultiPinGate, OneInputGate, TwoInputsGate,
+};
 pub use and_gate::AndGate;
 pub use buffer_gate::BufferGate;
+pub use flip_flop::FlipFlop;
 pub use latch_gate::LatchGate;
+pub use nand_gate::NandGate;
+pub use nor_gate::NorGate;
 pub use not_gate::NotGate;
 pub use or_gate::OrGate;
 pub use timer_gate::TimerGate;
+pub use xnor_gate::XnorGate;
 pub use xor_gate::XorGate;
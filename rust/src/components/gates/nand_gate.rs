@@ -0,0 +1,15 @@
+use crate::components::MultiInputsGate;
+use crate::Simulation;
+
+#[derive(Clone)]
+pub struct NandGate {
+    pub gate: MultiInputsGate,
+}
+
+impl NandGate {
+    pub fn compute_next_state(&self, sim: &Simulation) -> (u64, bool) {
+        let new_state = !self.gate.and_inputs(sim) & self.gate.circuit_element.mask();
+        let is_different = new_state != self.gate.circuit_element.state;
+        (new_state, is_different)
+    }
+}
@@ -1,18 +1,20 @@
 use crate::components::OneInputGate;
 use crate::Simulation;
 
+#[derive(Clone)]
 pub struct NotGate {
     pub gate: OneInputGate,
 }
 
 impl NotGate {
-    pub fn compute_next_state(&self, sim: &Simulation) -> (bool, bool) {
+    pub fn compute_next_state(&self, sim: &Simulation) -> (u64, bool) {
         let new_state = !self
             .gate
             .circuit_element
             .inputs
             .iter()
-            .any(|input| input.get_state(sim));
+            .fold(0, |acc, input| acc | input.get_state(sim))
+            & self.gate.circuit_element.mask();
         let is_different = new_state != self.gate.circuit_element.state;
         (new_state, is_different)
     }
@@ -1,18 +1,14 @@
-use crate::components::TwoInputsGate;
+use crate::components::MultiInputsGate;
 use crate::Simulation;
 
+#[derive(Clone)]
 pub struct OrGate {
-    pub gate: TwoInputsGate,
+    pub gate: MultiInputsGate,
 }
 
 impl OrGate {
-    pub fn compute_next_state(&self, sim: &Simulation) -> (bool, bool) {
-        let new_state = self
-            .gate
-            .circuit_element
-            .inputs
-            .iter()
-            .any(|input| input.get_state(sim));
+    pub fn compute_next_state(&self, sim: &Simulation) -> (u64, bool) {
+        let new_state = self.gate.or_inputs(sim);
         let is_different = new_state != self.gate.circuit_element.state;
         (new_state, is_different)
     }
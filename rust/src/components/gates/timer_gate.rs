@@ -1,12 +1,12 @@
 use crate::components::OneInputGate;
-use crate::utils::console_log;
 use crate::Simulation;
 
+#[derive(Clone)]
 pub struct TimerGate {
     pub gate: OneInputGate,
     pub ticks: u32,
-    pub input_state: bool,
-    pub stack: Vec<(u32, bool)>,
+    pub input_state: u64,
+    pub stack: Vec<(u32, u64)>,
 }
 
 impl TimerGate {
@@ -15,31 +15,32 @@ impl TimerGate {
             gate,
             ticks,
             stack: Vec::new(),
-            input_state: false,
+            input_state: 0,
         }
     }
 
-    pub fn compute_next_state(&self, sim: &Simulation) -> (bool, bool) {
+    pub fn compute_next_state(&self, sim: &Simulation) -> (u64, bool) {
         let new_state = self
             .gate
             .circuit_element
             .inputs
             .iter()
-            .any(|input| input.get_state(sim));
+            .fold(0, |acc, input| acc | input.get_state(sim))
+            & self.gate.circuit_element.mask();
         let is_different = new_state != self.gate.circuit_element.state;
         (new_state, is_different)
     }
 
-    pub fn update_input(&mut self, new_input_state: bool) {
+    pub fn update_input(&mut self, new_input_state: u64) {
         if self.input_state == new_input_state {
             return;
         }
         self.input_state = new_input_state;
-        self.stack.push((self.ticks.clone(), new_input_state));
+        self.stack.push((self.ticks, new_input_state));
     }
 
-    pub fn check_stack_and_update(&mut self) -> (bool, bool) {
-        let zero_elements: Vec<(u32, bool)> = self
+    pub fn check_stack_and_update(&mut self) -> (u64, bool) {
+        let zero_elements: Vec<(u32, u64)> = self
             .stack
             .iter()
             .filter(|(ticks, _)| *ticks == 0)
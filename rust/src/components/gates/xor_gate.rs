@@ -1,20 +1,14 @@
-use crate::components::TwoInputsGate;
+use crate::components::MultiInputsGate;
 use crate::Simulation;
 
+#[derive(Clone)]
 pub struct XorGate {
-    pub gate: TwoInputsGate,
+    pub gate: MultiInputsGate,
 }
 
 impl XorGate {
-    pub fn compute_next_state(&self, sim: &Simulation) -> (bool, bool) {
-        let new_state = self
-            .gate
-            .circuit_element
-            .inputs
-            .iter()
-            .map(|input| input.get_state(sim))
-            .fold(false, |acc, state| acc ^ state);
-
+    pub fn compute_next_state(&self, sim: &Simulation) -> (u64, bool) {
+        let new_state = self.gate.xor_inputs(sim);
         let is_different = new_state != self.gate.circuit_element.state;
         (new_state, is_different)
     }
@@ -0,0 +1,72 @@
+use std::collections::VecDeque;
+
+use crate::components::MultiPinGate;
+use crate::types::{Orientation, Position};
+
+pub const MAX_KEYBOARD_BUFFER: usize = 1024;
+
+// Clavier : file de caractères remplie par l'hôte. Entrées READ puis CLR,
+// sorties DATA (bus 8 bits, premier caractère de la file ou 0) puis AVAIL.
+// Un front montant sur READ retire le premier caractère
+#[derive(Clone)]
+pub struct Keyboard {
+    pub gate: MultiPinGate,
+    pub capacity: usize,
+    pub buffer: VecDeque<u8>,
+    pub last_read: bool,
+}
+
+impl Keyboard {
+    pub fn new(id: usize, position: Position, orientation: Orientation, capacity: usize) -> Self {
+        let mut gate = MultiPinGate::new(
+            id,
+            position,
+            orientation,
+            &MultiPinGate::column_offsets(-5, 2),
+            &MultiPinGate::column_offsets(0, 2),
+        );
+        gate.output_widths = vec![8, 1];
+        Keyboard {
+            gate,
+            capacity: capacity.clamp(1, MAX_KEYBOARD_BUFFER),
+            buffer: VecDeque::new(),
+            last_read: false,
+        }
+    }
+
+    // Renvoie false si la file est pleine
+    pub fn push(&mut self, key: u8) -> bool {
+        if self.buffer.len() >= self.capacity {
+            return false;
+        }
+        self.buffer.push_back(key);
+        self.refresh_outputs();
+        true
+    }
+
+    pub fn outputs(&self) -> Vec<u64> {
+        match self.buffer.front() {
+            Some(key) => vec![*key as u64, 1],
+            None => vec![0, 0],
+        }
+    }
+
+    fn refresh_outputs(&mut self) -> bool {
+        let new_outputs = self.outputs();
+        let is_different = self.gate.outputs_differ(&new_outputs);
+        self.gate.set_outputs(new_outputs);
+        is_different
+    }
+
+    // Reçoit les entrées échantillonnées pendant le tick et renvoie si les sorties ont changé
+    pub fn update_inputs(&mut self, inputs: &[u64]) -> bool {
+        let read = inputs[0] & 1 != 0;
+        if inputs[1] & 1 != 0 {
+            self.buffer.clear();
+        } else if read && !self.last_read {
+            self.buffer.pop_front();
+        }
+        self.last_read = read;
+        self.refresh_outputs()
+    }
+}
@@ -0,0 +1,164 @@
+// Décode un fichier Intel HEX en blocs `(adresse en octets, octets)`
+pub fn parse_intel_hex(text: &str) -> Result<Vec<(usize, Vec<u8>)>, String> {
+    let mut blocks = Vec::new();
+    let mut base_address: usize = 0;
+
+    for (line_index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let Some(record) = line.strip_prefix(':') else {
+            return Err(format!("Line {}: missing ':'", line_index + 1));
+        };
+        if record.len() % 2 != 0 || record.len() < 10 {
+            return Err(format!("Line {}: malformed record", line_index + 1));
+        }
+        // Lu octet par octet : un caractère non ASCII ne tombe pas sur une frontière
+        let bytes = record
+            .as_bytes()
+            .chunks(2)
+            .map(|pair| {
+                let high = (pair[0] as char).to_digit(16)?;
+                let low = (pair[1] as char).to_digit(16)?;
+                Some((high << 4 | low) as u8)
+            })
+            .collect::<Option<Vec<u8>>>()
+            .ok_or_else(|| format!("Line {}: invalid hex digit", line_index + 1))?;
+
+        let length = bytes[0] as usize;
+        if bytes.len() != length + 5 {
+            return Err(format!("Line {}: wrong byte count", line_index + 1));
+        }
+        let checksum = bytes.iter().fold(0u8, |acc, byte| acc.wrapping_add(*byte));
+        if checksum != 0 {
+            return Err(format!("Line {}: bad checksum", line_index + 1));
+        }
+
+        let offset = ((bytes[1] as usize) << 8) | bytes[2] as usize;
+        let data = &bytes[4..4 + length];
+        match bytes[3] {
+            0x00 => blocks.push((base_address + offset, data.to_vec())),
+            0x01 => break,
+            0x02 if length == 2 => {
+                base_address = (((data[0] as usize) << 8) | data[1] as usize) << 4;
+            }
+            0x04 if length == 2 => {
+                base_address = (((data[0] as usize) << 8) | data[1] as usize) << 16;
+            }
+            // Adresses de démarrage : sans effet sur le contenu
+            0x03 | 0x05 => (),
+            record_type => {
+                return Err(format!(
+                    "Line {}: unsupported record type {:02X}",
+                    line_index + 1,
+                    record_type
+                ))
+            }
+        }
+    }
+
+    Ok(blocks)
+}
+
+// Décode un fichier Logisim `v2.0 raw` : mots hexadécimaux, `N*valeur` pour les répétitions.
+// Refuse une image de plus de `max_words` mots, la taille de la mémoire à charger
+pub fn parse_logisim_raw(text: &str, max_words: usize) -> Result<Vec<u64>, String> {
+    let mut lines = text.lines();
+    match lines.next() {
+        Some(header) if header.trim() == "v2.0 raw" => (),
+        _ => return Err("Missing 'v2.0 raw' header".to_string()),
+    }
+
+    let mut words = Vec::new();
+    for line in lines {
+        let content = line.split('#').next().unwrap_or("");
+        for token in content.split_whitespace() {
+            let (count, value) = match token.split_once('*') {
+                Some((count, value)) => (
+                    count
+                        .parse::<usize>()
+                        .map_err(|_| format!("Invalid repeat count '{}'", token))?,
+                    value,
+                ),
+                None => (1, token),
+            };
+            let value =
+                u64::from_str_radix(value, 16).map_err(|_| format!("Invalid value '{}'", token))?;
+            if count > max_words - words.len() {
+                return Err(format!("Image larger than {} words", max_words));
+            }
+            words.extend(std::iter::repeat_n(value, count));
+        }
+    }
+
+    Ok(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_intel_hex, parse_logisim_raw};
+
+    #[test]
+    fn logisim_raw_expands_repeats_and_skips_comments() {
+        let text = "v2.0 raw\n# en-tête\n1 ff 3*a # fin de ligne\n\n2*0 7\n";
+        assert_eq!(
+            parse_logisim_raw(text, 16),
+            Ok(vec![0x1, 0xff, 0xa, 0xa, 0xa, 0, 0, 0x7])
+        );
+        assert!(parse_logisim_raw("1 2 3\n", 16).is_err());
+        assert!(parse_logisim_raw("v2.0 raw\n2*x\n", 16).is_err());
+        assert!(parse_logisim_raw("v2.0 raw\n-1*0\n", 16).is_err());
+    }
+
+    #[test]
+    fn logisim_raw_is_capped_at_the_memory_size() {
+        assert_eq!(
+            parse_logisim_raw("v2.0 raw\n4*1\n", 4).map(|w| w.len()),
+            Ok(4)
+        );
+        assert!(parse_logisim_raw("v2.0 raw\n4294967295*0\n", 1 << 20).is_err());
+        assert!(parse_logisim_raw("v2.0 raw\n3*1 2*2\n", 4).is_err());
+    }
+
+    #[test]
+    fn intel_hex_data_and_extended_addresses() {
+        let text = ":0300300002337A1E\n\
+                    :020000040001F9\n\
+                    :02000200ABCD84\n\
+                    :020000021000EC\n\
+                    :0100050042B8\n\
+                    :00000001FF\n\
+                    :0100000011EE\n";
+        assert_eq!(
+            parse_intel_hex(text),
+            Ok(vec![
+                (0x30, vec![0x02, 0x33, 0x7A]),
+                (0x1_0002, vec![0xAB, 0xCD]),
+                (0x1_0005, vec![0x42]),
+            ])
+        );
+    }
+
+    #[test]
+    fn intel_hex_rejects_bad_records() {
+        assert_eq!(
+            parse_intel_hex(":0300300002337A1F\n"),
+            Err("Line 1: bad checksum".to_string())
+        );
+        assert_eq!(
+            parse_intel_hex("0300300002337A1E\n"),
+            Err("Line 1: missing ':'".to_string())
+        );
+        assert_eq!(
+            parse_intel_hex(":0400300002337A1E\n"),
+            Err("Line 1: wrong byte count".to_string())
+        );
+        // Caractères multi-octets : refusés sans couper une chaîne au milieu d'un caractère
+        assert_eq!(
+            parse_intel_hex(":030030é002337A1\n"),
+            Err("Line 1: invalid hex digit".to_string())
+        );
+        assert!(parse_intel_hex(":00000006FA\n").is_err());
+    }
+}
@@ -0,0 +1,111 @@
+mod formats;
+mod ram;
+mod rom;
+
+pub use formats::{parse_intel_hex, parse_logisim_raw};
+pub use ram::Ram;
+pub use rom::Rom;
+
+use crate::utils::width_mask;
+
+pub const MAX_ADDRESS_BITS: u8 = 20;
+
+// Contenu d'une mémoire de `2^address_bits` mots de `data_bits` bits. Avec
+// `bus_pins`, adresse et données passent chacune par une seule pin de bus,
+// sinon par une pin par bit (bit 0 en haut)
+#[derive(Clone)]
+pub struct MemoryBank {
+    pub address_bits: u8,
+    pub data_bits: u8,
+    pub bus_pins: bool,
+    pub words: Vec<u64>,
+}
+
+impl MemoryBank {
+    pub fn new(address_bits: u8, data_bits: u8, bus_pins: bool) -> Self {
+        let address_bits = address_bits.clamp(1, MAX_ADDRESS_BITS);
+        MemoryBank {
+            address_bits,
+            data_bits,
+            bus_pins,
+            words: vec![0; 1 << address_bits],
+        }
+    }
+
+    pub fn address_pins(&self) -> usize {
+        if self.bus_pins {
+            1
+        } else {
+            self.address_bits as usize
+        }
+    }
+
+    pub fn data_pins(&self) -> usize {
+        if self.bus_pins {
+            1
+        } else {
+            self.data_bits as usize
+        }
+    }
+
+    pub fn data_widths(&self) -> Vec<u8> {
+        if self.bus_pins {
+            vec![self.data_bits]
+        } else {
+            vec![1; self.data_bits as usize]
+        }
+    }
+
+    fn value_from_pins(&self, inputs: &[u64], start: usize, bits: u8) -> u64 {
+        if self.bus_pins {
+            return inputs.get(start).copied().unwrap_or(0) & width_mask(bits);
+        }
+        (0..bits as usize).fold(0, |acc, bit| {
+            acc | ((inputs.get(start + bit).copied().unwrap_or(0) & 1) << bit)
+        })
+    }
+
+    pub fn address_from_pins(&self, inputs: &[u64], start: usize) -> usize {
+        self.value_from_pins(inputs, start, self.address_bits) as usize
+    }
+
+    pub fn data_from_pins(&self, inputs: &[u64], start: usize) -> u64 {
+        self.value_from_pins(inputs, start, self.data_bits)
+    }
+
+    pub fn data_outputs(&self, address: usize) -> Vec<u64> {
+        let word = self.words.get(address).copied().unwrap_or(0);
+        if self.bus_pins {
+            vec![word]
+        } else {
+            (0..self.data_bits).map(|bit| (word >> bit) & 1).collect()
+        }
+    }
+
+    pub fn read(&self, address: usize, len: usize) -> Vec<u64> {
+        let start = address.min(self.words.len());
+        let end = address.saturating_add(len).min(self.words.len());
+        self.words[start..end].to_vec()
+    }
+
+    pub fn write(&mut self, address: usize, values: &[u64]) {
+        let mask = width_mask(self.data_bits);
+        for (word, value) in self.words.iter_mut().skip(address).zip(values) {
+            *word = value & mask;
+        }
+    }
+
+    // Chaque mot occupe `ceil(data_bits / 8)` octets, poids faible en premier
+    pub fn load_bytes(&mut self, address: usize, bytes: &[u8]) {
+        let bytes_per_word = self.data_bits.div_ceil(8) as usize;
+        let values: Vec<u64> = bytes
+            .chunks(bytes_per_word)
+            .map(|chunk| {
+                chunk.iter().enumerate().fold(0, |acc, (index, byte)| {
+                    acc | ((*byte as u64) << (8 * index))
+                })
+            })
+            .collect();
+        self.write(address, &values);
+    }
+}
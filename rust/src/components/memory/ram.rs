@@ -0,0 +1,63 @@
+use crate::components::memory::MemoryBank;
+use crate::components::MultiPinGate;
+use crate::types::{Orientation, Position};
+
+// Mémoire vive : entrées adresse, données, write enable puis horloge. La lecture
+// est asynchrone, l'écriture a lieu sur le front montant quand WE est haut
+#[derive(Clone)]
+pub struct Ram {
+    pub gate: MultiPinGate,
+    pub memory: MemoryBank,
+    pub last_clock: bool,
+}
+
+impl Ram {
+    pub fn new(
+        id: usize,
+        position: Position,
+        orientation: Orientation,
+        memory: MemoryBank,
+    ) -> Self {
+        let inputs = memory.address_pins() + memory.data_pins() + 2;
+        let mut gate = MultiPinGate::new(
+            id,
+            position,
+            orientation,
+            &MultiPinGate::column_offsets(-5, inputs),
+            &MultiPinGate::column_offsets(0, memory.data_pins()),
+        );
+        gate.output_widths = memory.data_widths();
+        Ram {
+            gate,
+            memory,
+            last_clock: false,
+        }
+    }
+
+    pub fn write_enable_pin(&self) -> usize {
+        self.memory.address_pins() + self.memory.data_pins()
+    }
+
+    pub fn clock_pin(&self) -> usize {
+        self.write_enable_pin() + 1
+    }
+
+    // Reçoit les entrées échantillonnées pendant le tick et renvoie si les sorties ont changé
+    pub fn update_inputs(&mut self, inputs: &[u64]) -> bool {
+        let level = |pin: usize| inputs.get(pin).is_some_and(|state| state & 1 != 0);
+        let address = self.memory.address_from_pins(inputs, 0);
+        let clock = level(self.clock_pin());
+        if clock && !self.last_clock && level(self.write_enable_pin()) {
+            let data = self
+                .memory
+                .data_from_pins(inputs, self.memory.address_pins());
+            self.memory.write(address, &[data]);
+        }
+        self.last_clock = clock;
+
+        let new_outputs = self.memory.data_outputs(address);
+        let is_different = self.gate.outputs_differ(&new_outputs);
+        self.gate.set_outputs(new_outputs);
+        is_different
+    }
+}
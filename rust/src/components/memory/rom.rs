@@ -0,0 +1,39 @@
+use crate::components::memory::MemoryBank;
+use crate::components::MultiPinGate;
+use crate::types::{Orientation, Position};
+use crate::Simulation;
+
+// Mémoire en lecture seule : entrées d'adresse, sorties de données
+#[derive(Clone)]
+pub struct Rom {
+    pub gate: MultiPinGate,
+    pub memory: MemoryBank,
+}
+
+impl Rom {
+    pub fn new(
+        id: usize,
+        position: Position,
+        orientation: Orientation,
+        memory: MemoryBank,
+    ) -> Self {
+        let mut gate = MultiPinGate::new(
+            id,
+            position,
+            orientation,
+            &MultiPinGate::column_offsets(-5, memory.address_pins()),
+            &MultiPinGate::column_offsets(0, memory.data_pins()),
+        );
+        gate.output_widths = memory.data_widths();
+        Rom { gate, memory }
+    }
+
+    pub fn compute_next_state(&self, sim: &Simulation) -> (Vec<u64>, bool) {
+        let address = self
+            .memory
+            .address_from_pins(&self.gate.read_inputs(sim), 0);
+        let new_outputs = self.memory.data_outputs(address);
+        let is_different = self.gate.outputs_differ(&new_outputs);
+        (new_outputs, is_different)
+    }
+}
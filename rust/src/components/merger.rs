@@ -0,0 +1,34 @@
+use crate::components::MultiPinGate;
+use crate::types::{Orientation, Position};
+use crate::Simulation;
+
+// Regroupe `bits` entrées d'un bit en un bus, bit 0 en haut
+#[derive(Clone)]
+pub struct Merger {
+    pub gate: MultiPinGate,
+    pub bits: u8,
+}
+
+impl Merger {
+    pub fn new(id: usize, position: Position, orientation: Orientation, bits: u8) -> Self {
+        let mut gate = MultiPinGate::new(
+            id,
+            position,
+            orientation,
+            &MultiPinGate::column_offsets(-5, bits as usize),
+            &[[0, 0]],
+        );
+        gate.circuit_element.width = bits;
+        gate.output_widths[0] = bits;
+        Merger { gate, bits }
+    }
+
+    pub fn compute_next_state(&self, sim: &Simulation) -> (Vec<u64>, bool) {
+        let bus = (0..self.bits as usize).fold(0u64, |acc, bit| {
+            acc | ((self.gate.input_state(sim, bit) & 1) << bit)
+        });
+        let new_outputs = vec![bus];
+        let is_different = self.gate.outputs_differ(&new_outputs);
+        (new_outputs, is_different)
+    }
+}
@@ -1,8 +1,56 @@
+mod adder;
+mod alu;
+mod button;
+mod clock;
+mod comparator;
+mod constant;
+mod counter;
+mod decoder;
+mod demultiplexer;
+mod dip_switch;
+mod display;
 mod gates;
+mod keyboard;
+mod memory;
+mod merger;
+mod multiplexer;
+mod port;
+mod priority_encoder;
+mod pull;
+mod register;
+mod shift_register;
+mod splitter;
+mod sub_circuit;
 mod switch;
+mod terminal;
+mod tunnel;
 
+pub use adder::Adder;
+pub use alu::Alu;
+pub use button::Button;
+pub use clock::Clock;
+pub use comparator::Comparator;
+pub use constant::Constant;
+pub use counter::Counter;
+pub use decoder::Decoder;
+pub use demultiplexer::Demultiplexer;
+pub use dip_switch::DipSwitch;
+pub use display::{Led, PixelMatrix, SevenSegment};
 pub use gates::{
-    AndGate, BufferGate, LatchGate, NotGate, OneInputGate, OrGate, TimerGate, TwoInputsGate,
-    XorGate,
+    oriented_position, AndGate, BufferGate, FlipFlop, LatchGate, MultiInputsGate, MultiPinGate,
+    NandGate, NorGate, NotGate, OneInputGate, OrGate, TimerGate, TwoInputsGate, XnorGate, XorGate,
 };
+pub use keyboard::Keyboard;
+pub use memory::{parse_intel_hex, parse_logisim_raw, MemoryBank, Ram, Rom};
+pub use merger::Merger;
+pub use multiplexer::Multiplexer;
+pub use port::{InputPort, OutputPort, Port};
+pub use priority_encoder::PriorityEncoder;
+pub use pull::Pull;
+pub use register::Register;
+pub use shift_register::ShiftRegister;
+pub use splitter::Splitter;
+pub use sub_circuit::SubCircuit;
 pub use switch::Switch;
+pub use terminal::Terminal;
+pub use tunnel::Tunnel;
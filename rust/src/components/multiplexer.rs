@@ -0,0 +1,75 @@
+use crate::components::MultiPinGate;
+use crate::types::{Orientation, Position};
+use crate::Simulation;
+
+pub const MAX_SELECT_BITS: u8 = 5;
+
+// Multiplexeur `2^select_bits` voies : entrées de données à gauche, pins de
+// sélection (bit 0 côté sortie) en bas, sortie unique de la largeur des données
+#[derive(Clone)]
+pub struct Multiplexer {
+    pub gate: MultiPinGate,
+    pub select_bits: u8,
+}
+
+impl Multiplexer {
+    pub fn new(id: usize, position: Position, orientation: Orientation, select_bits: u8) -> Self {
+        let select_bits = select_bits.clamp(1, MAX_SELECT_BITS);
+        let ways = 1usize << select_bits;
+        let mut input_offsets = MultiPinGate::column_offsets(-5, ways);
+        input_offsets.extend(MultiPinGate::row_offsets(
+            -(ways as i32) - 1,
+            select_bits as usize,
+        ));
+        Multiplexer {
+            gate: MultiPinGate::new(id, position, orientation, &input_offsets, &[[0, 0]]),
+            select_bits,
+        }
+    }
+
+    pub fn compute_next_state(&self, sim: &Simulation) -> (Vec<u64>, bool) {
+        let inputs = self.gate.read_inputs(sim);
+        let ways = 1usize << self.select_bits;
+        let select = MultiPinGate::bits_value(&inputs, ways, self.select_bits as usize) as usize;
+        let new_outputs = vec![inputs.get(select).copied().unwrap_or(0)];
+        let is_different = self.gate.outputs_differ(&new_outputs);
+        (new_outputs, is_different)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::simulation::bench::{apply, drive_inputs, outputs};
+    use crate::types::Orientation;
+    use crate::wire::Wire;
+    use crate::{ComposantsEnum, Simulation};
+
+    #[test]
+    fn multiplexer_routes_the_selected_bus_at_full_width() {
+        let mut sim = Simulation::new();
+        sim.add_multiplexer(vec![0, 0], 2, Orientation::Right, 1);
+        // [D0, D1, D2, D3, S0, S1], données sur 8 bits
+        let sources = drive_inputs(&mut sim, 1, &[8, 8, 8, 8]);
+        sim.wires_map
+            .insert(50, Wire::new_bus(50, vec![[0, 0], [5, 0]], 8));
+        sim.compute_connections();
+        let ComposantsEnum::Multiplexer(multiplexer) = &sim.composants_map[&1] else {
+            panic!("multiplexer expected");
+        };
+        assert_eq!(multiplexer.gate.output_widths, vec![8]);
+
+        let data = [0x11, 0xa5, 0xff, 0x80];
+        let output_group = sim
+            .wire_groups
+            .iter()
+            .position(|group| group.wires.contains(&50))
+            .unwrap();
+        for select in 0..4u64 {
+            let mut values = data.to_vec();
+            values.extend([select & 1, select >> 1]);
+            apply(&mut sim, &sources, &values);
+            assert_eq!(outputs(&sim, 1), vec![data[select as usize]]);
+            assert_eq!(sim.wire_group_state(output_group), data[select as usize]);
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use crate::circuit_element::CircuitElement;
+use crate::types::Position;
+use crate::Simulation;
+
+// Port nommé d'une définition de sous-circuit ; sa pin est à `position`
+#[derive(Clone)]
+pub struct Port {
+    pub circuit_element: CircuitElement,
+    pub position: Position,
+    pub label: String,
+}
+
+impl Port {
+    pub fn new(id: usize, position: Position, label: String) -> Self {
+        Port {
+            circuit_element: CircuitElement::new(id),
+            position,
+            label,
+        }
+    }
+
+    pub fn compute_next_state(&self, sim: &Simulation) -> (u64, bool) {
+        let new_state = self
+            .circuit_element
+            .inputs
+            .iter()
+            .fold(0, |acc, input| acc | input.get_state(sim))
+            & self.circuit_element.mask();
+        let is_different = new_state != self.circuit_element.state;
+        (new_state, is_different)
+    }
+}
+
+// Pilote le wire group à sa position avec la valeur reçue sur la pin de l'instance
+#[derive(Clone)]
+pub struct InputPort {
+    pub port: Port,
+}
+
+// Lit le wire group à sa position et le renvoie sur la pin de l'instance
+#[derive(Clone)]
+pub struct OutputPort {
+    pub port: Port,
+}
@@ -0,0 +1,66 @@
+use crate::components::multiplexer::MAX_SELECT_BITS;
+use crate::components::MultiPinGate;
+use crate::types::{Orientation, Position};
+use crate::Simulation;
+
+// Encodeur de priorité : `2^select_bits` entrées, l'entrée haute de plus grand
+// indice l'emporte. Sorties : `select_bits` bits d'index puis V (une entrée est haute)
+#[derive(Clone)]
+pub struct PriorityEncoder {
+    pub gate: MultiPinGate,
+    pub select_bits: u8,
+}
+
+impl PriorityEncoder {
+    pub fn new(id: usize, position: Position, orientation: Orientation, select_bits: u8) -> Self {
+        let select_bits = select_bits.clamp(1, MAX_SELECT_BITS);
+        PriorityEncoder {
+            gate: MultiPinGate::new(
+                id,
+                position,
+                orientation,
+                &MultiPinGate::column_offsets(-5, 1 << select_bits),
+                &MultiPinGate::column_offsets(0, select_bits as usize + 1),
+            ),
+            select_bits,
+        }
+    }
+
+    pub fn compute_next_state(&self, sim: &Simulation) -> (Vec<u64>, bool) {
+        let inputs = self.gate.read_inputs(sim);
+        let highest = inputs.iter().rposition(|input| input & 1 != 0);
+        let index = highest.unwrap_or(0) as u64;
+        let mut new_outputs: Vec<u64> = (0..self.select_bits)
+            .map(|bit| (index >> bit) & 1)
+            .collect();
+        new_outputs.push(highest.is_some() as u64);
+        let is_different = self.gate.outputs_differ(&new_outputs);
+        (new_outputs, is_different)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::simulation::bench::{apply, drive_inputs, outputs};
+    use crate::types::Orientation;
+    use crate::Simulation;
+
+    #[test]
+    fn highest_raised_input_wins() {
+        let mut sim = Simulation::new();
+        sim.add_priority_encoder(vec![0, 0], 2, Orientation::Right, 1);
+        let sources = drive_inputs(&mut sim, 1, &[]);
+        sim.compute_connections();
+        // Sorties : [bit 0, bit 1, V]
+        for (inputs, expected) in [
+            ([0, 0, 0, 0], [0, 0, 0]),
+            ([1, 0, 0, 0], [0, 0, 1]),
+            ([1, 1, 0, 0], [1, 0, 1]),
+            ([0, 1, 1, 0], [0, 1, 1]),
+            ([1, 0, 1, 1], [1, 1, 1]),
+        ] {
+            apply(&mut sim, &sources, &inputs);
+            assert_eq!(outputs(&sim, 1), expected);
+        }
+    }
+}
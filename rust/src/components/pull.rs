@@ -0,0 +1,21 @@
+use crate::circuit_element::CircuitElement;
+use crate::types::{Position, PullKind};
+
+// Résistance de tirage : donne sa valeur par défaut au wire group sur lequel elle
+// est posée quand aucun composant ne le pilote. Elle n'est pas elle-même un pilote
+#[derive(Clone)]
+pub struct Pull {
+    pub circuit_element: CircuitElement,
+    pub position: Position,
+    pub kind: PullKind,
+}
+
+impl Pull {
+    pub fn new(id: usize, position: Position, kind: PullKind) -> Self {
+        Pull {
+            circuit_element: CircuitElement::new(id),
+            position,
+            kind,
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use crate::components::MultiPinGate;
+use crate::types::{Orientation, Position};
+use crate::utils::width_mask;
+
+// Registre de `bits` bits. Entrées : D (bus), EN, CLK, CLR. Sortie : Q (bus).
+// EN non connecté vaut actif, CLR est asynchrone
+#[derive(Clone)]
+pub struct Register {
+    pub gate: MultiPinGate,
+    pub bits: u8,
+    pub value: u64,
+    pub last_clock: bool,
+}
+
+impl Register {
+    pub const DATA_PIN: usize = 0;
+    pub const ENABLE_PIN: usize = 1;
+    pub const CLOCK_PIN: usize = 2;
+    pub const CLEAR_PIN: usize = 3;
+
+    pub fn new(id: usize, position: Position, orientation: Orientation, bits: u8) -> Self {
+        let mut gate = MultiPinGate::new(
+            id,
+            position,
+            orientation,
+            &MultiPinGate::column_offsets(-5, 4),
+            &[[0, 0]],
+        );
+        gate.output_widths = vec![bits];
+        Register {
+            gate,
+            bits,
+            value: 0,
+            last_clock: false,
+        }
+    }
+
+    pub fn set_value(&mut self, value: u64) {
+        self.value = value & width_mask(self.bits);
+    }
+
+    // Reçoit les entrées échantillonnées pendant le tick et renvoie si les sorties ont changé
+    pub fn update_inputs(&mut self, inputs: &[u64]) -> bool {
+        let level = |pin: usize| inputs.get(pin).is_some_and(|state| state & 1 != 0);
+        let clock = level(Self::CLOCK_PIN);
+        let is_enabled = !self.gate.is_input_connected(Self::ENABLE_PIN) || level(Self::ENABLE_PIN);
+        if level(Self::CLEAR_PIN) {
+            self.value = 0;
+        } else if clock && !self.last_clock && is_enabled {
+            self.set_value(inputs.get(Self::DATA_PIN).copied().unwrap_or(0));
+        }
+        self.last_clock = clock;
+
+        let new_outputs = vec![self.value];
+        let is_different = self.gate.outputs_differ(&new_outputs);
+        self.gate.set_outputs(new_outputs);
+        is_different
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::simulation::bench::{apply, drive_inputs, outputs};
+    use crate::types::Orientation;
+    use crate::Simulation;
+
+    #[test]
+    fn register_loads_on_enabled_edges_and_clears_asynchronously() {
+        let mut sim = Simulation::new();
+        sim.add_register(vec![0, 0], 8, Orientation::Right, 1);
+        // [D, EN, CLK, CLR]
+        let sources = drive_inputs(&mut sim, 1, &[8]);
+        sim.compute_connections();
+
+        apply(&mut sim, &sources, &[0x5a, 1, 0, 0]);
+        assert_eq!(outputs(&sim, 1), vec![0]);
+        apply(&mut sim, &sources, &[0x5a, 1, 1, 0]);
+        assert_eq!(outputs(&sim, 1), vec![0x5a]);
+        apply(&mut sim, &sources, &[0x33, 0, 0, 0]);
+        apply(&mut sim, &sources, &[0x33, 0, 1, 0]);
+        assert_eq!(sim.get_register_value(1), Some(0x5a));
+        apply(&mut sim, &sources, &[0x33, 1, 1, 1]);
+        assert_eq!(outputs(&sim, 1), vec![0]);
+
+        apply(&mut sim, &sources, &[0, 0, 0, 0]);
+        sim.set_register_value(1, 0x1ff);
+        apply(&mut sim, &sources, &[0, 0, 0, 0]);
+        assert_eq!(outputs(&sim, 1), vec![0xff]);
+    }
+}
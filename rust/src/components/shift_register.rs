@@ -0,0 +1,157 @@
+use crate::components::MultiPinGate;
+use crate::types::{Orientation, Position, ShiftRegisterMode};
+use crate::utils::width_mask;
+
+// Registre à décalage de `bits` bits, décalé vers le poids fort à chaque front
+// montant ; la sortie série est le bit de poids fort. Entrées selon le mode :
+// SI, [LOAD, D (bus),] CLK, CLR. Sorties : SO [, Q (bus) en SIPO]
+#[derive(Clone)]
+pub struct ShiftRegister {
+    pub gate: MultiPinGate,
+    pub bits: u8,
+    pub mode: ShiftRegisterMode,
+    pub value: u64,
+    pub last_clock: bool,
+}
+
+impl ShiftRegister {
+    pub const SERIAL_PIN: usize = 0;
+
+    pub fn new(
+        id: usize,
+        position: Position,
+        orientation: Orientation,
+        bits: u8,
+        mode: ShiftRegisterMode,
+    ) -> Self {
+        let (inputs, output_widths) = match mode {
+            ShiftRegisterMode::Siso => (3, vec![1]),
+            ShiftRegisterMode::Sipo => (3, vec![1, bits]),
+            ShiftRegisterMode::Piso => (5, vec![1]),
+        };
+        let mut gate = MultiPinGate::new(
+            id,
+            position,
+            orientation,
+            &MultiPinGate::column_offsets(-5, inputs),
+            &MultiPinGate::column_offsets(0, output_widths.len()),
+        );
+        gate.output_widths = output_widths;
+        ShiftRegister {
+            gate,
+            bits,
+            mode,
+            value: 0,
+            last_clock: false,
+        }
+    }
+
+    fn has_parallel_load(&self) -> bool {
+        self.mode == ShiftRegisterMode::Piso
+    }
+
+    pub fn clock_pin(&self) -> usize {
+        if self.has_parallel_load() {
+            3
+        } else {
+            1
+        }
+    }
+
+    pub fn set_value(&mut self, value: u64) {
+        self.value = value & width_mask(self.bits);
+    }
+
+    fn serial_output(&self) -> u64 {
+        (self.value >> (self.bits - 1)) & 1
+    }
+
+    // Reçoit les entrées échantillonnées pendant le tick et renvoie si les sorties ont changé
+    pub fn update_inputs(&mut self, inputs: &[u64]) -> bool {
+        let level = |pin: usize| inputs.get(pin).is_some_and(|state| state & 1 != 0);
+        let clock_pin = self.clock_pin();
+        let clock = level(clock_pin);
+        if level(clock_pin + 1) {
+            self.value = 0;
+        } else if clock && !self.last_clock {
+            if self.has_parallel_load() && level(1) {
+                self.set_value(inputs.get(2).copied().unwrap_or(0));
+            } else {
+                let serial_input = level(Self::SERIAL_PIN) as u64;
+                self.set_value((self.value << 1) | serial_input);
+            }
+        }
+        self.last_clock = clock;
+
+        let mut new_outputs = vec![self.serial_output()];
+        if self.mode == ShiftRegisterMode::Sipo {
+            new_outputs.push(self.value);
+        }
+        let is_different = self.gate.outputs_differ(&new_outputs);
+        self.gate.set_outputs(new_outputs);
+        is_different
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::simulation::bench::{apply, drive_inputs, outputs};
+    use crate::types::{Orientation, ShiftRegisterMode};
+    use crate::Simulation;
+
+    #[test]
+    fn sipo_shifts_serial_bits_towards_the_high_bit() {
+        let mut sim = Simulation::new();
+        sim.add_shift_register(
+            vec![0, 0],
+            4,
+            ShiftRegisterMode::Sipo,
+            Orientation::Right,
+            1,
+        );
+        // [SI, CLK, CLR]
+        let sources = drive_inputs(&mut sim, 1, &[]);
+        sim.compute_connections();
+        let mut seen = Vec::new();
+        for bit in [1, 0, 1, 1, 0] {
+            apply(&mut sim, &sources, &[bit, 0, 0]);
+            apply(&mut sim, &sources, &[bit, 1, 0]);
+            seen.push(outputs(&sim, 1));
+        }
+        assert_eq!(
+            seen,
+            vec![
+                vec![0, 0b0001],
+                vec![0, 0b0010],
+                vec![0, 0b0101],
+                vec![1, 0b1011],
+                vec![0, 0b0110],
+            ]
+        );
+        apply(&mut sim, &sources, &[0, 0, 1]);
+        assert_eq!(outputs(&sim, 1), vec![0, 0]);
+    }
+
+    #[test]
+    fn piso_loads_in_parallel_then_shifts_out() {
+        let mut sim = Simulation::new();
+        sim.add_shift_register(
+            vec![0, 0],
+            3,
+            ShiftRegisterMode::Piso,
+            Orientation::Right,
+            1,
+        );
+        // [SI, LOAD, D, CLK, CLR]
+        let sources = drive_inputs(&mut sim, 1, &[1, 1, 3]);
+        sim.compute_connections();
+        apply(&mut sim, &sources, &[0, 1, 0b101, 1, 0]);
+        let mut serial = vec![outputs(&sim, 1)[0]];
+        for _ in 0..3 {
+            apply(&mut sim, &sources, &[0, 0, 0, 0, 0]);
+            apply(&mut sim, &sources, &[0, 0, 0, 1, 0]);
+            serial.push(outputs(&sim, 1)[0]);
+        }
+        assert_eq!(serial, vec![1, 0, 1, 0]);
+    }
+}
@@ -0,0 +1,32 @@
+use crate::components::MultiPinGate;
+use crate::types::{Orientation, Position};
+use crate::utils::width_mask;
+use crate::Simulation;
+
+// Éclate un bus (entrée unique) en `bits` sorties d'un bit, bit 0 en haut
+#[derive(Clone)]
+pub struct Splitter {
+    pub gate: MultiPinGate,
+    pub bits: u8,
+}
+
+impl Splitter {
+    pub fn new(id: usize, position: Position, orientation: Orientation, bits: u8) -> Self {
+        let mut gate = MultiPinGate::new(
+            id,
+            position,
+            orientation,
+            &[[-5, 0]],
+            &MultiPinGate::column_offsets(0, bits as usize),
+        );
+        gate.circuit_element.width = bits;
+        Splitter { gate, bits }
+    }
+
+    pub fn compute_next_state(&self, sim: &Simulation) -> (Vec<u64>, bool) {
+        let bus = self.gate.input_state(sim, 0) & width_mask(self.bits);
+        let new_outputs: Vec<u64> = (0..self.bits).map(|bit| (bus >> bit) & 1).collect();
+        let is_different = self.gate.outputs_differ(&new_outputs);
+        (new_outputs, is_different)
+    }
+}
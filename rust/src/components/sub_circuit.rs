@@ -0,0 +1,59 @@
+use std::collections::HashMap;
+
+use crate::components::MultiPinGate;
+use crate::types::{Orientation, Position};
+use crate::Simulation;
+
+// Instance d'une définition de sous-circuit. Le contenu est mis à plat dans la
+// simulation parente lors de `compute_connections`
+#[derive(Clone)]
+pub struct SubCircuit {
+    pub gate: MultiPinGate,
+    pub definition: String,
+    pub is_expanded: bool,
+    // Id dans la définition -> id dans la simulation mise à plat
+    pub component_ids: HashMap<usize, usize>,
+    // Id d'un wire de la définition -> wire group de la simulation mise à plat
+    pub wire_groups: HashMap<usize, usize>,
+    pub input_ports: Vec<usize>,
+    pub output_ports: Vec<usize>,
+}
+
+impl SubCircuit {
+    pub fn new(
+        id: usize,
+        position: Position,
+        orientation: Orientation,
+        definition: String,
+        inputs: usize,
+        outputs: usize,
+    ) -> Self {
+        SubCircuit {
+            gate: MultiPinGate::new(
+                id,
+                position,
+                orientation,
+                &MultiPinGate::column_offsets(-5, inputs),
+                &MultiPinGate::column_offsets(0, outputs),
+            ),
+            definition,
+            is_expanded: false,
+            component_ids: HashMap::new(),
+            wire_groups: HashMap::new(),
+            input_ports: Vec::new(),
+            output_ports: Vec::new(),
+        }
+    }
+
+    pub fn compute_next_state(&self, sim: &Simulation) -> (Vec<u64>, bool) {
+        let new_outputs: Vec<u64> = self
+            .output_ports
+            .iter()
+            .map(|port| sim.component_state(*port))
+            .chain(std::iter::repeat(0))
+            .take(self.gate.output_positions.len())
+            .collect();
+        let is_different = self.gate.outputs_differ(&new_outputs);
+        (new_outputs, is_different)
+    }
+}
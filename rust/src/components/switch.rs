@@ -1,6 +1,7 @@
 use crate::circuit_element::CircuitElement;
-use crate::types::{Orientation, Position};
+use crate::types::Position;
 
+#[derive(Clone)]
 pub struct Switch {
     pub circuit_element: CircuitElement,
     pub output_position: Position,
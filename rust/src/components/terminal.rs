@@ -0,0 +1,86 @@
+use crate::components::MultiPinGate;
+use crate::types::{Orientation, Position};
+
+// Au-delà, les octets écrits sont perdus jusqu'au prochain `drain`
+pub const MAX_TERMINAL_BUFFER: usize = 4096;
+
+// Terminal : entrées DATA (bus 8 bits), WRITE puis CLR. Un front montant sur WRITE
+// ajoute l'octet présent sur DATA au tampon de sortie, vidé par l'hôte. Un front montant
+// sur CLR vide le tampon et y place un saut de page (0x0C) pour que l'hôte efface l'écran.
+// L'état du composant compte les octets reçus
+#[derive(Clone)]
+pub struct Terminal {
+    pub gate: MultiPinGate,
+    pub output: Vec<u8>,
+    pub last_write: bool,
+    pub last_clear: bool,
+}
+
+impl Terminal {
+    pub fn new(id: usize, position: Position, orientation: Orientation) -> Self {
+        Terminal {
+            gate: MultiPinGate::new(
+                id,
+                position,
+                orientation,
+                &MultiPinGate::column_offsets(-5, 3),
+                &[],
+            ),
+            output: Vec::new(),
+            last_write: false,
+            last_clear: false,
+        }
+    }
+
+    pub const FORM_FEED: u8 = 0x0C;
+
+    // Renvoie le texte reçu depuis le dernier appel. Un caractère UTF-8 incomplet en fin
+    // de tampon y reste pour l'appel suivant ; un octet invalide devient U+FFFD
+    pub fn drain(&mut self) -> String {
+        let mut text = String::new();
+        let mut rest = self.output.as_slice();
+        while !rest.is_empty() {
+            match std::str::from_utf8(rest) {
+                Ok(valid) => {
+                    text.push_str(valid);
+                    rest = &[];
+                }
+                Err(error) => {
+                    let (valid, after) = rest.split_at(error.valid_up_to());
+                    text.push_str(&String::from_utf8_lossy(valid));
+                    match error.error_len() {
+                        Some(length) => {
+                            text.push(char::REPLACEMENT_CHARACTER);
+                            rest = &after[length..];
+                        }
+                        None => {
+                            rest = after;
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+        self.output = rest.to_vec();
+        text
+    }
+
+    // Le terminal n'ayant pas de sortie, la propagation n'est jamais relancée
+    pub fn update_inputs(&mut self, inputs: &[u64]) -> bool {
+        let write = inputs[1] & 1 != 0;
+        let clear = inputs[2] & 1 != 0;
+        if clear && !self.last_clear {
+            self.output.clear();
+            self.output.push(Self::FORM_FEED);
+        } else if !clear && write && !self.last_write {
+            if self.output.len() < MAX_TERMINAL_BUFFER {
+                self.output.push(inputs[0] as u8);
+            }
+            let received = self.gate.circuit_element.state.wrapping_add(1);
+            self.gate.circuit_element.set_state(received);
+        }
+        self.last_write = write;
+        self.last_clear = clear;
+        false
+    }
+}
@@ -0,0 +1,20 @@
+use crate::circuit_element::CircuitElement;
+use crate::types::Position;
+
+// Étiquette de réseau : tous les tunnels portant le même label relient leurs wire groups
+#[derive(Clone)]
+pub struct Tunnel {
+    pub circuit_element: CircuitElement,
+    pub position: Position,
+    pub label: String,
+}
+
+impl Tunnel {
+    pub fn new(id: usize, position: Position, label: String) -> Self {
+        Tunnel {
+            circuit_element: CircuitElement::new(id),
+            position,
+            label,
+        }
+    }
+}
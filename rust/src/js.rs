@@ -1,4 +1,6 @@
-use crate::types::ChangedElement;
+use crate::simulation::{Fault, FaultSite, TimingPath};
+use crate::types::{ChangedDisplay, ChangedElement, DisplayData, PlacedComponent, Position};
+use crate::Simulation;
 use js_sys::{Array, Object};
 use wasm_bindgen::prelude::*;
 
@@ -11,12 +13,17 @@ pub fn main() {
 pub struct TickResults {
     wires: Vec<ChangedElement>,
     components: Vec<ChangedElement>,
+    displays: Vec<ChangedDisplay>,
 }
 
 #[wasm_bindgen]
 impl TickResults {
     pub fn new(wires: Vec<ChangedElement>, components: Vec<ChangedElement>) -> Self {
-        TickResults { wires, components }
+        TickResults {
+            wires,
+            components,
+            displays: Vec::new(),
+        }
     }
 
     fn elements_to_js_array(elements: &[ChangedElement]) -> Array {
@@ -35,6 +42,21 @@ impl TickResults {
                 &JsValue::from_bool(e.state),
             )
             .unwrap();
+            js_sys::Reflect::set(
+                &obj,
+                &JsValue::from_str("value"),
+                &JsValue::from_f64(e.value()),
+            )
+            .unwrap();
+            // Au-delà de 32 bits, la valeur exacte n'est garantie qu'en BigInt
+            if e.width > 32 {
+                js_sys::Reflect::set(
+                    &obj,
+                    &JsValue::from_str("bigValue"),
+                    &JsValue::from(e.value),
+                )
+                .unwrap();
+            }
             arr.push(&obj);
         }
         arr
@@ -49,12 +71,777 @@ impl TickResults {
     pub fn components(&self) -> Array {
         Self::elements_to_js_array(&self.components)
     }
+
+    // { index, kind, ... } pour chaque afficheur dont l'état a changé
+    #[wasm_bindgen(getter)]
+    pub fn displays(&self) -> Array {
+        let arr = Array::new();
+        for display in &self.displays {
+            let obj = Object::new();
+            let set = |key: &str, value: &JsValue| {
+                js_sys::Reflect::set(&obj, &JsValue::from_str(key), value).unwrap();
+            };
+            set("index", &JsValue::from_f64(display.id as f64));
+            match &display.data {
+                DisplayData::Led { value, color } => {
+                    set("kind", &JsValue::from_str("led"));
+                    set("state", &JsValue::from_bool(*value != 0));
+                    set("value", &JsValue::from(*value));
+                    set("color", &JsValue::from_f64(*color as f64));
+                }
+                DisplayData::SevenSegment { segments } => {
+                    set("kind", &JsValue::from_str("seven_segment"));
+                    let lit = Array::new();
+                    for segment in 0..8 {
+                        lit.push(&JsValue::from_bool((segments >> segment) & 1 != 0));
+                    }
+                    set("segments", &lit);
+                }
+                DisplayData::PixelMatrix {
+                    rows,
+                    columns,
+                    pixels,
+                } => {
+                    set("kind", &JsValue::from_str("pixel_matrix"));
+                    set("rows", &JsValue::from_f64(*rows as f64));
+                    set("columns", &JsValue::from_f64(*columns as f64));
+                    let lines = Array::new();
+                    for row in pixels {
+                        lines.push(&JsValue::from(*row));
+                    }
+                    set("pixels", &lines);
+                }
+            }
+            arr.push(&obj);
+        }
+        arr
+    }
+}
+
+impl TickResults {
+    pub(crate) fn with_displays(mut self, displays: Vec<ChangedDisplay>) -> Self {
+        self.displays = displays;
+        self
+    }
+}
+
+// Rapport d'analyse de timing statique, délais en sous-ticks
+#[wasm_bindgen]
+pub struct TimingReport {
+    paths: Vec<TimingPath>,
+    loops: Vec<usize>,
+    // Délai du chemin critique, calculé avant que `paths` ne soit tronqué
+    critical_delay: u64,
+    clock_period: u64,
+    sub_ticks_per_tick: u32,
+}
+
+impl TimingReport {
+    pub(crate) fn new(
+        paths: Vec<TimingPath>,
+        loops: Vec<usize>,
+        critical_delay: u64,
+        clock_period: u64,
+        sub_ticks_per_tick: u32,
+    ) -> Self {
+        TimingReport {
+            paths,
+            loops,
+            critical_delay,
+            clock_period,
+            sub_ticks_per_tick,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl TimingReport {
+    #[wasm_bindgen(getter)]
+    pub fn critical_delay(&self) -> u64 {
+        self.critical_delay
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn clock_period(&self) -> u64 {
+        self.clock_period
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn worst_slack(&self) -> i64 {
+        self.clock_period as i64 - self.critical_delay() as i64
+    }
+
+    // Plus petite période d'horloge sûre, en ticks
+    #[wasm_bindgen(getter)]
+    pub fn min_clock_period_ticks(&self) -> u64 {
+        self.critical_delay()
+            .div_ceil(self.sub_ticks_per_tick as u64)
+            .max(1)
+    }
+
+    // Fréquence d'horloge maximale, en cycles par tick
+    #[wasm_bindgen(getter)]
+    pub fn max_frequency(&self) -> f64 {
+        match self.critical_delay() {
+            0 => f64::INFINITY,
+            delay => self.sub_ticks_per_tick as f64 / delay as f64,
+        }
+    }
+
+    // Composants pris dans une boucle combinatoire, exclus de l'analyse
+    #[wasm_bindgen(getter)]
+    pub fn loops(&self) -> Vec<usize> {
+        self.loops.clone()
+    }
+
+    // [{ ids, delay, slack }], du chemin le plus long au plus court
+    #[wasm_bindgen(getter)]
+    pub fn paths(&self) -> Array {
+        let arr = Array::new();
+        for path in &self.paths {
+            let obj = Object::new();
+            let ids = Array::new();
+            for id in &path.ids {
+                ids.push(&JsValue::from_f64(*id as f64));
+            }
+            js_sys::Reflect::set(&obj, &JsValue::from_str("ids"), &ids).unwrap();
+            js_sys::Reflect::set(
+                &obj,
+                &JsValue::from_str("delay"),
+                &JsValue::from_f64(path.delay as f64),
+            )
+            .unwrap();
+            js_sys::Reflect::set(
+                &obj,
+                &JsValue::from_str("slack"),
+                &JsValue::from_f64(path.slack as f64),
+            )
+            .unwrap();
+            arr.push(&obj);
+        }
+        arr
+    }
+}
+
+// Résultat d'une simulation de fautes de collage
+#[wasm_bindgen]
+pub struct FaultReport {
+    detected: Vec<Fault>,
+    undetected: Vec<Fault>,
+}
+
+impl FaultReport {
+    pub(crate) fn new(detected: Vec<Fault>, undetected: Vec<Fault>) -> Self {
+        FaultReport {
+            detected,
+            undetected,
+        }
+    }
+
+    pub(crate) fn fault_to_js(fault: &Fault) -> Object {
+        let obj = Object::new();
+        let set = |key: &str, value: &JsValue| {
+            js_sys::Reflect::set(&obj, &JsValue::from_str(key), value).unwrap();
+        };
+        let (site, id, pin) = match fault.site {
+            FaultSite::WireGroup(id) => ("wire_group", id, 0),
+            FaultSite::Output(id, pin) => ("output", id, pin),
+        };
+        set("site", &JsValue::from_str(site));
+        set("id", &JsValue::from_f64(id as f64));
+        set("pin", &JsValue::from_f64(pin as f64));
+        set("stuck_at", &JsValue::from_f64(fault.stuck_at as u8 as f64));
+        let wires = Array::new();
+        for wire in &fault.wires {
+            wires.push(&JsValue::from_f64(*wire as f64));
+        }
+        set("wires", &wires);
+        obj
+    }
+
+    pub(crate) fn faults_to_js_array(faults: &[Fault]) -> Array {
+        let arr = Array::new();
+        for fault in faults {
+            arr.push(&Self::fault_to_js(fault));
+        }
+        arr
+    }
+}
+
+#[wasm_bindgen]
+impl FaultReport {
+    #[wasm_bindgen(getter)]
+    pub fn total(&self) -> usize {
+        self.detected.len() + self.undetected.len()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn detected_count(&self) -> usize {
+        self.detected.len()
+    }
+
+    // Pourcentage de fautes détectées
+    #[wasm_bindgen(getter)]
+    pub fn coverage(&self) -> f64 {
+        match self.total() {
+            0 => 100.0,
+            total => self.detected.len() as f64 * 100.0 / total as f64,
+        }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn detected(&self) -> Array {
+        Self::faults_to_js_array(&self.detected)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn undetected(&self) -> Array {
+        Self::faults_to_js_array(&self.undetected)
+    }
+}
+
+// Vecteurs générés par l'ATPG et classement des fautes de collage
+#[wasm_bindgen]
+pub struct TestPatternReport {
+    inputs: Vec<usize>,
+    vectors: Vec<u64>,
+    detected: Vec<Fault>,
+    untestable: Vec<Fault>,
+    aborted: Vec<Fault>,
+    is_minimal: bool,
+}
+
+impl TestPatternReport {
+    pub(crate) fn new(
+        inputs: Vec<usize>,
+        vectors: Vec<u64>,
+        detected: Vec<Fault>,
+        untestable: Vec<Fault>,
+        aborted: Vec<Fault>,
+        is_minimal: bool,
+    ) -> Self {
+        TestPatternReport {
+            inputs,
+            vectors,
+            detected,
+            untestable,
+            aborted,
+            is_minimal,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl TestPatternReport {
+    // Switches pilotés, dans l'ordre des bits des vecteurs
+    #[wasm_bindgen(getter)]
+    pub fn inputs(&self) -> Vec<usize> {
+        self.inputs.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn vectors(&self) -> Vec<u64> {
+        self.vectors.clone()
+    }
+
+    // Vrai si aucun jeu plus petit ne détecte les mêmes fautes
+    #[wasm_bindgen(getter)]
+    pub fn is_minimal(&self) -> bool {
+        self.is_minimal
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn total(&self) -> usize {
+        self.detected.len() + self.untestable.len() + self.aborted.len()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn detected_count(&self) -> usize {
+        self.detected.len()
+    }
+
+    // Pourcentage de fautes détectées
+    #[wasm_bindgen(getter)]
+    pub fn coverage(&self) -> f64 {
+        match self.total() {
+            0 => 100.0,
+            total => self.detected.len() as f64 * 100.0 / total as f64,
+        }
+    }
+
+    // Pourcentage de fautes détectées parmi les fautes détectables
+    #[wasm_bindgen(getter)]
+    pub fn testable_coverage(&self) -> f64 {
+        match self.total() - self.untestable.len() {
+            0 => 100.0,
+            testable => self.detected.len() as f64 * 100.0 / testable as f64,
+        }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn detected(&self) -> Array {
+        FaultReport::faults_to_js_array(&self.detected)
+    }
+
+    // Fautes redondantes : aucun vecteur ne peut les détecter
+    #[wasm_bindgen(getter)]
+    pub fn untestable(&self) -> Array {
+        FaultReport::faults_to_js_array(&self.untestable)
+    }
+
+    // Fautes pour lesquelles la recherche a été abandonnée
+    #[wasm_bindgen(getter)]
+    pub fn aborted(&self) -> Array {
+        FaultReport::faults_to_js_array(&self.aborted)
+    }
+}
+
+// Résultat de `check_properties` : contre-exemple de la première propriété violée
+#[wasm_bindgen]
+pub struct ModelCheckResult {
+    property: Option<usize>,
+    inputs: Vec<usize>,
+    trace: Vec<u64>,
+    confirmed: bool,
+    steps: u32,
+}
+
+impl ModelCheckResult {
+    pub(crate) fn new(
+        property: Option<usize>,
+        inputs: Vec<usize>,
+        trace: Vec<u64>,
+        confirmed: bool,
+        steps: u32,
+    ) -> Self {
+        ModelCheckResult {
+            property,
+            inputs,
+            trace,
+            confirmed,
+            steps,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl ModelCheckResult {
+    // Vrai si aucune propriété n'est violée sur `steps` ticks
+    #[wasm_bindgen(getter)]
+    pub fn holds(&self) -> bool {
+        self.property.is_none()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn violated_property(&self) -> Option<usize> {
+        self.property
+    }
+
+    // Switches pilotés, dans l'ordre des bits de chaque vecteur de la trace
+    #[wasm_bindgen(getter)]
+    pub fn inputs(&self) -> Vec<usize> {
+        self.inputs.clone()
+    }
+
+    // Un vecteur par tick, jusqu'au tick de la violation
+    #[wasm_bindgen(getter)]
+    pub fn trace(&self) -> Vec<u64> {
+        self.trace.clone()
+    }
+
+    // Faux si le simulateur ne reproduit pas la violation en rejouant la trace
+    #[wasm_bindgen(getter)]
+    pub fn confirmed(&self) -> bool {
+        self.confirmed
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn steps(&self) -> u32 {
+        self.steps
+    }
+}
+
+// [{ id, kind, position, orientation, inputs }]
+fn placed_components_to_js(components: &[PlacedComponent]) -> Array {
+    let arr = Array::new();
+    for component in components {
+        let obj = Object::new();
+        let set = |key: &str, value: &JsValue| {
+            js_sys::Reflect::set(&obj, &JsValue::from_str(key), value).unwrap();
+        };
+        set("id", &JsValue::from_f64(component.id as f64));
+        set("kind", &JsValue::from_str(component.kind));
+        set(
+            "position",
+            &SynthesisResult::position_to_js(&component.position),
+        );
+        set("orientation", &JsValue::from(component.orientation));
+        set("inputs", &JsValue::from_f64(component.inputs as f64));
+        arr.push(&obj);
+    }
+    arr
+}
+
+// [{ id, positions }], au format attendu par `add_wire`
+fn wires_to_js(wires: &[(usize, Vec<Position>)]) -> Array {
+    let arr = Array::new();
+    for (id, positions) in wires {
+        let obj = Object::new();
+        let points = Array::new();
+        for position in positions {
+            points.push(&SynthesisResult::position_to_js(position));
+        }
+        js_sys::Reflect::set(
+            &obj,
+            &JsValue::from_str("id"),
+            &JsValue::from_f64(*id as f64),
+        )
+        .unwrap();
+        js_sys::Reflect::set(&obj, &JsValue::from_str("positions"), &points).unwrap();
+        arr.push(&obj);
+    }
+    arr
+}
+
+// Circuit créé par la synthèse logique
+#[wasm_bindgen]
+pub struct SynthesisResult {
+    variables: Vec<String>,
+    inputs: Vec<usize>,
+    output: usize,
+    expression: String,
+    components: Vec<PlacedComponent>,
+    wires: Vec<(usize, Vec<Position>)>,
+    next_id: usize,
+}
+
+impl SynthesisResult {
+    pub(crate) fn new(
+        variables: Vec<String>,
+        inputs: Vec<usize>,
+        output: usize,
+        expression: String,
+        components: Vec<PlacedComponent>,
+        wires: Vec<(usize, Vec<Position>)>,
+        next_id: usize,
+    ) -> Self {
+        SynthesisResult {
+            variables,
+            inputs,
+            output,
+            expression,
+            components,
+            wires,
+            next_id,
+        }
+    }
+
+    pub(crate) fn position_to_js(position: &Position) -> Array {
+        let arr = Array::new();
+        arr.push(&JsValue::from_f64(position[0] as f64));
+        arr.push(&JsValue::from_f64(position[1] as f64));
+        arr
+    }
+}
+
+#[wasm_bindgen]
+impl SynthesisResult {
+    #[wasm_bindgen(getter)]
+    pub fn variables(&self) -> Vec<String> {
+        self.variables.clone()
+    }
+
+    // Id du switch de chaque variable, dans l'ordre de `variables`
+    #[wasm_bindgen(getter)]
+    pub fn inputs(&self) -> Vec<usize> {
+        self.inputs.clone()
+    }
+
+    // Id de la LED de sortie
+    #[wasm_bindgen(getter)]
+    pub fn output(&self) -> usize {
+        self.output
+    }
+
+    // Expression réalisée par le circuit, après minimisation
+    #[wasm_bindgen(getter)]
+    pub fn expression(&self) -> String {
+        self.expression.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn gate_count(&self) -> usize {
+        self.components
+            .iter()
+            .filter(|component| component.kind.ends_with("Gate"))
+            .count()
+    }
+
+    // Premier id non utilisé par le circuit généré
+    #[wasm_bindgen(getter)]
+    pub fn next_id(&self) -> usize {
+        self.next_id
+    }
+
+    // [{ id, kind, position, orientation, inputs }]
+    #[wasm_bindgen(getter)]
+    pub fn components(&self) -> Array {
+        placed_components_to_js(&self.components)
+    }
+
+    // [{ id, positions }], au format attendu par `add_wire`
+    #[wasm_bindgen(getter)]
+    pub fn wires(&self) -> Array {
+        wires_to_js(&self.wires)
+    }
+}
+
+// Résultat de `optimize` : circuit optimisé et ids concernés par chaque passe
+#[wasm_bindgen]
+pub struct OptimizationResult {
+    pub(crate) simulation: Option<Simulation>,
+    pub(crate) folded: Vec<usize>,
+    pub(crate) merged: Vec<(usize, usize)>,
+    pub(crate) removed: Vec<usize>,
+    pub(crate) removed_wires: Vec<usize>,
+    pub(crate) mapped: Vec<usize>,
+    pub(crate) added: Vec<usize>,
+    pub(crate) added_wires: Vec<usize>,
+    pub(crate) gates_before: usize,
+    pub(crate) gates_after: usize,
+}
+
+#[wasm_bindgen]
+impl OptimizationResult {
+    // Circuit optimisé ; ne peut être récupéré qu'une fois
+    pub fn take_simulation(&mut self) -> Option<Simulation> {
+        self.simulation.take()
+    }
+
+    // Portes remplacées par une constante de même id
+    #[wasm_bindgen(getter)]
+    pub fn folded(&self) -> Vec<usize> {
+        self.folded.clone()
+    }
+
+    // Portes supprimées car identiques à la porte de même rang dans `merged_into`
+    #[wasm_bindgen(getter)]
+    pub fn merged(&self) -> Vec<usize> {
+        self.merged.iter().map(|(id, _)| *id).collect()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn merged_into(&self) -> Vec<usize> {
+        self.merged.iter().map(|(_, kept)| *kept).collect()
+    }
+
+    // Portes et constantes dont la sortie n'était pas utilisée
+    #[wasm_bindgen(getter)]
+    pub fn removed(&self) -> Vec<usize> {
+        self.removed.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn removed_wires(&self) -> Vec<usize> {
+        self.removed_wires.clone()
+    }
+
+    // Portes réécrites en NAND ou NOR, qui gardent leur id
+    #[wasm_bindgen(getter)]
+    pub fn mapped(&self) -> Vec<usize> {
+        self.mapped.clone()
+    }
+
+    // Portes créées par la réécriture
+    #[wasm_bindgen(getter)]
+    pub fn added(&self) -> Vec<usize> {
+        self.added.clone()
+    }
+
+    // Wires routés depuis les portes fusionnées et vers les portes réécrites ou créées
+    #[wasm_bindgen(getter)]
+    pub fn added_wires(&self) -> Vec<usize> {
+        self.added_wires.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn gates_before(&self) -> usize {
+        self.gates_before
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn gates_after(&self) -> usize {
+        self.gates_after
+    }
+}
+
+// Wires créés par le routage automatique
+#[wasm_bindgen]
+pub struct RoutingResult {
+    wires: Vec<(usize, Vec<Position>)>,
+    failed: Vec<usize>,
+    next_id: usize,
+}
+
+impl RoutingResult {
+    pub(crate) fn new(
+        wires: Vec<(usize, Vec<Position>)>,
+        failed: Vec<usize>,
+        next_id: usize,
+    ) -> Self {
+        RoutingResult {
+            wires,
+            failed,
+            next_id,
+        }
+    }
+
+    pub(crate) fn routed_wires(&self) -> &[(usize, Vec<Position>)] {
+        &self.wires
+    }
+}
+
+#[wasm_bindgen]
+impl RoutingResult {
+    // [{ id, positions }]
+    #[wasm_bindgen(getter)]
+    pub fn wires(&self) -> Array {
+        wires_to_js(&self.wires)
+    }
+
+    // Index des nets dont au moins une pin n'a pas pu être reliée
+    #[wasm_bindgen(getter)]
+    pub fn failed(&self) -> Vec<usize> {
+        self.failed.clone()
+    }
+
+    // Premier id suivant les wires créés
+    #[wasm_bindgen(getter)]
+    pub fn next_id(&self) -> usize {
+        self.next_id
+    }
+}
+
+// Circuit placé en couches par `place_netlist`, puis routé
+#[wasm_bindgen]
+pub struct PlacementResult {
+    components: Vec<PlacedComponent>,
+    layers: Vec<usize>,
+    routing: RoutingResult,
+}
+
+impl PlacementResult {
+    pub(crate) fn new(
+        components: Vec<PlacedComponent>,
+        layers: Vec<usize>,
+        routing: RoutingResult,
+    ) -> Self {
+        PlacementResult {
+            components,
+            layers,
+            routing,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl PlacementResult {
+    #[wasm_bindgen(getter)]
+    pub fn components(&self) -> Array {
+        placed_components_to_js(&self.components)
+    }
+
+    // Couche de chaque composant, dans l'ordre de `components`
+    #[wasm_bindgen(getter)]
+    pub fn layers(&self) -> Vec<usize> {
+        self.layers.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn wires(&self) -> Array {
+        self.routing.wires()
+    }
+
+    // Index des nets qui n'ont pas pu être routés entièrement
+    #[wasm_bindgen(getter)]
+    pub fn failed(&self) -> Vec<usize> {
+        self.routing.failed()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn next_id(&self) -> usize {
+        self.routing.next_id()
+    }
+}
+
+// Géométrie d'une sélection après transformation ou duplication
+#[wasm_bindgen]
+pub struct SelectionResult {
+    components: Vec<PlacedComponent>,
+    wires: Vec<(usize, Vec<Position>)>,
+    copied_from: Vec<usize>,
+}
+
+impl SelectionResult {
+    pub(crate) fn new(
+        components: Vec<PlacedComponent>,
+        wires: Vec<(usize, Vec<Position>)>,
+        copied_from: Vec<usize>,
+    ) -> Self {
+        SelectionResult {
+            components,
+            wires,
+            copied_from,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl SelectionResult {
+    #[wasm_bindgen(getter)]
+    pub fn components(&self) -> Array {
+        placed_components_to_js(&self.components)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn wires(&self) -> Array {
+        wires_to_js(&self.wires)
+    }
+
+    // Pour une duplication, id d'origine de chaque copie : composants puis wires
+    #[wasm_bindgen(getter)]
+    pub fn copied_from(&self) -> Vec<usize> {
+        self.copied_from.clone()
+    }
+}
+
+impl ChangedElement {
+    pub(crate) fn new(id: usize, value: u64, width: u8) -> Self {
+        ChangedElement {
+            id,
+            state: value != 0,
+            value,
+            width,
+        }
+    }
 }
 
 #[wasm_bindgen]
 impl ChangedElement {
-    #[wasm_bindgen(constructor)]
-    pub fn new(id: usize, state: bool) -> Self {
-        ChangedElement { id, state }
+    // Nombre JS, exact pour les bus jusqu'à 32 bits (et au-delà tant que la valeur tient sur 53 bits)
+    #[wasm_bindgen(getter)]
+    pub fn value(&self) -> f64 {
+        self.value as f64
+    }
+
+    // Valeur complète en BigInt, pour les bus larges
+    #[wasm_bindgen(getter = bigValue)]
+    pub fn big_value(&self) -> u64 {
+        self.value
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn width(&self) -> u8 {
+        self.width
     }
 }
@@ -9,12 +9,18 @@ mod wire;
 use std::collections::{HashMap, HashSet};
 use wasm_bindgen::prelude::*;
 
+use crate::circuit_element::CircuitElement;
 use crate::components::*;
 use crate::js::TickResults;
-use crate::types::{ChangedElement, OldComponents, OldWireGroup};
+use crate::simulation::{FaultSite, Property, TimingModel};
+use crate::types::{
+    ChangedDisplay, ChangedElement, ComponentKind, ConnectionMode, OldComponents, OldWireGroup,
+    Orientation, Position,
+};
 use crate::utils::console_log;
 use crate::wire::{Wire, WireGroup};
 
+#[derive(Clone)]
 pub enum ComposantsEnum {
     OrGate(OrGate),
     AndGate(AndGate),
@@ -24,6 +30,479 @@ pub enum ComposantsEnum {
     BufferGate(BufferGate),
     LatchGate(LatchGate),
     TimerGate(TimerGate),
+    Splitter(Splitter),
+    Merger(Merger),
+    InputPort(InputPort),
+    OutputPort(OutputPort),
+    SubCircuit(SubCircuit),
+    Clock(Clock),
+    FlipFlop(FlipFlop),
+    NandGate(NandGate),
+    NorGate(NorGate),
+    XnorGate(XnorGate),
+    Rom(Rom),
+    Ram(Ram),
+    Register(Register),
+    Counter(Counter),
+    ShiftRegister(ShiftRegister),
+    Multiplexer(Multiplexer),
+    Demultiplexer(Demultiplexer),
+    Decoder(Decoder),
+    PriorityEncoder(PriorityEncoder),
+    Adder(Adder),
+    Comparator(Comparator),
+    Alu(Alu),
+    Button(Button),
+    DipSwitch(DipSwitch),
+    Led(Led),
+    SevenSegment(SevenSegment),
+    PixelMatrix(PixelMatrix),
+    Keyboard(Keyboard),
+    Terminal(Terminal),
+    Constant(Constant),
+    Pull(Pull),
+    Tunnel(Tunnel),
+}
+
+impl ComposantsEnum {
+    pub fn circuit_element(&self) -> &CircuitElement {
+        match self {
+            ComposantsEnum::OrGate(gate) => &gate.gate.circuit_element,
+            ComposantsEnum::AndGate(gate) => &gate.gate.circuit_element,
+            ComposantsEnum::Switch(switch) => &switch.circuit_element,
+            ComposantsEnum::XorGate(gate) => &gate.gate.circuit_element,
+            ComposantsEnum::NotGate(gate) => &gate.gate.circuit_element,
+            ComposantsEnum::BufferGate(gate) => &gate.gate.circuit_element,
+            ComposantsEnum::LatchGate(gate) => &gate.gate.circuit_element,
+            ComposantsEnum::TimerGate(gate) => &gate.gate.circuit_element,
+            ComposantsEnum::Splitter(splitter) => &splitter.gate.circuit_element,
+            ComposantsEnum::Merger(merger) => &merger.gate.circuit_element,
+            ComposantsEnum::InputPort(port) => &port.port.circuit_element,
+            ComposantsEnum::OutputPort(port) => &port.port.circuit_element,
+            ComposantsEnum::SubCircuit(sub_circuit) => &sub_circuit.gate.circuit_element,
+            ComposantsEnum::Clock(clock) => &clock.gate.circuit_element,
+            ComposantsEnum::FlipFlop(flip_flop) => &flip_flop.gate.circuit_element,
+            ComposantsEnum::NandGate(gate) => &gate.gate.circuit_element,
+            ComposantsEnum::NorGate(gate) => &gate.gate.circuit_element,
+            ComposantsEnum::XnorGate(gate) => &gate.gate.circuit_element,
+            ComposantsEnum::Rom(rom) => &rom.gate.circuit_element,
+            ComposantsEnum::Ram(ram) => &ram.gate.circuit_element,
+            ComposantsEnum::Register(register) => &register.gate.circuit_element,
+            ComposantsEnum::Counter(counter) => &counter.gate.circuit_element,
+            ComposantsEnum::ShiftRegister(register) => &register.gate.circuit_element,
+            ComposantsEnum::Multiplexer(multiplexer) => &multiplexer.gate.circuit_element,
+            ComposantsEnum::Demultiplexer(demultiplexer) => &demultiplexer.gate.circuit_element,
+            ComposantsEnum::Decoder(decoder) => &decoder.gate.circuit_element,
+            ComposantsEnum::PriorityEncoder(encoder) => &encoder.gate.circuit_element,
+            ComposantsEnum::Adder(adder) => &adder.gate.circuit_element,
+            ComposantsEnum::Comparator(comparator) => &comparator.gate.circuit_element,
+            ComposantsEnum::Alu(alu) => &alu.gate.circuit_element,
+            ComposantsEnum::Button(button) => &button.circuit_element,
+            ComposantsEnum::Constant(constant) => &constant.circuit_element,
+            ComposantsEnum::Pull(pull) => &pull.circuit_element,
+            ComposantsEnum::Tunnel(tunnel) => &tunnel.circuit_element,
+            ComposantsEnum::DipSwitch(dip_switch) => &dip_switch.gate.circuit_element,
+            ComposantsEnum::Led(led) => &led.gate.circuit_element,
+            ComposantsEnum::SevenSegment(seven_segment) => &seven_segment.gate.circuit_element,
+            ComposantsEnum::PixelMatrix(pixel_matrix) => &pixel_matrix.gate.circuit_element,
+            ComposantsEnum::Keyboard(keyboard) => &keyboard.gate.circuit_element,
+            ComposantsEnum::Terminal(terminal) => &terminal.gate.circuit_element,
+        }
+    }
+
+    pub fn circuit_element_mut(&mut self) -> &mut CircuitElement {
+        match self {
+            ComposantsEnum::OrGate(gate) => &mut gate.gate.circuit_element,
+            ComposantsEnum::AndGate(gate) => &mut gate.gate.circuit_element,
+            ComposantsEnum::Switch(switch) => &mut switch.circuit_element,
+            ComposantsEnum::XorGate(gate) => &mut gate.gate.circuit_element,
+            ComposantsEnum::NotGate(gate) => &mut gate.gate.circuit_element,
+            ComposantsEnum::BufferGate(gate) => &mut gate.gate.circuit_element,
+            ComposantsEnum::LatchGate(gate) => &mut gate.gate.circuit_element,
+            ComposantsEnum::TimerGate(gate) => &mut gate.gate.circuit_element,
+            ComposantsEnum::Splitter(splitter) => &mut splitter.gate.circuit_element,
+            ComposantsEnum::Merger(merger) => &mut merger.gate.circuit_element,
+            ComposantsEnum::InputPort(port) => &mut port.port.circuit_element,
+            ComposantsEnum::OutputPort(port) => &mut port.port.circuit_element,
+            ComposantsEnum::SubCircuit(sub_circuit) => &mut sub_circuit.gate.circuit_element,
+            ComposantsEnum::Clock(clock) => &mut clock.gate.circuit_element,
+            ComposantsEnum::FlipFlop(flip_flop) => &mut flip_flop.gate.circuit_element,
+            ComposantsEnum::NandGate(gate) => &mut gate.gate.circuit_element,
+            ComposantsEnum::NorGate(gate) => &mut gate.gate.circuit_element,
+            ComposantsEnum::XnorGate(gate) => &mut gate.gate.circuit_element,
+            ComposantsEnum::Rom(rom) => &mut rom.gate.circuit_element,
+            ComposantsEnum::Ram(ram) => &mut ram.gate.circuit_element,
+            ComposantsEnum::Register(register) => &mut register.gate.circuit_element,
+            ComposantsEnum::Counter(counter) => &mut counter.gate.circuit_element,
+            ComposantsEnum::ShiftRegister(register) => &mut register.gate.circuit_element,
+            ComposantsEnum::Multiplexer(multiplexer) => &mut multiplexer.gate.circuit_element,
+            ComposantsEnum::Demultiplexer(demultiplexer) => &mut demultiplexer.gate.circuit_element,
+            ComposantsEnum::Decoder(decoder) => &mut decoder.gate.circuit_element,
+            ComposantsEnum::PriorityEncoder(encoder) => &mut encoder.gate.circuit_element,
+            ComposantsEnum::Adder(adder) => &mut adder.gate.circuit_element,
+            ComposantsEnum::Comparator(comparator) => &mut comparator.gate.circuit_element,
+            ComposantsEnum::Alu(alu) => &mut alu.gate.circuit_element,
+            ComposantsEnum::Button(button) => &mut button.circuit_element,
+            ComposantsEnum::Constant(constant) => &mut constant.circuit_element,
+            ComposantsEnum::Pull(pull) => &mut pull.circuit_element,
+            ComposantsEnum::Tunnel(tunnel) => &mut tunnel.circuit_element,
+            ComposantsEnum::DipSwitch(dip_switch) => &mut dip_switch.gate.circuit_element,
+            ComposantsEnum::Led(led) => &mut led.gate.circuit_element,
+            ComposantsEnum::SevenSegment(seven_segment) => &mut seven_segment.gate.circuit_element,
+            ComposantsEnum::PixelMatrix(pixel_matrix) => &mut pixel_matrix.gate.circuit_element,
+            ComposantsEnum::Keyboard(keyboard) => &mut keyboard.gate.circuit_element,
+            ComposantsEnum::Terminal(terminal) => &mut terminal.gate.circuit_element,
+        }
+    }
+
+    // Nom du type de composant, utilisé pour configurer les délais par type
+    pub fn kind(&self) -> ComponentKind {
+        match self {
+            ComposantsEnum::OrGate(_) => ComponentKind::OrGate,
+            ComposantsEnum::AndGate(_) => ComponentKind::AndGate,
+            ComposantsEnum::Switch(_) => ComponentKind::Switch,
+            ComposantsEnum::XorGate(_) => ComponentKind::XorGate,
+            ComposantsEnum::NotGate(_) => ComponentKind::NotGate,
+            ComposantsEnum::BufferGate(_) => ComponentKind::BufferGate,
+            ComposantsEnum::LatchGate(_) => ComponentKind::LatchGate,
+            ComposantsEnum::TimerGate(_) => ComponentKind::TimerGate,
+            ComposantsEnum::Splitter(_) => ComponentKind::Splitter,
+            ComposantsEnum::Merger(_) => ComponentKind::Merger,
+            ComposantsEnum::InputPort(_) => ComponentKind::InputPort,
+            ComposantsEnum::OutputPort(_) => ComponentKind::OutputPort,
+            ComposantsEnum::SubCircuit(_) => ComponentKind::SubCircuit,
+            ComposantsEnum::Clock(_) => ComponentKind::Clock,
+            ComposantsEnum::FlipFlop(_) => ComponentKind::FlipFlop,
+            ComposantsEnum::NandGate(_) => ComponentKind::NandGate,
+            ComposantsEnum::NorGate(_) => ComponentKind::NorGate,
+            ComposantsEnum::XnorGate(_) => ComponentKind::XnorGate,
+            ComposantsEnum::Rom(_) => ComponentKind::Rom,
+            ComposantsEnum::Ram(_) => ComponentKind::Ram,
+            ComposantsEnum::Register(_) => ComponentKind::Register,
+            ComposantsEnum::Counter(_) => ComponentKind::Counter,
+            ComposantsEnum::ShiftRegister(_) => ComponentKind::ShiftRegister,
+            ComposantsEnum::Multiplexer(_) => ComponentKind::Multiplexer,
+            ComposantsEnum::Demultiplexer(_) => ComponentKind::Demultiplexer,
+            ComposantsEnum::Decoder(_) => ComponentKind::Decoder,
+            ComposantsEnum::PriorityEncoder(_) => ComponentKind::PriorityEncoder,
+            ComposantsEnum::Adder(_) => ComponentKind::Adder,
+            ComposantsEnum::Comparator(_) => ComponentKind::Comparator,
+            ComposantsEnum::Alu(_) => ComponentKind::Alu,
+            ComposantsEnum::Button(_) => ComponentKind::Button,
+            ComposantsEnum::DipSwitch(_) => ComponentKind::DipSwitch,
+            ComposantsEnum::Led(_) => ComponentKind::Led,
+            ComposantsEnum::SevenSegment(_) => ComponentKind::SevenSegment,
+            ComposantsEnum::PixelMatrix(_) => ComponentKind::PixelMatrix,
+            ComposantsEnum::Keyboard(_) => ComponentKind::Keyboard,
+            ComposantsEnum::Terminal(_) => ComponentKind::Terminal,
+            ComposantsEnum::Constant(_) => ComponentKind::Constant,
+            ComposantsEnum::Pull(_) => ComponentKind::Pull,
+            ComposantsEnum::Tunnel(_) => ComponentKind::Tunnel,
+        }
+    }
+
+    pub fn kind_name(&self) -> &'static str {
+        match self {
+            ComposantsEnum::OrGate(_) => "OrGate",
+            ComposantsEnum::AndGate(_) => "AndGate",
+            ComposantsEnum::Switch(_) => "Switch",
+            ComposantsEnum::XorGate(_) => "XorGate",
+            ComposantsEnum::NotGate(_) => "NotGate",
+            ComposantsEnum::BufferGate(_) => "BufferGate",
+            ComposantsEnum::LatchGate(_) => "LatchGate",
+            ComposantsEnum::TimerGate(_) => "TimerGate",
+            ComposantsEnum::Splitter(_) => "Splitter",
+            ComposantsEnum::Merger(_) => "Merger",
+            ComposantsEnum::InputPort(_) => "InputPort",
+            ComposantsEnum::OutputPort(_) => "OutputPort",
+            ComposantsEnum::SubCircuit(_) => "SubCircuit",
+            ComposantsEnum::Clock(_) => "Clock",
+            ComposantsEnum::FlipFlop(_) => "FlipFlop",
+            ComposantsEnum::NandGate(_) => "NandGate",
+            ComposantsEnum::NorGate(_) => "NorGate",
+            ComposantsEnum::XnorGate(_) => "XnorGate",
+            ComposantsEnum::Rom(_) => "Rom",
+            ComposantsEnum::Ram(_) => "Ram",
+            ComposantsEnum::Register(_) => "Register",
+            ComposantsEnum::Counter(_) => "Counter",
+            ComposantsEnum::ShiftRegister(_) => "ShiftRegister",
+            ComposantsEnum::Multiplexer(_) => "Multiplexer",
+            ComposantsEnum::Demultiplexer(_) => "Demultiplexer",
+            ComposantsEnum::Decoder(_) => "Decoder",
+            ComposantsEnum::PriorityEncoder(_) => "PriorityEncoder",
+            ComposantsEnum::Adder(_) => "Adder",
+            ComposantsEnum::Comparator(_) => "Comparator",
+            ComposantsEnum::Alu(_) => "Alu",
+            ComposantsEnum::Button(_) => "Button",
+            ComposantsEnum::DipSwitch(_) => "DipSwitch",
+            ComposantsEnum::Led(_) => "Led",
+            ComposantsEnum::SevenSegment(_) => "SevenSegment",
+            ComposantsEnum::PixelMatrix(_) => "PixelMatrix",
+            ComposantsEnum::Keyboard(_) => "Keyboard",
+            ComposantsEnum::Terminal(_) => "Terminal",
+            ComposantsEnum::Constant(_) => "Constant",
+            ComposantsEnum::Pull(_) => "Pull",
+            ComposantsEnum::Tunnel(_) => "Tunnel",
+        }
+    }
+
+    pub fn multi_pin_gate(&self) -> Option<&MultiPinGate> {
+        match self {
+            ComposantsEnum::Splitter(splitter) => Some(&splitter.gate),
+            ComposantsEnum::Merger(merger) => Some(&merger.gate),
+            ComposantsEnum::SubCircuit(sub_circuit) => Some(&sub_circuit.gate),
+            ComposantsEnum::FlipFlop(flip_flop) => Some(&flip_flop.gate),
+            ComposantsEnum::Rom(rom) => Some(&rom.gate),
+            ComposantsEnum::Ram(ram) => Some(&ram.gate),
+            ComposantsEnum::Register(register) => Some(&register.gate),
+            ComposantsEnum::Counter(counter) => Some(&counter.gate),
+            ComposantsEnum::ShiftRegister(register) => Some(&register.gate),
+            ComposantsEnum::Multiplexer(multiplexer) => Some(&multiplexer.gate),
+            ComposantsEnum::Demultiplexer(demultiplexer) => Some(&demultiplexer.gate),
+            ComposantsEnum::Decoder(decoder) => Some(&decoder.gate),
+            ComposantsEnum::PriorityEncoder(encoder) => Some(&encoder.gate),
+            ComposantsEnum::Adder(adder) => Some(&adder.gate),
+            ComposantsEnum::Comparator(comparator) => Some(&comparator.gate),
+            ComposantsEnum::Alu(alu) => Some(&alu.gate),
+            ComposantsEnum::DipSwitch(dip_switch) => Some(&dip_switch.gate),
+            ComposantsEnum::Led(led) => Some(&led.gate),
+            ComposantsEnum::SevenSegment(seven_segment) => Some(&seven_segment.gate),
+            ComposantsEnum::PixelMatrix(pixel_matrix) => Some(&pixel_matrix.gate),
+            ComposantsEnum::Keyboard(keyboard) => Some(&keyboard.gate),
+            ComposantsEnum::Terminal(terminal) => Some(&terminal.gate),
+            _ => None,
+        }
+    }
+
+    pub fn multi_pin_gate_mut(&mut self) -> Option<&mut MultiPinGate> {
+        match self {
+            ComposantsEnum::Splitter(splitter) => Some(&mut splitter.gate),
+            ComposantsEnum::Merger(merger) => Some(&mut merger.gate),
+            ComposantsEnum::SubCircuit(sub_circuit) => Some(&mut sub_circuit.gate),
+            ComposantsEnum::FlipFlop(flip_flop) => Some(&mut flip_flop.gate),
+            ComposantsEnum::Rom(rom) => Some(&mut rom.gate),
+            ComposantsEnum::Ram(ram) => Some(&mut ram.gate),
+            ComposantsEnum::Register(register) => Some(&mut register.gate),
+            ComposantsEnum::Counter(counter) => Some(&mut counter.gate),
+            ComposantsEnum::ShiftRegister(register) => Some(&mut register.gate),
+            ComposantsEnum::Multiplexer(multiplexer) => Some(&mut multiplexer.gate),
+            ComposantsEnum::Demultiplexer(demultiplexer) => Some(&mut demultiplexer.gate),
+            ComposantsEnum::Decoder(decoder) => Some(&mut decoder.gate),
+            ComposantsEnum::PriorityEncoder(encoder) => Some(&mut encoder.gate),
+            ComposantsEnum::Adder(adder) => Some(&mut adder.gate),
+            ComposantsEnum::Comparator(comparator) => Some(&mut comparator.gate),
+            ComposantsEnum::Alu(alu) => Some(&mut alu.gate),
+            ComposantsEnum::DipSwitch(dip_switch) => Some(&mut dip_switch.gate),
+            ComposantsEnum::Led(led) => Some(&mut led.gate),
+            ComposantsEnum::SevenSegment(seven_segment) => Some(&mut seven_segment.gate),
+            ComposantsEnum::PixelMatrix(pixel_matrix) => Some(&mut pixel_matrix.gate),
+            ComposantsEnum::Keyboard(keyboard) => Some(&mut keyboard.gate),
+            ComposantsEnum::Terminal(terminal) => Some(&mut terminal.gate),
+            _ => None,
+        }
+    }
+
+    // Wire group de chaque pin d'entrée, pour les composants qui les suivent pin par pin
+    pub fn input_groups_mut(&mut self) -> Option<&mut Vec<Option<usize>>> {
+        match self {
+            ComposantsEnum::AndGate(AndGate { gate })
+            | ComposantsEnum::OrGate(OrGate { gate })
+            | ComposantsEnum::XorGate(XorGate { gate })
+            | ComposantsEnum::NandGate(NandGate { gate })
+            | ComposantsEnum::NorGate(NorGate { gate })
+            | ComposantsEnum::XnorGate(XnorGate { gate }) => Some(&mut gate.input_groups),
+            _ => self.multi_pin_gate_mut().map(|gate| &mut gate.input_groups),
+        }
+    }
+
+    pub fn memory_bank(&self) -> Option<&MemoryBank> {
+        match self {
+            ComposantsEnum::Rom(rom) => Some(&rom.memory),
+            ComposantsEnum::Ram(ram) => Some(&ram.memory),
+            _ => None,
+        }
+    }
+
+    pub fn memory_bank_mut(&mut self) -> Option<&mut MemoryBank> {
+        match self {
+            ComposantsEnum::Rom(rom) => Some(&mut rom.memory),
+            ComposantsEnum::Ram(ram) => Some(&mut ram.memory),
+            _ => None,
+        }
+    }
+
+    // État de la sortie `pin` ; les composants à une seule sortie n'ont que la pin 0
+    pub fn output_state(&self, pin: usize) -> u64 {
+        match self.multi_pin_gate() {
+            Some(gate) => gate.output_state(pin),
+            None if pin == 0 => self.circuit_element().state,
+            None => 0,
+        }
+    }
+
+    pub fn position(&self) -> Position {
+        match self {
+            ComposantsEnum::OrGate(OrGate { gate })
+            | ComposantsEnum::AndGate(AndGate { gate })
+            | ComposantsEnum::XorGate(XorGate { gate })
+            | ComposantsEnum::NandGate(NandGate { gate })
+            | ComposantsEnum::NorGate(NorGate { gate })
+            | ComposantsEnum::XnorGate(XnorGate { gate }) => gate.position,
+            ComposantsEnum::NotGate(NotGate { gate })
+            | ComposantsEnum::BufferGate(BufferGate { gate })
+            | ComposantsEnum::TimerGate(TimerGate { gate, .. })
+            | ComposantsEnum::Clock(Clock { gate, .. }) => gate.position,
+            ComposantsEnum::LatchGate(latch) => latch.gate.position,
+            ComposantsEnum::Switch(switch) => switch.position,
+            ComposantsEnum::Button(button) => button.position,
+            ComposantsEnum::InputPort(InputPort { port })
+            | ComposantsEnum::OutputPort(OutputPort { port }) => port.position,
+            ComposantsEnum::Constant(constant) => constant.position,
+            ComposantsEnum::Pull(pull) => pull.position,
+            ComposantsEnum::Tunnel(tunnel) => tunnel.position,
+            _ => self.multi_pin_gate().map_or([0, 0], |gate| gate.position),
+        }
+    }
+
+    pub fn input_positions(&self) -> Vec<Position> {
+        match self {
+            ComposantsEnum::OrGate(OrGate { gate })
+            | ComposantsEnum::AndGate(AndGate { gate })
+            | ComposantsEnum::XorGate(XorGate { gate })
+            | ComposantsEnum::NandGate(NandGate { gate })
+            | ComposantsEnum::NorGate(NorGate { gate })
+            | ComposantsEnum::XnorGate(XnorGate { gate }) => gate.input_positions.clone(),
+            ComposantsEnum::NotGate(NotGate { gate })
+            | ComposantsEnum::BufferGate(BufferGate { gate })
+            | ComposantsEnum::TimerGate(TimerGate { gate, .. })
+            | ComposantsEnum::Clock(Clock { gate, .. }) => vec![gate.input_position],
+            ComposantsEnum::LatchGate(latch) => latch.gate.input_positions.to_vec(),
+            // Sources à une seule pin
+            ComposantsEnum::Switch(_)
+            | ComposantsEnum::Button(_)
+            | ComposantsEnum::InputPort(_)
+            | ComposantsEnum::Constant(_) => Vec::new(),
+            _ => match self.multi_pin_gate() {
+                Some(gate) => gate.input_positions.clone(),
+                None => vec![self.position()],
+            },
+        }
+    }
+
+    pub fn output_positions(&self) -> Vec<Position> {
+        match self {
+            ComposantsEnum::OrGate(OrGate { gate })
+            | ComposantsEnum::AndGate(AndGate { gate })
+            | ComposantsEnum::XorGate(XorGate { gate })
+            | ComposantsEnum::NandGate(NandGate { gate })
+            | ComposantsEnum::NorGate(NorGate { gate })
+            | ComposantsEnum::XnorGate(XnorGate { gate }) => vec![gate.output_position],
+            ComposantsEnum::NotGate(NotGate { gate })
+            | ComposantsEnum::BufferGate(BufferGate { gate })
+            | ComposantsEnum::TimerGate(TimerGate { gate, .. })
+            | ComposantsEnum::Clock(Clock { gate, .. }) => vec![gate.output_position],
+            ComposantsEnum::LatchGate(latch) => vec![latch.gate.output_position],
+            ComposantsEnum::Switch(switch) => vec![switch.output_position],
+            ComposantsEnum::Button(_)
+            | ComposantsEnum::InputPort(_)
+            | ComposantsEnum::Constant(_) => vec![self.position()],
+            _ => self
+                .multi_pin_gate()
+                .map(|gate| gate.output_positions.clone())
+                .unwrap_or_default(),
+        }
+    }
+
+    // Positions des pins, entrées puis sorties
+    pub fn pin_positions(&self) -> Vec<Position> {
+        let mut positions = self.input_positions();
+        positions.extend(self.output_positions());
+        positions
+    }
+
+    // Orientation des composants multi-pins ; pour les portes, déduite de la
+    // position de la première entrée. Les composants à une seule pin sont `Right`
+    pub fn orientation(&self) -> Orientation {
+        if let Some(gate) = self.multi_pin_gate() {
+            return gate.orientation;
+        }
+        let position = self.position();
+        let Some(input) = self.input_positions().first().copied() else {
+            return Orientation::Right;
+        };
+        let (dx, dy) = (input[0] - position[0], input[1] - position[1]);
+        match (dx, dy) {
+            _ if dx < 0 && dx.abs() >= dy.abs() => Orientation::Right,
+            _ if dx > 0 && dx.abs() >= dy.abs() => Orientation::Left,
+            _ if dy < 0 => Orientation::Up,
+            _ if dy > 0 => Orientation::Down,
+            _ => Orientation::Right,
+        }
+    }
+
+    // Applique `map` à la position et à chaque pin du composant
+    pub fn map_positions(&mut self, map: impl Fn(Position) -> Position) {
+        match self {
+            ComposantsEnum::OrGate(OrGate { gate })
+            | ComposantsEnum::AndGate(AndGate { gate })
+            | ComposantsEnum::XorGate(XorGate { gate })
+            | ComposantsEnum::NandGate(NandGate { gate })
+            | ComposantsEnum::NorGate(NorGate { gate })
+            | ComposantsEnum::XnorGate(XnorGate { gate }) => {
+                gate.position = map(gate.position);
+                gate.output_position = map(gate.output_position);
+                for input in gate.input_positions.iter_mut() {
+                    *input = map(*input);
+                }
+            }
+            ComposantsEnum::NotGate(NotGate { gate })
+            | ComposantsEnum::BufferGate(BufferGate { gate })
+            | ComposantsEnum::TimerGate(TimerGate { gate, .. })
+            | ComposantsEnum::Clock(Clock { gate, .. }) => {
+                gate.position = map(gate.position);
+                gate.output_position = map(gate.output_position);
+                gate.input_position = map(gate.input_position);
+            }
+            ComposantsEnum::LatchGate(latch) => {
+                latch.gate.position = map(latch.gate.position);
+                latch.gate.output_position = map(latch.gate.output_position);
+                for input in latch.gate.input_positions.iter_mut() {
+                    *input = map(*input);
+                }
+            }
+            ComposantsEnum::Switch(switch) => {
+                switch.position = map(switch.position);
+                switch.output_position = map(switch.output_position);
+            }
+            ComposantsEnum::Button(button) => button.position = map(button.position),
+            ComposantsEnum::InputPort(InputPort { port })
+            | ComposantsEnum::OutputPort(OutputPort { port }) => port.position = map(port.position),
+            ComposantsEnum::Constant(constant) => constant.position = map(constant.position),
+            ComposantsEnum::Pull(pull) => pull.position = map(pull.position),
+            ComposantsEnum::Tunnel(tunnel) => tunnel.position = map(tunnel.position),
+            _ => {
+                if let Some(gate) = self.multi_pin_gate_mut() {
+                    gate.position = map(gate.position);
+                    for pin in gate
+                        .input_positions
+                        .iter_mut()
+                        .chain(gate.output_positions.iter_mut())
+                    {
+                        *pin = map(*pin);
+                    }
+                }
+            }
+        }
+    }
+}
+
+// États calculés pendant un tick, appliqués une fois tous les composants évalués
+pub(crate) struct PendingStates {
+    pub composant_states: Vec<(usize, (u64, bool))>,
+    pub pin_states: Vec<(usize, Vec<u64>)>,
+    // Entrées échantillonnées des composants séquentiels
+    pub sampled_inputs: Vec<(usize, Vec<u64>)>,
+    pub is_different: bool,
 }
 
 #[wasm_bindgen]
@@ -33,6 +512,29 @@ pub struct Simulation {
     wire_set: HashSet<usize>,
     wire_groups: Vec<WireGroup>,
     tick_counter: u32,
+    // Pas de simulation (appels à `compute_frame` × `tick_per_frame`) ; les évènements
+    // programmés des boutons sont datés dans ce compteur
+    step_counter: u32,
+    // Tick vu par les horloges ; n'avance pas quand elles sont en pause
+    clock_tick: u32,
+    clocks_running: bool,
+    clock_steps: u32,
+    sub_circuit_definitions: HashMap<String, Simulation>,
+    // Composants issus de la mise à plat des sous-circuits, absents du front
+    internal_ids: HashSet<usize>,
+    connection_mode: ConnectionMode,
+    junctions: HashSet<Position>,
+    timing: TimingModel,
+    // Collages injectés, valeur forcée par site
+    faults: HashMap<FaultSite, u64>,
+    // Propriétés vérifiées par `check_properties`
+    properties: Vec<Property>,
+}
+
+impl Default for Simulation {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 #[wasm_bindgen]
@@ -44,6 +546,47 @@ impl Simulation {
             wire_set: HashSet::new(),
             wire_groups: Vec::new(),
             tick_counter: 0,
+            step_counter: 0,
+            clock_tick: 0,
+            clocks_running: true,
+            clock_steps: 0,
+            sub_circuit_definitions: HashMap::new(),
+            internal_ids: HashSet::new(),
+            connection_mode: ConnectionMode::Legacy,
+            junctions: HashSet::new(),
+            timing: TimingModel::default(),
+            faults: HashMap::new(),
+            properties: Vec::new(),
+        }
+    }
+
+    pub(crate) fn component_state(&self, id: usize) -> u64 {
+        match self.composants_map.get(&id) {
+            Some(composant) if composant.multi_pin_gate().is_none() => self
+                .forced_state(FaultSite::Output(id, 0))
+                .unwrap_or(composant.circuit_element().state),
+            Some(composant) => composant.circuit_element().state,
+            None => 0,
+        }
+    }
+
+    pub(crate) fn component_pin_state(&self, id: usize, pin: usize) -> u64 {
+        if let Some(state) = self.forced_state(FaultSite::Output(id, pin)) {
+            return state;
+        }
+        match self.composants_map.get(&id) {
+            Some(composant) => composant.output_state(pin),
+            None => 0,
+        }
+    }
+
+    pub(crate) fn wire_group_state(&self, wire_group_index: usize) -> u64 {
+        if let Some(state) = self.forced_state(FaultSite::WireGroup(wire_group_index)) {
+            return state;
+        }
+        match self.wire_groups.get(wire_group_index) {
+            Some(group) => group.circuit_element.state,
+            None => 0,
         }
     }
 
@@ -59,8 +602,31 @@ impl Simulation {
         }
 
         // Calcul des nouveaux états des composants
-        let mut new_composant_state: Vec<(usize, (bool, bool))> =
+        let pending = self.compute_pending_states();
+        is_something_different |= pending.is_different;
+
+        // Mise à jour des wire groups
+        for (index, &(_, wire_group_state)) in new_wire_group_states.iter().enumerate() {
+            self.wire_groups[index]
+                .circuit_element
+                .set_state(wire_group_state.0);
+        }
+
+        is_something_different |= self.apply_pending_states(pending);
+
+        self.tick_counter += 1;
+        is_something_different
+    }
+
+    // Nouveaux états des composants, calculés à partir des états courants
+    pub(crate) fn compute_pending_states(&self) -> PendingStates {
+        let mut is_something_different: bool = false;
+
+        let mut new_composant_state: Vec<(usize, (u64, bool))> =
             Vec::with_capacity(self.composants_map.len());
+        let mut new_pin_states: Vec<(usize, Vec<u64>)> = Vec::new();
+        // Entrées échantillonnées des composants séquentiels
+        let mut new_sampled_inputs: Vec<(usize, Vec<u64>)> = Vec::new();
 
         for (id, composant) in self.composants_map.iter() {
             match composant {
@@ -79,6 +645,21 @@ impl Simulation {
                     new_composant_state.push((*id, new_state));
                     is_something_different |= new_state.1;
                 }
+                ComposantsEnum::NandGate(nand_gate) => {
+                    let new_state = nand_gate.compute_next_state(self);
+                    new_composant_state.push((*id, new_state));
+                    is_something_different |= new_state.1;
+                }
+                ComposantsEnum::NorGate(nor_gate) => {
+                    let new_state = nor_gate.compute_next_state(self);
+                    new_composant_state.push((*id, new_state));
+                    is_something_different |= new_state.1;
+                }
+                ComposantsEnum::XnorGate(xnor_gate) => {
+                    let new_state = xnor_gate.compute_next_state(self);
+                    new_composant_state.push((*id, new_state));
+                    is_something_different |= new_state.1;
+                }
                 ComposantsEnum::NotGate(not_gate) => {
                     let new_state = not_gate.compute_next_state(self);
                     new_composant_state.push((*id, new_state));
@@ -101,18 +682,137 @@ impl Simulation {
                 ComposantsEnum::Switch(switch) => {
                     new_composant_state.push((*id, (switch.circuit_element.state, false)));
                 }
+                ComposantsEnum::Button(button) => {
+                    new_composant_state.push((*id, (button.circuit_element.state, false)));
+                }
+                ComposantsEnum::Constant(constant) => {
+                    let new_state = constant.compute_next_state();
+                    new_composant_state.push((*id, new_state));
+                    is_something_different |= new_state.1;
+                }
+                ComposantsEnum::Pull(_) | ComposantsEnum::Tunnel(_) => (),
+                ComposantsEnum::DipSwitch(dip_switch) => {
+                    let new_outputs = dip_switch.compute_next_state();
+                    is_something_different |= new_outputs.1;
+                    new_pin_states.push((*id, new_outputs.0));
+                }
+                ComposantsEnum::Led(led) => {
+                    new_composant_state.push((*id, led.compute_next_state(self)));
+                }
+                ComposantsEnum::SevenSegment(seven_segment) => {
+                    new_composant_state.push((*id, seven_segment.compute_next_state(self)));
+                }
+                ComposantsEnum::PixelMatrix(pixel_matrix) => {
+                    new_sampled_inputs.push((*id, pixel_matrix.gate.read_inputs(self)));
+                }
+                ComposantsEnum::Keyboard(keyboard) => {
+                    new_sampled_inputs.push((*id, keyboard.gate.read_inputs(self)));
+                }
+                ComposantsEnum::Terminal(terminal) => {
+                    new_sampled_inputs.push((*id, terminal.gate.read_inputs(self)));
+                }
+                ComposantsEnum::Splitter(splitter) => {
+                    let new_outputs = splitter.compute_next_state(self);
+                    is_something_different |= new_outputs.1;
+                    new_pin_states.push((*id, new_outputs.0));
+                }
+                ComposantsEnum::Merger(merger) => {
+                    let new_outputs = merger.compute_next_state(self);
+                    is_something_different |= new_outputs.1;
+                    new_pin_states.push((*id, new_outputs.0));
+                }
+                ComposantsEnum::InputPort(input_port) => {
+                    let new_state = input_port.port.compute_next_state(self);
+                    new_composant_state.push((*id, new_state));
+                    is_something_different |= new_state.1;
+                }
+                ComposantsEnum::OutputPort(output_port) => {
+                    let new_state = output_port.port.compute_next_state(self);
+                    new_composant_state.push((*id, new_state));
+                    is_something_different |= new_state.1;
+                }
+                ComposantsEnum::SubCircuit(sub_circuit) => {
+                    let new_outputs = sub_circuit.compute_next_state(self);
+                    is_something_different |= new_outputs.1;
+                    new_pin_states.push((*id, new_outputs.0));
+                }
+                ComposantsEnum::Clock(clock) => {
+                    let new_state = clock.compute_next_state(self);
+                    new_composant_state.push((*id, new_state));
+                    is_something_different |= new_state.1;
+                }
+                ComposantsEnum::FlipFlop(flip_flop) => {
+                    new_sampled_inputs.push((*id, flip_flop.gate.read_inputs(self)));
+                }
+                ComposantsEnum::Rom(rom) => {
+                    let new_outputs = rom.compute_next_state(self);
+                    is_something_different |= new_outputs.1;
+                    new_pin_states.push((*id, new_outputs.0));
+                }
+                ComposantsEnum::Ram(ram) => {
+                    new_sampled_inputs.push((*id, ram.gate.read_inputs(self)));
+                }
+                ComposantsEnum::Register(register) => {
+                    new_sampled_inputs.push((*id, register.gate.read_inputs(self)));
+                }
+                ComposantsEnum::Counter(counter) => {
+                    new_sampled_inputs.push((*id, counter.gate.read_inputs(self)));
+                }
+                ComposantsEnum::ShiftRegister(register) => {
+                    new_sampled_inputs.push((*id, register.gate.read_inputs(self)));
+                }
+                ComposantsEnum::Multiplexer(multiplexer) => {
+                    let new_outputs = multiplexer.compute_next_state(self);
+                    is_something_different |= new_outputs.1;
+                    new_pin_states.push((*id, new_outputs.0));
+                }
+                ComposantsEnum::Demultiplexer(demultiplexer) => {
+                    let new_outputs = demultiplexer.compute_next_state(self);
+                    is_something_different |= new_outputs.1;
+                    new_pin_states.push((*id, new_outputs.0));
+                }
+                ComposantsEnum::Decoder(decoder) => {
+                    let new_outputs = decoder.compute_next_state(self);
+                    is_something_different |= new_outputs.1;
+                    new_pin_states.push((*id, new_outputs.0));
+                }
+                ComposantsEnum::PriorityEncoder(encoder) => {
+                    let new_outputs = encoder.compute_next_state(self);
+                    is_something_different |= new_outputs.1;
+                    new_pin_states.push((*id, new_outputs.0));
+                }
+                ComposantsEnum::Adder(adder) => {
+                    let new_outputs = adder.compute_next_state(self);
+                    is_something_different |= new_outputs.1;
+                    new_pin_states.push((*id, new_outputs.0));
+                }
+                ComposantsEnum::Comparator(comparator) => {
+                    let new_outputs = comparator.compute_next_state(self);
+                    is_something_different |= new_outputs.1;
+                    new_pin_states.push((*id, new_outputs.0));
+                }
+                ComposantsEnum::Alu(alu) => {
+                    let new_outputs = alu.compute_next_state(self);
+                    is_something_different |= new_outputs.1;
+                    new_pin_states.push((*id, new_outputs.0));
+                }
             };
         }
 
-        // Mise à jour des wire groups
-        for (index, &(_, wire_group_state)) in new_wire_group_states.iter().enumerate() {
-            self.wire_groups[index]
-                .circuit_element
-                .set_state(wire_group_state.0);
+        PendingStates {
+            composant_states: new_composant_state,
+            pin_states: new_pin_states,
+            sampled_inputs: new_sampled_inputs,
+            is_different: is_something_different,
         }
+    }
+
+    // Applique les états calculés ; renvoie si un composant à état interne a changé
+    pub(crate) fn apply_pending_states(&mut self, pending: PendingStates) -> bool {
+        let mut is_something_different: bool = false;
 
         // Mise à jour des composants
-        for (id, comp_state) in new_composant_state {
+        for (id, comp_state) in pending.composant_states {
             if let Some(composant) = self.composants_map.get_mut(&id) {
                 match composant {
                     ComposantsEnum::OrGate(or_gate) => {
@@ -124,6 +824,15 @@ impl Simulation {
                     ComposantsEnum::XorGate(xor_gate) => {
                         xor_gate.gate.circuit_element.set_state(comp_state.0)
                     }
+                    ComposantsEnum::NandGate(nand_gate) => {
+                        nand_gate.gate.circuit_element.set_state(comp_state.0)
+                    }
+                    ComposantsEnum::NorGate(nor_gate) => {
+                        nor_gate.gate.circuit_element.set_state(comp_state.0)
+                    }
+                    ComposantsEnum::XnorGate(xnor_gate) => {
+                        xnor_gate.gate.circuit_element.set_state(comp_state.0)
+                    }
                     ComposantsEnum::NotGate(not_gate) => {
                         not_gate.gate.circuit_element.set_state(comp_state.0)
                     }
@@ -138,51 +847,101 @@ impl Simulation {
                         let new_state = timer_gate.check_stack_and_update();
                         is_something_different |= new_state.1;
                     }
-                    ComposantsEnum::Switch(_) => (),
+                    ComposantsEnum::InputPort(input_port) => {
+                        input_port.port.circuit_element.set_state(comp_state.0)
+                    }
+                    ComposantsEnum::OutputPort(output_port) => {
+                        output_port.port.circuit_element.set_state(comp_state.0)
+                    }
+                    ComposantsEnum::Clock(clock) => {
+                        clock.gate.circuit_element.set_state(comp_state.0)
+                    }
+                    ComposantsEnum::Constant(constant) => {
+                        constant.circuit_element.set_state(comp_state.0)
+                    }
+                    ComposantsEnum::Led(led) => led.gate.circuit_element.set_state(comp_state.0),
+                    ComposantsEnum::SevenSegment(seven_segment) => {
+                        seven_segment.gate.circuit_element.set_state(comp_state.0)
+                    }
+                    ComposantsEnum::Switch(_)
+                    | ComposantsEnum::Splitter(_)
+                    | ComposantsEnum::Merger(_)
+                    | ComposantsEnum::SubCircuit(_)
+                    | ComposantsEnum::FlipFlop(_)
+                    | ComposantsEnum::Rom(_)
+                    | ComposantsEnum::Ram(_)
+                    | ComposantsEnum::Register(_)
+                    | ComposantsEnum::Counter(_)
+                    | ComposantsEnum::ShiftRegister(_)
+                    | ComposantsEnum::Multiplexer(_)
+                    | ComposantsEnum::Demultiplexer(_)
+                    | ComposantsEnum::Decoder(_)
+                    | ComposantsEnum::PriorityEncoder(_)
+                    | ComposantsEnum::Adder(_)
+                    | ComposantsEnum::Comparator(_)
+                    | ComposantsEnum::Alu(_)
+                    | ComposantsEnum::Button(_)
+                    | ComposantsEnum::DipSwitch(_)
+                    | ComposantsEnum::PixelMatrix(_)
+                    | ComposantsEnum::Keyboard(_)
+                    | ComposantsEnum::Terminal(_)
+                    | ComposantsEnum::Pull(_)
+                    | ComposantsEnum::Tunnel(_) => (),
                 }
             }
         }
 
-        self.tick_counter += 1;
+        // Mise à jour des composants à plusieurs sorties
+        for (id, outputs) in pending.pin_states {
+            if let Some(gate) = self
+                .composants_map
+                .get_mut(&id)
+                .and_then(|composant| composant.multi_pin_gate_mut())
+            {
+                gate.set_outputs(outputs);
+            }
+        }
+
+        // Mise à jour des composants séquentiels
+        for (id, inputs) in pending.sampled_inputs {
+            match self.composants_map.get_mut(&id) {
+                Some(ComposantsEnum::FlipFlop(flip_flop)) => {
+                    is_something_different |= flip_flop.update_inputs(&inputs);
+                }
+                Some(ComposantsEnum::Ram(ram)) => {
+                    is_something_different |= ram.update_inputs(&inputs);
+                }
+                Some(ComposantsEnum::Register(register)) => {
+                    is_something_different |= register.update_inputs(&inputs);
+                }
+                Some(ComposantsEnum::Counter(counter)) => {
+                    is_something_different |= counter.update_inputs(&inputs);
+                }
+                Some(ComposantsEnum::ShiftRegister(register)) => {
+                    is_something_different |= register.update_inputs(&inputs);
+                }
+                Some(ComposantsEnum::PixelMatrix(pixel_matrix)) => {
+                    is_something_different |= pixel_matrix.update_inputs(&inputs);
+                }
+                Some(ComposantsEnum::Keyboard(keyboard)) => {
+                    is_something_different |= keyboard.update_inputs(&inputs);
+                }
+                Some(ComposantsEnum::Terminal(terminal)) => {
+                    is_something_different |= terminal.update_inputs(&inputs);
+                }
+                _ => (),
+            }
+        }
+
         is_something_different
     }
 
     fn create_old_components_copy(&self) -> Vec<OldComponents> {
         self.composants_map
             .values()
-            .map(|composant| match composant {
-                ComposantsEnum::OrGate(or_gate) => OldComponents {
-                    id: or_gate.gate.circuit_element.id,
-                    state: or_gate.gate.circuit_element.state,
-                },
-                ComposantsEnum::AndGate(and_gate) => OldComponents {
-                    id: and_gate.gate.circuit_element.id,
-                    state: and_gate.gate.circuit_element.state,
-                },
-                ComposantsEnum::XorGate(xor_gate) => OldComponents {
-                    id: xor_gate.gate.circuit_element.id,
-                    state: xor_gate.gate.circuit_element.state,
-                },
-                ComposantsEnum::NotGate(not_gate) => OldComponents {
-                    id: not_gate.gate.circuit_element.id,
-                    state: not_gate.gate.circuit_element.state,
-                },
-                ComposantsEnum::BufferGate(buffer_gate) => OldComponents {
-                    id: buffer_gate.gate.circuit_element.id,
-                    state: buffer_gate.gate.circuit_element.state,
-                },
-                ComposantsEnum::LatchGate(latch_gate) => OldComponents {
-                    id: latch_gate.gate.circuit_element.id,
-                    state: latch_gate.gate.circuit_element.state,
-                },
-                ComposantsEnum::TimerGate(timer_gate) => OldComponents {
-                    id: timer_gate.gate.circuit_element.id,
-                    state: timer_gate.gate.circuit_element.state,
-                },
-                ComposantsEnum::Switch(switch) => OldComponents {
-                    id: switch.circuit_element.id,
-                    state: switch.circuit_element.state,
-                },
+            .map(|composant| OldComponents {
+                id: composant.circuit_element().id,
+                state: composant.circuit_element().state,
             })
             .collect()
     }
@@ -191,7 +950,6 @@ impl Simulation {
         self.wire_groups
             .iter()
             .map(|wire_group| OldWireGroup {
-                index: wire_group.circuit_element.id,
                 state: wire_group.circuit_element.state,
             })
             .collect()
@@ -211,6 +969,7 @@ impl Simulation {
                     Some(ChangedElement::new(
                         index,
                         wires_group.circuit_element.state,
+                        wires_group.circuit_element.width,
                     ))
                 } else {
                     None
@@ -222,12 +981,15 @@ impl Simulation {
             .iter()
             .flat_map(|changed_wire_group| {
                 let wire_group = &self.wire_groups[changed_wire_group.id];
-                let state = wire_group.circuit_element.state;
+                let (state, width) = (
+                    wire_group.circuit_element.state,
+                    wire_group.circuit_element.width,
+                );
 
                 wire_group
                     .wires
                     .iter()
-                    .map(move |wire| ChangedElement::new(wire.clone(), state))
+                    .map(move |wire| ChangedElement::new(*wire, state, width))
             })
             .collect()
     }
@@ -238,43 +1000,16 @@ impl Simulation {
     ) -> Vec<ChangedElement> {
         self.composants_map
             .values()
+            .filter(|composant| !self.internal_ids.contains(&composant.circuit_element().id))
             .filter_map(|composant| {
-                let (id, new_state) = match composant {
-                    ComposantsEnum::OrGate(or_gate) => (
-                        or_gate.gate.circuit_element.id,
-                        or_gate.gate.circuit_element.state,
-                    ),
-                    ComposantsEnum::AndGate(and_gate) => (
-                        and_gate.gate.circuit_element.id,
-                        and_gate.gate.circuit_element.state,
-                    ),
-                    ComposantsEnum::XorGate(xor_gate) => (
-                        xor_gate.gate.circuit_element.id,
-                        xor_gate.gate.circuit_element.state,
-                    ),
-                    ComposantsEnum::NotGate(not_gate) => (
-                        not_gate.gate.circuit_element.id,
-                        not_gate.gate.circuit_element.state,
-                    ),
-                    ComposantsEnum::BufferGate(buffer_gate) => (
-                        buffer_gate.gate.circuit_element.id,
-                        buffer_gate.gate.circuit_element.state,
-                    ),
-                    ComposantsEnum::LatchGate(latch_gate) => (
-                        latch_gate.gate.circuit_element.id,
-                        latch_gate.gate.circuit_element.state,
-                    ),
-                    ComposantsEnum::TimerGate(timer_gate) => (
-                        timer_gate.gate.circuit_element.id,
-                        timer_gate.gate.circuit_element.state,
-                    ),
-                    ComposantsEnum::Switch(switch) => {
-                        (switch.circuit_element.id, switch.circuit_element.state)
-                    }
-                };
+                let (id, new_state, width) = (
+                    composant.circuit_element().id,
+                    composant.circuit_element().state,
+                    composant.circuit_element().width,
+                );
                 if let Some(old_component) = old_components.iter().find(|c| c.id == id) {
                     if new_state != old_component.state {
-                        return Some(ChangedElement::new(id, new_state));
+                        return Some(ChangedElement::new(id, new_state, width));
                     }
                 }
                 None
@@ -282,25 +1017,74 @@ impl Simulation {
             .collect()
     }
 
-    fn run_until_stabilizes(&mut self, max_depth: u32) {
-        let mut counter: u32 = 0;
-
-        while counter < max_depth {
-            counter += 1;
+    fn create_changed_displays(
+        &self,
+        changed_components: &[ChangedElement],
+    ) -> Vec<ChangedDisplay> {
+        changed_components
+            .iter()
+            .filter_map(|changed| {
+                let data = match self.composants_map.get(&changed.id)? {
+                    ComposantsEnum::Led(led) => led.display_data(),
+                    ComposantsEnum::SevenSegment(seven_segment) => seven_segment.display_data(),
+                    ComposantsEnum::PixelMatrix(pixel_matrix) => pixel_matrix.display_data(),
+                    _ => return None,
+                };
+                Some(ChangedDisplay {
+                    id: changed.id,
+                    data,
+                })
+            })
+            .collect()
+    }
 
-            let is_something_different = self.tick();
-            if !is_something_different {
-                break;
-            }
+    fn advance_clocks(&mut self) {
+        if self.clocks_running {
+            self.clock_tick = self.clock_tick.wrapping_add(1);
+        } else if self.clock_steps > 0 {
+            self.clock_steps -= 1;
+            self.clock_tick = self.clock_tick.wrapping_add(1);
         }
+    }
 
+    fn run_until_stabilizes(&mut self, max_depth: u32) {
+        let mut counter: u32 = 0;
+
+        let step_counter = self.step_counter;
         self.composants_map.values_mut().for_each(|composant| {
-            if let ComposantsEnum::TimerGate(timer_gate) = composant {
-                timer_gate.decrement_ticks();
+            if let ComposantsEnum::Button(button) = composant {
+                button.apply_events(step_counter);
             }
         });
 
-        if counter == max_depth {
+        let reached_max_depth = if self.timing.enabled {
+            self.run_timed(max_depth)
+        } else {
+            while counter < max_depth {
+                counter += 1;
+
+                let is_something_different = self.tick();
+                self.record_waveform();
+                self.timing.time += 1;
+                if !is_something_different {
+                    break;
+                }
+            }
+            counter == max_depth
+        };
+
+        self.composants_map
+            .values_mut()
+            .for_each(|composant| match composant {
+                ComposantsEnum::TimerGate(timer_gate) => timer_gate.decrement_ticks(),
+                ComposantsEnum::Button(button) => button.finish_step(),
+                _ => (),
+            });
+
+        self.step_counter += 1;
+        self.advance_clocks();
+
+        if reached_max_depth {
             console_log("!!!!!!!!! Reached max_depth !!!!!!!!!!!!");
         }
     }
@@ -317,14 +1101,16 @@ impl Simulation {
         let changed_components: Vec<ChangedElement> =
             self.create_changed_components_copy(old_components);
 
-        TickResults::new(changed_wires, changed_components)
+        let changed_displays = self.create_changed_displays(&changed_components);
+
+        TickResults::new(changed_wires, changed_components).with_displays(changed_displays)
     }
 
     pub fn update_switch_state(&mut self, component_index: usize, state: bool) {
         if let Some(composant) = self.composants_map.get_mut(&component_index) {
             match composant {
                 ComposantsEnum::Switch(switch) => {
-                    switch.circuit_element.set_state(state);
+                    switch.circuit_element.set_state(state as u64);
                 }
                 _ => console_log("Invalid gate index"),
             }
@@ -338,6 +1124,187 @@ impl Simulation {
         self.wires_map.clear();
         self.wire_set.clear();
         self.wire_groups.clear();
+        self.internal_ids.clear();
+        self.junctions.clear();
+        self.faults.clear();
+        self.properties.clear();
+        self.timing.clear_events();
+        self.timing.component_delays.clear();
+        self.timing.probes.clear();
+        self.timing.waveform.clear();
+        self.timing.time = 0;
         self.tick_counter = 0;
+        self.step_counter = 0;
+        self.clock_tick = 0;
+        self.clocks_running = true;
+        self.clock_steps = 0;
+        // Les définitions de sous-circuits sont conservées d'un reset à l'autre
+    }
+
+    pub fn get_tick_counter(&self) -> u32 {
+        self.tick_counter
+    }
+
+    pub fn get_step_counter(&self) -> u32 {
+        self.step_counter
+    }
+
+    pub fn pause_clocks(&mut self) {
+        self.clocks_running = false;
+    }
+
+    pub fn resume_clocks(&mut self) {
+        self.clocks_running = true;
+        self.clock_steps = 0;
+    }
+
+    // Avance les horloges en pause d'un tick lors du prochain tick simulé
+    pub fn step_clocks(&mut self) {
+        self.clock_steps += 1;
+    }
+
+    pub fn are_clocks_running(&self) -> bool {
+        self.clocks_running
+    }
+
+    pub fn get_component_state(&self, component_index: usize) -> u64 {
+        self.component_state(component_index)
+    }
+
+    // Valeur mémorisée par un registre, un compteur ou un registre à décalage
+    pub fn get_register_value(&self, component_index: usize) -> Option<u64> {
+        match self.composants_map.get(&component_index) {
+            Some(ComposantsEnum::Register(register)) => Some(register.value),
+            Some(ComposantsEnum::Counter(counter)) => Some(counter.value),
+            Some(ComposantsEnum::ShiftRegister(register)) => Some(register.value),
+            _ => None,
+        }
+    }
+
+    // La sortie est mise à jour au tick suivant
+    pub fn set_register_value(&mut self, component_index: usize, value: u64) {
+        match self.composants_map.get_mut(&component_index) {
+            Some(ComposantsEnum::Register(register)) => register.set_value(value),
+            Some(ComposantsEnum::Counter(counter)) => counter.set_value(value),
+            Some(ComposantsEnum::ShiftRegister(register)) => register.set_value(value),
+            _ => console_log("Invalid register index"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::Orientation;
+    use crate::wire::Wire;
+    use crate::Simulation;
+
+    const ROWS: [i32; 4] = [3, 1, -1, -3];
+
+    // Switches 1 à 4 -> merger 5 -> bus (wire 24) -> splitter 6 -> LEDs 7 à 10
+    fn bus_circuit() -> Simulation {
+        let mut sim = Simulation::new();
+        sim.add_merger(vec![0, 0], 4, Orientation::Right, 5);
+        sim.add_splitter(vec![15, 0], 4, Orientation::Right, 6);
+        for (bit, y) in ROWS.into_iter().enumerate() {
+            sim.add_switch(vec![-10, y], 1 + bit);
+            sim.add_led(vec![20, y], 0, Orientation::Right, 7 + bit);
+            sim.wires_map
+                .insert(20 + bit, Wire::new(20 + bit, vec![[-10, y], [-5, y]]));
+            sim.wires_map
+                .insert(30 + bit, Wire::new(30 + bit, vec![[15, y], [20, y]]));
+        }
+        sim.wires_map
+            .insert(24, Wire::new_bus(24, vec![[0, 0], [10, 0]], 4));
+        sim.compute_connections();
+        sim
+    }
+
+    fn bus_group(sim: &Simulation) -> usize {
+        sim.wire_groups
+            .iter()
+            .position(|group| group.wires.contains(&24))
+            .unwrap()
+    }
+
+    #[test]
+    fn bus_round_trips_through_merger_and_splitter() {
+        let mut sim = bus_circuit();
+        for value in [0b1010, 0b0101, 0b1111, 0] {
+            for bit in 0..4 {
+                sim.update_switch_state(1 + bit, (value >> bit) & 1 == 1);
+            }
+            sim.compute_frame(100, 1);
+            assert_eq!(sim.wire_group_state(bus_group(&sim)), value);
+            let leds: Vec<u64> = (7..11).map(|id| sim.get_component_state(id)).collect();
+            let bits: Vec<u64> = (0..4).map(|bit| (value >> bit) & 1).collect();
+            assert_eq!(leds, bits);
+        }
+    }
+
+    #[test]
+    fn changed_wires_carry_the_bus_value() {
+        let mut sim = bus_circuit();
+        let old = sim.create_old_wires_group_copy();
+        sim.update_switch_state(2, true);
+        sim.update_switch_state(4, true);
+        sim.compute_frame(100, 1);
+        let changed = sim.create_changed_wires_copy(old);
+        let bus = changed.iter().find(|element| element.id == 24).unwrap();
+        assert!(bus.state);
+        assert_eq!(bus.value(), 10.0);
+        assert_eq!(bus.width(), 4);
+        assert!(changed.iter().any(|element| element.id == 31));
+        assert!(!changed.iter().any(|element| element.id == 30));
+    }
+
+    fn clock_levels(sim: &mut Simulation, id: usize, frames: usize) -> Vec<u64> {
+        (0..frames)
+            .map(|_| {
+                sim.compute_frame(100, 1);
+                sim.get_component_state(id)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn clocks_follow_their_period_and_phase() {
+        let mut sim = Simulation::new();
+        sim.add_clock(vec![0, 0], 2, 1, 0, Orientation::Right, 1);
+        sim.add_clock(vec![0, 10], 2, 1, 1, Orientation::Right, 2);
+        sim.compute_connections();
+        let mut levels = Vec::new();
+        for _ in 0..6 {
+            sim.compute_frame(100, 1);
+            levels.push((sim.get_component_state(1), sim.get_component_state(2)));
+        }
+        assert_eq!(levels, vec![(1, 1), (1, 0), (0, 1), (1, 1), (1, 0), (0, 1)]);
+    }
+
+    #[test]
+    fn paused_clocks_only_advance_when_stepped() {
+        let mut sim = Simulation::new();
+        sim.add_clock(vec![0, 0], 1, 1, 0, Orientation::Right, 1);
+        sim.compute_connections();
+        assert_eq!(clock_levels(&mut sim, 1, 2), vec![1, 0]);
+        sim.pause_clocks();
+        assert!(!sim.are_clocks_running());
+        assert_eq!(clock_levels(&mut sim, 1, 3), vec![1, 1, 1]);
+        sim.step_clocks();
+        assert_eq!(clock_levels(&mut sim, 1, 2), vec![1, 0]);
+        sim.resume_clocks();
+        assert_eq!(clock_levels(&mut sim, 1, 3), vec![0, 1, 0]);
+    }
+
+    #[test]
+    fn clock_enable_input_holds_it_low() {
+        let mut sim = Simulation::new();
+        sim.add_switch(vec![-10, 0], 1);
+        sim.add_clock(vec![0, 0], 1, 1, 0, Orientation::Right, 2);
+        sim.wires_map
+            .insert(10, Wire::new(10, vec![[-10, 0], [-5, 0]]));
+        sim.compute_connections();
+        assert_eq!(clock_levels(&mut sim, 2, 2), vec![0, 0]);
+        sim.update_switch_state(1, true);
+        assert_eq!(clock_levels(&mut sim, 2, 2), vec![1, 0]);
     }
 }
@@ -1,6 +1,10 @@
 use crate::components::*;
-use crate::types::Orientation;
 use crate::types::Position;
+use crate::types::{
+    ClockEdge, FlipFlopKind, LogicGateKind, Orientation, PixelMatrixMode, PullKind,
+    SevenSegmentMode, ShiftRegisterMode,
+};
+use crate::utils::{console_log, MAX_WIDTH};
 use crate::wire::Wire;
 use crate::ComposantsEnum;
 use crate::Simulation;
@@ -10,8 +14,8 @@ use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen]
 impl Simulation {
-    pub fn add_wire(&mut self, positions: Array, id: usize) -> usize {
-        let positions_vec: Vec<Position> = positions
+    pub(crate) fn positions_from_js(positions: Array) -> Vec<Position> {
+        positions
             .iter()
             .map(|js_val| {
                 let tuple = js_val.dyn_into::<Array>().unwrap();
@@ -20,12 +24,41 @@ impl Simulation {
                     tuple.get(1).as_f64().unwrap() as i32,
                 ]
             })
-            .collect();
-        let wire = Wire::new(id, positions_vec);
+            .collect()
+    }
+
+    fn clamp_width(width: u8) -> u8 {
+        if width == 0 || width > MAX_WIDTH {
+            console_log(&format!("Invalid bus width {}", width));
+        }
+        width.clamp(1, MAX_WIDTH)
+    }
+
+    pub fn add_wire(&mut self, positions: Array, id: usize) -> usize {
+        let wire = Wire::new(id, Self::positions_from_js(positions));
+        self.wires_map.insert(id, wire);
+        id
+    }
+
+    pub fn add_bus_wire(&mut self, positions: Array, width: u8, id: usize) -> usize {
+        let wire = Wire::new_bus(
+            id,
+            Self::positions_from_js(positions),
+            Self::clamp_width(width),
+        );
         self.wires_map.insert(id, wire);
         id
     }
 
+    // Point de jonction reliant les wires qui y passent, en mode `ConnectionMode::Endpoints`
+    pub fn add_junction(&mut self, position: Vec<i32>) {
+        self.junctions.insert([position[0], position[1]]);
+    }
+
+    pub fn remove_junction(&mut self, position: Vec<i32>) -> bool {
+        self.junctions.remove(&[position[0], position[1]])
+    }
+
     pub fn add_and_gate(
         &mut self,
         position: Vec<i32>,
@@ -34,7 +67,7 @@ impl Simulation {
     ) -> usize {
         let position = [position[0], position[1]];
         let and_gate = AndGate {
-            gate: TwoInputsGate::new(id, position, orientation),
+            gate: MultiInputsGate::new(id, position, orientation, 2),
         };
         self.composants_map
             .insert(id, ComposantsEnum::AndGate(and_gate));
@@ -49,7 +82,7 @@ impl Simulation {
     ) -> usize {
         let position = [position[0], position[1]];
         let or_gate = OrGate {
-            gate: TwoInputsGate::new(id, position, orientation),
+            gate: MultiInputsGate::new(id, position, orientation, 2),
         };
         self.composants_map
             .insert(id, ComposantsEnum::OrGate(or_gate));
@@ -64,13 +97,66 @@ impl Simulation {
     ) -> usize {
         let position = [position[0], position[1]];
         let xor_gate = XorGate {
-            gate: TwoInputsGate::new(id, position, orientation),
+            gate: MultiInputsGate::new(id, position, orientation, 2),
         };
         self.composants_map
             .insert(id, ComposantsEnum::XorGate(xor_gate));
         id
     }
 
+    pub fn add_nand_gate(
+        &mut self,
+        position: Vec<i32>,
+        orientation: Orientation,
+        id: usize,
+    ) -> usize {
+        self.add_logic_gate(position, LogicGateKind::Nand, 2, orientation, id)
+    }
+
+    pub fn add_nor_gate(
+        &mut self,
+        position: Vec<i32>,
+        orientation: Orientation,
+        id: usize,
+    ) -> usize {
+        self.add_logic_gate(position, LogicGateKind::Nor, 2, orientation, id)
+    }
+
+    pub fn add_xnor_gate(
+        &mut self,
+        position: Vec<i32>,
+        orientation: Orientation,
+        id: usize,
+    ) -> usize {
+        self.add_logic_gate(position, LogicGateKind::Xnor, 2, orientation, id)
+    }
+
+    // Porte à `inputs` entrées (2 à 16), placées selon l'orientation
+    pub fn add_logic_gate(
+        &mut self,
+        position: Vec<i32>,
+        kind: LogicGateKind,
+        inputs: u8,
+        orientation: Orientation,
+        id: usize,
+    ) -> usize {
+        let position = [position[0], position[1]];
+        if !(MultiInputsGate::MIN_INPUTS..=MultiInputsGate::MAX_INPUTS).contains(&inputs) {
+            console_log(&format!("Invalid gate inputs count {}", inputs));
+        }
+        let gate = MultiInputsGate::new(id, position, orientation, inputs);
+        let composant = match kind {
+            LogicGateKind::And => ComposantsEnum::AndGate(AndGate { gate }),
+            LogicGateKind::Or => ComposantsEnum::OrGate(OrGate { gate }),
+            LogicGateKind::Xor => ComposantsEnum::XorGate(XorGate { gate }),
+            LogicGateKind::Nand => ComposantsEnum::NandGate(NandGate { gate }),
+            LogicGateKind::Nor => ComposantsEnum::NorGate(NorGate { gate }),
+            LogicGateKind::Xnor => ComposantsEnum::XnorGate(XnorGate { gate }),
+        };
+        self.composants_map.insert(id, composant);
+        id
+    }
+
     pub fn add_not_gate(
         &mut self,
         position: Vec<i32>,
@@ -116,6 +202,196 @@ impl Simulation {
         id
     }
 
+    pub fn add_flip_flop(
+        &mut self,
+        position: Vec<i32>,
+        kind: FlipFlopKind,
+        edge: ClockEdge,
+        orientation: Orientation,
+        id: usize,
+    ) -> usize {
+        let position = [position[0], position[1]];
+        let flip_flop = FlipFlop::new(id, position, orientation, kind, edge);
+        self.composants_map
+            .insert(id, ComposantsEnum::FlipFlop(flip_flop));
+        id
+    }
+
+    pub fn add_rom(
+        &mut self,
+        position: Vec<i32>,
+        address_bits: u8,
+        data_bits: u8,
+        bus_pins: bool,
+        orientation: Orientation,
+        id: usize,
+    ) -> usize {
+        let position = [position[0], position[1]];
+        let memory = MemoryBank::new(address_bits, Self::clamp_width(data_bits), bus_pins);
+        let rom = Rom::new(id, position, orientation, memory);
+        self.composants_map.insert(id, ComposantsEnum::Rom(rom));
+        id
+    }
+
+    pub fn add_ram(
+        &mut self,
+        position: Vec<i32>,
+        address_bits: u8,
+        data_bits: u8,
+        bus_pins: bool,
+        orientation: Orientation,
+        id: usize,
+    ) -> usize {
+        let position = [position[0], position[1]];
+        let memory = MemoryBank::new(address_bits, Self::clamp_width(data_bits), bus_pins);
+        let ram = Ram::new(id, position, orientation, memory);
+        self.composants_map.insert(id, ComposantsEnum::Ram(ram));
+        id
+    }
+
+    pub fn add_register(
+        &mut self,
+        position: Vec<i32>,
+        bits: u8,
+        orientation: Orientation,
+        id: usize,
+    ) -> usize {
+        let position = [position[0], position[1]];
+        let register = Register::new(id, position, orientation, Self::clamp_width(bits));
+        self.composants_map
+            .insert(id, ComposantsEnum::Register(register));
+        id
+    }
+
+    pub fn add_counter(
+        &mut self,
+        position: Vec<i32>,
+        bits: u8,
+        wrap: bool,
+        orientation: Orientation,
+        id: usize,
+    ) -> usize {
+        let position = [position[0], position[1]];
+        let counter = Counter::new(id, position, orientation, Self::clamp_width(bits), wrap);
+        self.composants_map
+            .insert(id, ComposantsEnum::Counter(counter));
+        id
+    }
+
+    pub fn add_shift_register(
+        &mut self,
+        position: Vec<i32>,
+        bits: u8,
+        mode: ShiftRegisterMode,
+        orientation: Orientation,
+        id: usize,
+    ) -> usize {
+        let position = [position[0], position[1]];
+        let shift_register =
+            ShiftRegister::new(id, position, orientation, Self::clamp_width(bits), mode);
+        self.composants_map
+            .insert(id, ComposantsEnum::ShiftRegister(shift_register));
+        id
+    }
+
+    pub fn add_multiplexer(
+        &mut self,
+        position: Vec<i32>,
+        select_bits: u8,
+        orientation: Orientation,
+        id: usize,
+    ) -> usize {
+        let position = [position[0], position[1]];
+        let multiplexer = Multiplexer::new(id, position, orientation, select_bits);
+        self.composants_map
+            .insert(id, ComposantsEnum::Multiplexer(multiplexer));
+        id
+    }
+
+    pub fn add_demultiplexer(
+        &mut self,
+        position: Vec<i32>,
+        select_bits: u8,
+        orientation: Orientation,
+        id: usize,
+    ) -> usize {
+        let position = [position[0], position[1]];
+        let demultiplexer = Demultiplexer::new(id, position, orientation, select_bits);
+        self.composants_map
+            .insert(id, ComposantsEnum::Demultiplexer(demultiplexer));
+        id
+    }
+
+    pub fn add_decoder(
+        &mut self,
+        position: Vec<i32>,
+        select_bits: u8,
+        orientation: Orientation,
+        id: usize,
+    ) -> usize {
+        let position = [position[0], position[1]];
+        let decoder = Decoder::new(id, position, orientation, select_bits);
+        self.composants_map
+            .insert(id, ComposantsEnum::Decoder(decoder));
+        id
+    }
+
+    pub fn add_priority_encoder(
+        &mut self,
+        position: Vec<i32>,
+        select_bits: u8,
+        orientation: Orientation,
+        id: usize,
+    ) -> usize {
+        let position = [position[0], position[1]];
+        let priority_encoder = PriorityEncoder::new(id, position, orientation, select_bits);
+        self.composants_map
+            .insert(id, ComposantsEnum::PriorityEncoder(priority_encoder));
+        id
+    }
+
+    pub fn add_adder(
+        &mut self,
+        position: Vec<i32>,
+        bits: u8,
+        orientation: Orientation,
+        id: usize,
+    ) -> usize {
+        let position = [position[0], position[1]];
+        let adder = Adder::new(id, position, orientation, Self::clamp_width(bits));
+        self.composants_map.insert(id, ComposantsEnum::Adder(adder));
+        id
+    }
+
+    pub fn add_comparator(
+        &mut self,
+        position: Vec<i32>,
+        bits: u8,
+        signed: bool,
+        orientation: Orientation,
+        id: usize,
+    ) -> usize {
+        let position = [position[0], position[1]];
+        let comparator =
+            Comparator::new(id, position, orientation, Self::clamp_width(bits), signed);
+        self.composants_map
+            .insert(id, ComposantsEnum::Comparator(comparator));
+        id
+    }
+
+    pub fn add_alu(
+        &mut self,
+        position: Vec<i32>,
+        bits: u8,
+        orientation: Orientation,
+        id: usize,
+    ) -> usize {
+        let position = [position[0], position[1]];
+        let alu = Alu::new(id, position, orientation, Self::clamp_width(bits));
+        self.composants_map.insert(id, ComposantsEnum::Alu(alu));
+        id
+    }
+
     pub fn add_timer(
         &mut self,
         position: Vec<i32>,
@@ -130,6 +406,26 @@ impl Simulation {
         id
     }
 
+    pub fn add_clock(
+        &mut self,
+        position: Vec<i32>,
+        high_ticks: u32,
+        low_ticks: u32,
+        phase: u32,
+        orientation: Orientation,
+        id: usize,
+    ) -> usize {
+        let position = [position[0], position[1]];
+        let clock = Clock::new(
+            OneInputGate::new(id, position, orientation),
+            high_ticks,
+            low_ticks,
+            phase,
+        );
+        self.composants_map.insert(id, ComposantsEnum::Clock(clock));
+        id
+    }
+
     pub fn add_switch(&mut self, position: Vec<i32>, id: usize) -> usize {
         let position = [position[0], position[1]];
         let switch = Switch::new(id, position);
@@ -137,4 +433,212 @@ impl Simulation {
             .insert(id, ComposantsEnum::Switch(switch));
         id
     }
+
+    pub fn add_constant_high(&mut self, position: Vec<i32>, id: usize) -> usize {
+        self.add_constant(position, true, id)
+    }
+
+    pub fn add_constant_low(&mut self, position: Vec<i32>, id: usize) -> usize {
+        self.add_constant(position, false, id)
+    }
+
+    pub fn add_tunnel(&mut self, position: Vec<i32>, label: String, id: usize) -> usize {
+        let position = [position[0], position[1]];
+        let tunnel = Tunnel::new(id, position, label);
+        self.composants_map
+            .insert(id, ComposantsEnum::Tunnel(tunnel));
+        id
+    }
+
+    pub fn add_pull_up(&mut self, position: Vec<i32>, id: usize) -> usize {
+        self.add_pull(position, PullKind::Up, id)
+    }
+
+    pub fn add_pull_down(&mut self, position: Vec<i32>, id: usize) -> usize {
+        self.add_pull(position, PullKind::Down, id)
+    }
+
+    // `hold_ticks == 0` : la sortie reste haute tant que le bouton est appuyé
+    pub fn add_button(&mut self, position: Vec<i32>, hold_ticks: u32, id: usize) -> usize {
+        let position = [position[0], position[1]];
+        let button = Button::new(id, position, hold_ticks);
+        self.composants_map
+            .insert(id, ComposantsEnum::Button(button));
+        id
+    }
+
+    pub fn add_dip_switch(
+        &mut self,
+        position: Vec<i32>,
+        bits: u8,
+        radio: bool,
+        orientation: Orientation,
+        id: usize,
+    ) -> usize {
+        let position = [position[0], position[1]];
+        let dip_switch = DipSwitch::new(id, position, orientation, bits, radio);
+        self.composants_map
+            .insert(id, ComposantsEnum::DipSwitch(dip_switch));
+        id
+    }
+
+    pub fn add_led(
+        &mut self,
+        position: Vec<i32>,
+        color: u32,
+        orientation: Orientation,
+        id: usize,
+    ) -> usize {
+        let position = [position[0], position[1]];
+        let led = Led::new(id, position, orientation, color);
+        self.composants_map.insert(id, ComposantsEnum::Led(led));
+        id
+    }
+
+    pub fn add_seven_segment(
+        &mut self,
+        position: Vec<i32>,
+        mode: SevenSegmentMode,
+        orientation: Orientation,
+        id: usize,
+    ) -> usize {
+        let position = [position[0], position[1]];
+        let seven_segment = SevenSegment::new(id, position, orientation, mode);
+        self.composants_map
+            .insert(id, ComposantsEnum::SevenSegment(seven_segment));
+        id
+    }
+
+    pub fn add_pixel_matrix(
+        &mut self,
+        position: Vec<i32>,
+        rows: u8,
+        columns: u8,
+        mode: PixelMatrixMode,
+        orientation: Orientation,
+        id: usize,
+    ) -> usize {
+        let position = [position[0], position[1]];
+        let pixel_matrix = PixelMatrix::new(id, position, orientation, rows, columns, mode);
+        self.composants_map
+            .insert(id, ComposantsEnum::PixelMatrix(pixel_matrix));
+        id
+    }
+
+    pub fn add_keyboard(
+        &mut self,
+        position: Vec<i32>,
+        capacity: usize,
+        orientation: Orientation,
+        id: usize,
+    ) -> usize {
+        let position = [position[0], position[1]];
+        let keyboard = Keyboard::new(id, position, orientation, capacity);
+        self.composants_map
+            .insert(id, ComposantsEnum::Keyboard(keyboard));
+        id
+    }
+
+    pub fn add_terminal(
+        &mut self,
+        position: Vec<i32>,
+        orientation: Orientation,
+        id: usize,
+    ) -> usize {
+        let position = [position[0], position[1]];
+        let terminal = Terminal::new(id, position, orientation);
+        self.composants_map
+            .insert(id, ComposantsEnum::Terminal(terminal));
+        id
+    }
+
+    pub fn add_splitter(
+        &mut self,
+        position: Vec<i32>,
+        bits: u8,
+        orientation: Orientation,
+        id: usize,
+    ) -> usize {
+        let position = [position[0], position[1]];
+        let splitter = Splitter::new(id, position, orientation, Self::clamp_width(bits));
+        self.composants_map
+            .insert(id, ComposantsEnum::Splitter(splitter));
+        id
+    }
+
+    pub fn add_merger(
+        &mut self,
+        position: Vec<i32>,
+        bits: u8,
+        orientation: Orientation,
+        id: usize,
+    ) -> usize {
+        let position = [position[0], position[1]];
+        let merger = Merger::new(id, position, orientation, Self::clamp_width(bits));
+        self.composants_map
+            .insert(id, ComposantsEnum::Merger(merger));
+        id
+    }
+
+    pub fn add_input_port(&mut self, position: Vec<i32>, label: String, id: usize) -> usize {
+        let position = [position[0], position[1]];
+        let input_port = InputPort {
+            port: Port::new(id, position, label),
+        };
+        self.composants_map
+            .insert(id, ComposantsEnum::InputPort(input_port));
+        id
+    }
+
+    pub fn add_output_port(&mut self, position: Vec<i32>, label: String, id: usize) -> usize {
+        let position = [position[0], position[1]];
+        let output_port = OutputPort {
+            port: Port::new(id, position, label),
+        };
+        self.composants_map
+            .insert(id, ComposantsEnum::OutputPort(output_port));
+        id
+    }
+
+    pub fn add_sub_circuit(
+        &mut self,
+        position: Vec<i32>,
+        definition: String,
+        orientation: Orientation,
+        id: usize,
+    ) -> usize {
+        let position = [position[0], position[1]];
+        let Some(sub_circuit_definition) = self.sub_circuit_definitions.get(&definition) else {
+            console_log(&format!("Unknown sub circuit {}", definition));
+            return id;
+        };
+        let sub_circuit = SubCircuit::new(
+            id,
+            position,
+            orientation,
+            definition.clone(),
+            sub_circuit_definition.port_ids(true).len(),
+            sub_circuit_definition.port_ids(false).len(),
+        );
+        self.composants_map
+            .insert(id, ComposantsEnum::SubCircuit(sub_circuit));
+        id
+    }
+}
+
+impl Simulation {
+    fn add_constant(&mut self, position: Vec<i32>, high: bool, id: usize) -> usize {
+        let position = [position[0], position[1]];
+        let constant = Constant::new(id, position, high);
+        self.composants_map
+            .insert(id, ComposantsEnum::Constant(constant));
+        id
+    }
+
+    fn add_pull(&mut self, position: Vec<i32>, kind: PullKind, id: usize) -> usize {
+        let position = [position[0], position[1]];
+        let pull = Pull::new(id, position, kind);
+        self.composants_map.insert(id, ComposantsEnum::Pull(pull));
+        id
+    }
 }
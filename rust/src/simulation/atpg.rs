@@ -0,0 +1,347 @@
+use std::collections::HashMap;
+
+use crate::circuit_element::CircuitElementEnum;
+use crate::js::TestPatternReport;
+use crate::simulation::sat::{Lit, SatSolver};
+use crate::simulation::Fault;
+use crate::simulation::Observation;
+use crate::utils::console_log;
+use crate::{ComposantsEnum, Simulation};
+
+use wasm_bindgen::prelude::*;
+
+// Au-delà, la recherche d'un vecteur pour une faute est abandonnée
+pub const ATPG_CONFLICT_LIMIT: usize = 20_000;
+// Jusqu'à ce nombre d'entrées, la couverture minimale est choisie parmi tous les vecteurs
+pub const EXACT_COVER_MAX_INPUTS: usize = 10;
+
+enum PatternSearch {
+    Found(u64),
+    Untestable,
+    Aborted,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum FaultStatus {
+    Pending,
+    Detected,
+    Untestable,
+    Aborted,
+}
+
+#[wasm_bindgen]
+impl Simulation {
+    // Génère un jeu minimal de vecteurs pour les switches `inputs` (bit i → `inputs[i]`,
+    // tous les switches par id croissant si vide) détectant toutes les fautes de collage
+    // détectables sur `observed_wires` (entrées des afficheurs et ports de sortie si vide).
+    // Au-delà de `EXACT_COVER_MAX_INPUTS` entrées, ou si une recherche est abandonnée,
+    // le jeu n'est que compact et `is_minimal` est faux. Le circuit doit être
+    // combinatoire et au niveau porte
+    pub fn generate_test_patterns(
+        &self,
+        inputs: Vec<usize>,
+        observed_wires: Vec<usize>,
+    ) -> Option<TestPatternReport> {
+        let inputs = self.switch_inputs(inputs)?;
+        let groups = self.observed_groups(&observed_wires);
+        if groups.is_empty() {
+            console_log("No observation point");
+            return None;
+        }
+        if let Err(kind) = self.encode_circuit(&mut SatSolver::new(), &HashMap::new(), None) {
+            console_log(&format!("Unsupported component for ATPG: {}", kind));
+            return None;
+        }
+
+        let observations: Vec<Observation> = groups
+            .iter()
+            .map(|group| Observation::WireGroup(*group))
+            .collect();
+        let faults = self.collect_faults();
+        let mut status = vec![FaultStatus::Pending; faults.len()];
+        let mut vectors = Vec::new();
+        for index in 0..faults.len() {
+            if status[index] != FaultStatus::Pending {
+                continue;
+            }
+            let vector = match self.search_pattern(&faults[index], &inputs, &groups) {
+                PatternSearch::Found(vector) => vector,
+                PatternSearch::Untestable => {
+                    status[index] = FaultStatus::Untestable;
+                    continue;
+                }
+                PatternSearch::Aborted => {
+                    status[index] = FaultStatus::Aborted;
+                    continue;
+                }
+            };
+            // Le vecteur est confirmé par simulation et retire toutes les fautes qu'il détecte
+            let expected = self.fault_free_responses(&inputs, &[vector], &observations);
+            let mut is_useful = false;
+            for other in index..faults.len() {
+                if status[other] == FaultStatus::Pending
+                    && self.detects(&faults[other], &inputs, &[vector], &observations, &expected)
+                {
+                    status[other] = FaultStatus::Detected;
+                    is_useful = true;
+                }
+            }
+            if status[index] == FaultStatus::Pending {
+                status[index] = FaultStatus::Aborted;
+            }
+            if is_useful {
+                vectors.push(vector);
+            }
+        }
+
+        // Couverture minimale : le plus petit sous-ensemble des vecteurs candidats qui
+        // détecte toutes les fautes détectées. Les candidats sont tous les vecteurs quand
+        // les entrées sont peu nombreuses, sinon ceux trouvés ci-dessus
+        let candidates: Vec<u64> = if inputs.len() <= EXACT_COVER_MAX_INPUTS {
+            (0..1u64 << inputs.len()).collect()
+        } else {
+            vectors.clone()
+        };
+        let expected = self.fault_free_responses(&inputs, &candidates, &observations);
+        let detections: Vec<Vec<usize>> = faults
+            .iter()
+            .zip(&status)
+            .filter(|(_, status)| **status == FaultStatus::Detected)
+            .map(|(fault, _)| {
+                self.detecting_vectors(fault, &inputs, &candidates, &observations, &expected)
+            })
+            .collect();
+        let (kept, is_minimal) = match minimum_cover(&detections, candidates.len()) {
+            Some((cover, is_minimal)) => (
+                cover.into_iter().map(|index| candidates[index]).collect(),
+                is_minimal && inputs.len() <= EXACT_COVER_MAX_INPUTS,
+            ),
+            None => (vectors, false),
+        };
+
+        let mut detected = Vec::new();
+        let mut untestable = Vec::new();
+        let mut aborted = Vec::new();
+        for (fault, status) in faults.into_iter().zip(status) {
+            match status {
+                FaultStatus::Detected => detected.push(fault),
+                FaultStatus::Untestable => untestable.push(fault),
+                FaultStatus::Aborted | FaultStatus::Pending => aborted.push(fault),
+            }
+        }
+        Some(TestPatternReport::new(
+            inputs, kept, detected, untestable, aborted, is_minimal,
+        ))
+    }
+}
+
+// Plus petit ensemble de candidats couvrant chaque ligne de `detections` (indices des
+// candidats détectant une faute), par appels successifs au solveur avec une borne
+// décroissante sur la taille. Le booléen est faux si une recherche a été abandonnée
+fn minimum_cover(detections: &[Vec<usize>], candidate_count: usize) -> Option<(Vec<usize>, bool)> {
+    let mut best: Option<Vec<usize>> = None;
+    loop {
+        let limit = match &best {
+            Some(cover) if cover.is_empty() => return best.map(|cover| (cover, true)),
+            Some(cover) => cover.len() - 1,
+            None => candidate_count,
+        };
+        let mut solver = SatSolver::new();
+        let chosen: Vec<Lit> = (0..candidate_count).map(|_| solver.new_var()).collect();
+        for detection in detections {
+            let clause: Vec<Lit> = detection.iter().map(|index| chosen[*index]).collect();
+            solver.add_clause(&clause);
+        }
+        solver.at_most(&chosen, limit);
+        match solver.solve(ATPG_CONFLICT_LIMIT) {
+            Some(true) => {
+                best = Some(
+                    (0..candidate_count)
+                        .filter(|index| solver.model_value(chosen[*index]))
+                        .collect(),
+                );
+            }
+            Some(false) => return best.map(|cover| (cover, true)),
+            None => return best.map(|cover| (cover, false)),
+        }
+    }
+}
+
+impl Simulation {
+    // Wire groups des wires observés, ou ceux lus par les afficheurs et ports de sortie
+    pub(crate) fn observed_groups(&self, observed_wires: &[usize]) -> Vec<usize> {
+        let mut groups: Vec<usize> = if observed_wires.is_empty() {
+            self.composants_map
+                .iter()
+                .filter(|(id, composant)| match composant {
+                    ComposantsEnum::Led(_) | ComposantsEnum::SevenSegment(_) => true,
+                    ComposantsEnum::OutputPort(_) => !self.internal_ids.contains(id),
+                    _ => false,
+                })
+                .flat_map(|(_, composant)| match composant.multi_pin_gate() {
+                    Some(gate) => gate.input_groups.iter().flatten().copied().collect(),
+                    None => composant
+                        .circuit_element()
+                        .inputs
+                        .iter()
+                        .filter_map(|input| match input {
+                            CircuitElementEnum::WireGroup(id) => Some(*id),
+                            _ => None,
+                        })
+                        .collect::<Vec<usize>>(),
+                })
+                .collect()
+        } else {
+            observed_wires
+                .iter()
+                .filter_map(|wire| {
+                    self.wire_groups
+                        .iter()
+                        .position(|group| group.wires.contains(wire))
+                })
+                .collect()
+        };
+        groups.sort_unstable();
+        groups.dedup();
+        groups
+    }
+
+    // Cherche un vecteur distinguant le circuit sain du circuit fautif (miter)
+    fn search_pattern(&self, fault: &Fault, inputs: &[usize], groups: &[usize]) -> PatternSearch {
+        let mut solver = SatSolver::new();
+        let input_lits: Vec<Lit> = inputs.iter().map(|_| solver.new_var()).collect();
+        let sources = inputs
+            .iter()
+            .zip(&input_lits)
+            .map(|(id, lit)| ((*id, 0), vec![*lit]))
+            .collect();
+        let (Ok(good), Ok(faulty)) = (
+            self.encode_circuit(&mut solver, &sources, None),
+            self.encode_circuit(&mut solver, &sources, Some(fault)),
+        ) else {
+            return PatternSearch::Aborted;
+        };
+        let mut differences = Vec::new();
+        for &group in groups {
+            for (a, b) in good.group_bits[group].iter().zip(&faulty.group_bits[group]) {
+                differences.push(solver.xor_gate(*a, *b));
+            }
+        }
+        solver.add_clause(&differences);
+        match solver.solve(ATPG_CONFLICT_LIMIT) {
+            Some(true) => {
+                PatternSearch::Found(input_lits.iter().enumerate().fold(0, |acc, (bit, lit)| {
+                    acc | ((solver.model_value(*lit) as u64) << bit)
+                }))
+            }
+            Some(false) => PatternSearch::Untestable,
+            None => PatternSearch::Aborted,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PatternSearch;
+    use crate::simulation::FaultSite;
+    use crate::types::Orientation;
+    use crate::wire::Wire;
+    use crate::Simulation;
+
+    // `a | (a & b)` : switches 1 (a) et 2 (b), ET 3, OU 4 observé sur le wire 13.
+    // Par absorption, la sortie ne dépend pas de la porte ET
+    fn absorption_circuit() -> Simulation {
+        let mut sim = Simulation::new();
+        sim.add_switch(vec![0, 1], 1);
+        sim.add_switch(vec![0, -1], 2);
+        sim.add_and_gate(vec![10, 0], Orientation::Right, 3);
+        sim.add_or_gate(vec![20, 0], Orientation::Right, 4);
+        let wires = [
+            (10, vec![[0, 1], [5, 1]]),
+            (11, vec![[0, 1], [0, 5], [15, 5], [15, 1]]),
+            (12, vec![[0, -1], [5, -1]]),
+            (13, vec![[20, 0], [25, 0]]),
+            (14, vec![[10, 0], [12, 0], [12, -1], [15, -1]]),
+        ];
+        for (id, positions) in wires {
+            sim.wires_map.insert(id, Wire::new(id, positions));
+        }
+        sim.compute_connections();
+        sim
+    }
+
+    fn search(sim: &Simulation, site: FaultSite, stuck_at: bool) -> PatternSearch {
+        let fault = sim
+            .collect_faults()
+            .into_iter()
+            .find(|fault| fault.site == site && fault.stuck_at == stuck_at);
+        let groups = sim.observed_groups(&[13]);
+        match fault {
+            Some(fault) => sim.search_pattern(&fault, &[1, 2], &groups),
+            None => PatternSearch::Aborted,
+        }
+    }
+
+    #[test]
+    fn redundant_fault_is_untestable() {
+        let sim = absorption_circuit();
+        assert!(matches!(
+            search(&sim, FaultSite::Output(3, 0), false),
+            PatternSearch::Untestable
+        ));
+        // Collée à 1, la porte ET force la sortie quand `a = 0`
+        assert!(matches!(
+            search(&sim, FaultSite::Output(3, 0), true),
+            PatternSearch::Found(vector) if vector & 1 == 0
+        ));
+    }
+
+    #[test]
+    fn testable_fault_gets_a_detecting_vector() {
+        let sim = absorption_circuit();
+        // Seul `a = 1` met la sortie à 1
+        assert!(matches!(
+            search(&sim, FaultSite::Output(4, 0), false),
+            PatternSearch::Found(vector) if vector & 1 == 1
+        ));
+    }
+
+    // ET 5 sur les switches 1 et 2, OU 6 sur les switches 3 et 4, observés sur les
+    // wires 14 et 15. Trois vecteurs suffisent pour les deux portes à la fois
+    fn and_or_circuit() -> Simulation {
+        let mut sim = Simulation::new();
+        sim.add_switch(vec![0, 1], 1);
+        sim.add_switch(vec![0, -1], 2);
+        sim.add_switch(vec![0, 21], 3);
+        sim.add_switch(vec![0, 19], 4);
+        sim.add_and_gate(vec![10, 0], Orientation::Right, 5);
+        sim.add_or_gate(vec![10, 20], Orientation::Right, 6);
+        let wires = [
+            (10, vec![[0, 1], [5, 1]]),
+            (11, vec![[0, -1], [5, -1]]),
+            (12, vec![[0, 21], [5, 21]]),
+            (13, vec![[0, 19], [5, 19]]),
+            (14, vec![[10, 0], [15, 0]]),
+            (15, vec![[10, 20], [15, 20]]),
+        ];
+        for (id, positions) in wires {
+            sim.wires_map.insert(id, Wire::new(id, positions));
+        }
+        sim.compute_connections();
+        sim
+    }
+
+    #[test]
+    fn pattern_set_is_a_minimum_cover() {
+        let sim = and_or_circuit();
+        let report = sim
+            .generate_test_patterns(vec![1, 2, 3, 4], vec![14, 15])
+            .unwrap();
+        assert!(report.is_minimal());
+        assert_eq!(report.vectors().len(), 3);
+        assert_eq!(report.detected_count(), report.total());
+
+        let (_, undetected) = sim.simulate_faults(&[1, 2, 3, 4], &report.vectors(), &[14, 15]);
+        assert!(undetected.is_empty());
+    }
+}
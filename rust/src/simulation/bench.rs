@@ -0,0 +1,65 @@
+use crate::types::Position;
+use crate::wire::Wire;
+use crate::Simulation;
+
+// Les sources du banc prennent leurs ids à partir d'ici, au-dessus de ceux des tests
+const SOURCE_ID_BASE: usize = 1 << 20;
+
+// Branche une source (un switch dont l'état est écrit directement) sur chaque
+// position, via un wire d'un point de la largeur indiquée. Renvoie leurs ids
+pub(crate) fn drive_pins(sim: &mut Simulation, pins: &[(Position, u8)]) -> Vec<usize> {
+    let first_id = sim
+        .composants_map
+        .keys()
+        .filter(|id| (SOURCE_ID_BASE..SOURCE_ID_BASE * 2).contains(*id))
+        .max()
+        .map_or(SOURCE_ID_BASE, |id| id + 1);
+    pins.iter()
+        .enumerate()
+        .map(|(index, (position, width))| {
+            let id = first_id + index;
+            sim.add_switch(position.to_vec(), id);
+            sim.wires_map
+                .insert(id, Wire::new_bus(id, vec![*position], *width));
+            id
+        })
+        .collect()
+}
+
+// Une source par pin d'entrée d'un composant multi-pins ; les pins au-delà de
+// `widths` font un bit
+pub(crate) fn drive_inputs(sim: &mut Simulation, id: usize, widths: &[u8]) -> Vec<usize> {
+    let positions = sim
+        .composants_map
+        .get(&id)
+        .and_then(|composant| composant.multi_pin_gate())
+        .map(|gate| gate.input_positions.clone())
+        .unwrap_or_default();
+    let pins: Vec<(Position, u8)> = positions
+        .into_iter()
+        .enumerate()
+        .map(|(pin, position)| (position, widths.get(pin).copied().unwrap_or(1)))
+        .collect();
+    drive_pins(sim, &pins)
+}
+
+// Écrit les valeurs des sources puis laisse le circuit se stabiliser
+pub(crate) fn apply(sim: &mut Simulation, sources: &[usize], values: &[u64]) {
+    for (id, value) in sources.iter().zip(values) {
+        if let Some(composant) = sim.composants_map.get_mut(id) {
+            composant.circuit_element_mut().set_state(*value);
+        }
+    }
+    sim.compute_frame(100, 1);
+}
+
+pub(crate) fn outputs(sim: &Simulation, id: usize) -> Vec<u64> {
+    let count = sim
+        .composants_map
+        .get(&id)
+        .and_then(|composant| composant.multi_pin_gate())
+        .map_or(0, |gate| gate.output_positions.len());
+    (0..count)
+        .map(|pin| sim.component_pin_state(id, pin))
+        .collect()
+}
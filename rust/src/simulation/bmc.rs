@@ -0,0 +1,754 @@
+use std::collections::HashMap;
+
+use crate::circuit_element::CircuitElementEnum;
+use crate::components::FlipFlop;
+use crate::js::{ModelCheckResult, TickResults};
+use crate::simulation::cnf::{bit, bitwise, constant_bits, output_pins, Bits, CircuitCnf};
+use crate::simulation::sat::{Lit, SatSolver};
+use crate::types::{ClockEdge, FlipFlopKind};
+use crate::utils::console_log;
+use crate::{ComposantsEnum, Simulation};
+
+use wasm_bindgen::prelude::*;
+
+pub const MODEL_CHECK_CONFLICT_LIMIT: usize = 200_000;
+
+// Propriété vérifiée sur l'état stable atteint à chaque tick. Un wire est haut si
+// l'un de ses bits vaut 1
+#[derive(Clone)]
+pub enum Property {
+    // Les wires `high` ne sont jamais tous hauts pendant que les wires `low` sont tous bas
+    Never {
+        high: Vec<usize>,
+        low: Vec<usize>,
+    },
+    // Après chaque front montant de `trigger`, `response` est haut dans les `within` ticks
+    Response {
+        trigger: usize,
+        response: usize,
+        within: u32,
+    },
+}
+
+impl Property {
+    // Même propriété, exprimée sur les wire groups des wires
+    fn to_groups(&self, sim: &Simulation) -> Option<Property> {
+        let group = |wire: &usize| {
+            sim.wire_groups
+                .iter()
+                .position(|wire_group| wire_group.wires.contains(wire))
+        };
+        let property = match self {
+            Property::Never { high, low } => Property::Never {
+                high: high.iter().map(group).collect::<Option<_>>()?,
+                low: low.iter().map(group).collect::<Option<_>>()?,
+            },
+            Property::Response {
+                trigger,
+                response,
+                within,
+            } => Property::Response {
+                trigger: group(trigger)?,
+                response: group(response)?,
+                within: *within,
+            },
+        };
+        Some(property)
+    }
+
+    // Dernier tick de la première violation, à partir des états des wire groups
+    // avant le premier tick (`initial`) puis après chaque tick (`states`)
+    fn violation_step(&self, initial: &[u64], states: &[Vec<u64>]) -> Option<usize> {
+        match self {
+            Property::Never { high, low } => states.iter().position(|state| {
+                high.iter().all(|group| state[*group] != 0)
+                    && low.iter().all(|group| state[*group] == 0)
+            }),
+            Property::Response {
+                trigger,
+                response,
+                within,
+            } => {
+                let within = *within as usize;
+                (0..states.len().saturating_sub(within)).find_map(|step| {
+                    let previous = if step == 0 {
+                        initial
+                    } else {
+                        &states[step - 1]
+                    };
+                    let rises = states[step][*trigger] != 0 && previous[*trigger] == 0;
+                    let answered = (step..=step + within).any(|s| states[s][*response] != 0);
+                    (rises && !answered).then_some(step + within)
+                })
+            }
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Simulation {
+    // Renvoie l'index de la propriété
+    pub fn add_never_property(&mut self, high_wires: Vec<usize>, low_wires: Vec<usize>) -> usize {
+        self.properties.push(Property::Never {
+            high: high_wires,
+            low: low_wires,
+        });
+        self.properties.len() - 1
+    }
+
+    pub fn add_response_property(
+        &mut self,
+        trigger_wire: usize,
+        response_wire: usize,
+        within_ticks: u32,
+    ) -> usize {
+        self.properties.push(Property::Response {
+            trigger: trigger_wire,
+            response: response_wire,
+            within: within_ticks,
+        });
+        self.properties.len() - 1
+    }
+
+    pub fn clear_properties(&mut self) {
+        self.properties.clear();
+    }
+
+    // Cherche, sur `steps` ticks à partir de l'état courant, des valeurs des switches
+    // `inputs` (tous si vide) violant une propriété. Les propriétés sont vérifiées
+    // dans l'ordre ; le contre-exemple se rejoue avec `replay_step`
+    pub fn check_properties(&self, steps: u32, inputs: Vec<usize>) -> Option<ModelCheckResult> {
+        let inputs = self.switch_inputs(inputs)?;
+        let mut properties = Vec::with_capacity(self.properties.len());
+        for property in &self.properties {
+            let Some(property) = property.to_groups(self) else {
+                console_log("Invalid wire index in property");
+                return None;
+            };
+            properties.push(property);
+        }
+
+        for (index, property) in properties.iter().enumerate() {
+            // Profondeur croissante : le premier contre-exemple trouvé est le plus court
+            for depth in 1..=steps as usize {
+                let mut solver = SatSolver::new();
+                let (frames, input_lits) = match self.unroll(&mut solver, &inputs, depth) {
+                    Ok(unrolling) => unrolling,
+                    Err(kind) => {
+                        console_log(&format!(
+                            "Unsupported component for model checking: {}",
+                            kind
+                        ));
+                        return None;
+                    }
+                };
+                let Some(violation) = self.encode_violation(&mut solver, property, &frames) else {
+                    continue;
+                };
+                solver.add_clause(&[violation]);
+                match solver.solve(MODEL_CHECK_CONFLICT_LIMIT) {
+                    Some(false) => continue,
+                    None => {
+                        console_log("Model checking aborted");
+                        return None;
+                    }
+                    Some(true) => (),
+                }
+
+                let mut trace: Vec<u64> = input_lits
+                    .iter()
+                    .map(|lits| {
+                        lits.iter().enumerate().fold(0, |acc, (bit, lit)| {
+                            acc | ((solver.model_value(*lit) as u64) << bit)
+                        })
+                    })
+                    .collect();
+                // Le contre-exemple est confirmé en le rejouant dans le simulateur
+                let (initial, states) = self.replay_trace(&inputs, &trace);
+                let step = property.violation_step(&initial, &states);
+                match step {
+                    Some(step) => trace.truncate(step + 1),
+                    None => console_log("Counterexample could not be replayed"),
+                }
+                return Some(ModelCheckResult::new(
+                    Some(index),
+                    inputs,
+                    trace,
+                    step.is_some(),
+                    steps,
+                ));
+            }
+        }
+        Some(ModelCheckResult::new(None, inputs, Vec::new(), true, steps))
+    }
+
+    // Rejoue un tick d'une trace : bit i de `vector` → switch `inputs[i]`
+    pub fn replay_step(
+        &mut self,
+        inputs: Vec<usize>,
+        vector: u64,
+        max_depth: u32,
+    ) -> Option<TickResults> {
+        let inputs = self.switch_inputs(inputs)?;
+        self.set_switches(&inputs, vector);
+        Some(self.compute_frame(max_depth, 1))
+    }
+}
+
+impl Simulation {
+    fn group_states(&self) -> Vec<u64> {
+        (0..self.wire_groups.len())
+            .map(|group| self.wire_group_state(group))
+            .collect()
+    }
+
+    fn replay_trace(&self, inputs: &[usize], trace: &[u64]) -> (Vec<u64>, Vec<Vec<u64>>) {
+        let mut circuit = self.clone_state();
+        let initial = circuit.group_states();
+        let states = trace
+            .iter()
+            .map(|vector| {
+                circuit.apply_vector(inputs, *vector);
+                circuit.group_states()
+            })
+            .collect();
+        (initial, states)
+    }
+
+    // Ticks vus par les horloges pendant les `steps` prochains ticks
+    fn future_clock_ticks(&self, steps: usize) -> Vec<u32> {
+        let (mut clock_tick, mut clock_steps) = (self.clock_tick, self.clock_steps);
+        (0..steps)
+            .map(|_| {
+                let current = clock_tick;
+                if self.clocks_running {
+                    clock_tick = clock_tick.wrapping_add(1);
+                } else if clock_steps > 0 {
+                    clock_steps -= 1;
+                    clock_tick = clock_tick.wrapping_add(1);
+                }
+                current
+            })
+            .collect()
+    }
+
+    // Nombre de passes de `tick` déroulées par tick : deux par composant sur le plus
+    // long chemin (composant puis wire group), plus une marge pour les boucles
+    fn settle_passes(&self) -> usize {
+        let mut successors: HashMap<usize, Vec<usize>> = HashMap::new();
+        for group in &self.wire_groups {
+            let ids = |elements: &[CircuitElementEnum]| -> Vec<usize> {
+                elements
+                    .iter()
+                    .filter_map(|element| match element {
+                        CircuitElementEnum::Component(id)
+                        | CircuitElementEnum::ComponentPin(id, _) => Some(*id),
+                        CircuitElementEnum::WireGroup(_) => None,
+                    })
+                    .collect()
+            };
+            let readers = ids(&group.circuit_element.outputs);
+            for driver in ids(&group.circuit_element.inputs) {
+                successors
+                    .entry(driver)
+                    .or_default()
+                    .extend(readers.iter().copied());
+            }
+        }
+
+        fn depth(
+            id: usize,
+            successors: &HashMap<usize, Vec<usize>>,
+            depths: &mut HashMap<usize, usize>,
+            path: &mut Vec<usize>,
+        ) -> usize {
+            if let Some(depth) = depths.get(&id) {
+                return *depth;
+            }
+            path.push(id);
+            let mut longest = 0;
+            for next in successors.get(&id).into_iter().flatten() {
+                if !path.contains(next) {
+                    longest = longest.max(depth(*next, successors, depths, path));
+                }
+            }
+            path.pop();
+            depths.insert(id, longest + 1);
+            longest + 1
+        }
+
+        let mut ids: Vec<usize> = self.composants_map.keys().copied().collect();
+        ids.sort_unstable();
+        let mut depths = HashMap::new();
+        let longest = ids
+            .iter()
+            .map(|id| depth(*id, &successors, &mut depths, &mut Vec::new()))
+            .max()
+            .unwrap_or(0);
+        2 * longest + 4
+    }
+
+    // État courant encodé en constantes, et mémoire interne des composants séquentiels
+    fn initial_state(&self, solver: &mut SatSolver) -> (CircuitCnf, Memory) {
+        let group_bits = self
+            .wire_groups
+            .iter()
+            .enumerate()
+            .map(|(index, group)| {
+                constant_bits(
+                    solver,
+                    self.wire_group_state(index),
+                    group.circuit_element.width,
+                )
+            })
+            .collect();
+        let mut output_bits = HashMap::new();
+        let mut memory = HashMap::new();
+        for (id, composant) in &self.composants_map {
+            for (pin, width) in output_pins(composant) {
+                let state = match composant.multi_pin_gate() {
+                    Some(_) => self.component_pin_state(*id, pin),
+                    None => self.component_state(*id),
+                };
+                output_bits.insert((*id, pin), constant_bits(solver, state, width));
+            }
+            match composant {
+                ComposantsEnum::FlipFlop(flip_flop) => {
+                    memory.insert(*id, vec![solver.constant(flip_flop.last_clock)]);
+                }
+                ComposantsEnum::TimerGate(timer_gate) => {
+                    let width = timer_gate.gate.circuit_element.width;
+                    let input_state = constant_bits(solver, timer_gate.input_state, width);
+                    memory.insert(*id, input_state);
+                }
+                _ => (),
+            }
+        }
+        let state = CircuitCnf {
+            group_bits,
+            output_bits,
+        };
+        (state, memory)
+    }
+
+    // Déroule `steps` ticks de `settle_passes` passes chacun ; renvoie l'état à la fin
+    // de chaque tick et les littéraux des switches
+    fn unroll(
+        &self,
+        solver: &mut SatSolver,
+        inputs: &[usize],
+        steps: usize,
+    ) -> Result<(Vec<CircuitCnf>, Vec<Vec<Lit>>), &'static str> {
+        let passes = self.settle_passes();
+        let (mut state, mut memory) = self.initial_state(solver);
+        let mut frames: Vec<CircuitCnf> = Vec::with_capacity(steps);
+        let mut input_lits = Vec::with_capacity(steps);
+
+        for (step, clock_tick) in self.future_clock_ticks(steps).into_iter().enumerate() {
+            let lits: Vec<Lit> = inputs.iter().map(|_| solver.new_var()).collect();
+            for (id, lit) in inputs.iter().zip(&lits) {
+                state.output_bits.insert((*id, 0), vec![*lit]);
+            }
+            let timers = self.timer_values(solver, step, &frames, &state);
+            let context = PassContext {
+                clock_tick,
+                timers: &timers,
+            };
+            for _ in 0..passes {
+                state = self.next_pass(solver, &state, &mut memory, &context)?;
+            }
+            frames.push(state.clone());
+            input_lits.push(lits);
+        }
+        Ok((frames, input_lits))
+    }
+
+    // Sortie des temporisations à délai non nul pendant le tick `step` : la valeur
+    // qu'avait leur entrée `ticks` ticks plus tôt, si elle avait changé
+    fn timer_values(
+        &self,
+        solver: &mut SatSolver,
+        step: usize,
+        frames: &[CircuitCnf],
+        state: &CircuitCnf,
+    ) -> HashMap<usize, Bits> {
+        let mut values = HashMap::new();
+        for (id, composant) in &self.composants_map {
+            let ComposantsEnum::TimerGate(timer_gate) = composant else {
+                continue;
+            };
+            let Some(previous) = state.output_bits.get(&(*id, 0)) else {
+                continue;
+            };
+            let delay = timer_gate.ticks as usize;
+            if delay == 0 {
+                continue;
+            }
+            let width = previous.len() as u8;
+            let inputs = &timer_gate.gate.circuit_element.inputs;
+            let value = match step.checked_sub(delay) {
+                None => {
+                    let pending = timer_gate
+                        .stack
+                        .iter()
+                        .rfind(|(ticks, _)| *ticks as usize <= step)
+                        .map(|(_, state)| *state);
+                    match pending {
+                        Some(state) => constant_bits(solver, state, width),
+                        None => previous.clone(),
+                    }
+                }
+                Some(source) => {
+                    let input = bitwise(solver, &frames[source], inputs, width, SatSolver::or_gate);
+                    let before = match source.checked_sub(1) {
+                        Some(source) => {
+                            bitwise(solver, &frames[source], inputs, width, SatSolver::or_gate)
+                        }
+                        None => constant_bits(solver, timer_gate.input_state, width),
+                    };
+                    let differences: Vec<Lit> = input
+                        .iter()
+                        .zip(&before)
+                        .map(|(a, b)| solver.xor_gate(*a, *b))
+                        .collect();
+                    let changed = solver.or_gate(&differences);
+                    (0..previous.len())
+                        .map(|index| solver.mux_gate(changed, input[index], previous[index]))
+                        .collect()
+                }
+            };
+            values.insert(*id, value);
+        }
+        values
+    }
+
+    // Une passe de `tick` : wire groups et composants calculés à partir de `state`
+    fn next_pass(
+        &self,
+        solver: &mut SatSolver,
+        state: &CircuitCnf,
+        memory: &mut Memory,
+        context: &PassContext,
+    ) -> Result<CircuitCnf, &'static str> {
+        let group_bits = (0..self.wire_groups.len())
+            .map(|index| self.group_value(solver, state, index, None))
+            .collect();
+        let mut output_bits: HashMap<(usize, usize), Bits> = HashMap::new();
+        let mut next_memory = Memory::new();
+
+        let mut ids: Vec<usize> = self.composants_map.keys().copied().collect();
+        ids.sort_unstable();
+        for id in ids {
+            let composant = &self.composants_map[&id];
+            for (pin, width) in output_pins(composant) {
+                let current = &state.output_bits[&(id, pin)];
+                let next: Bits = match composant {
+                    ComposantsEnum::Switch(_)
+                    | ComposantsEnum::Button(_)
+                    | ComposantsEnum::DipSwitch(_) => current.clone(),
+                    // Verrou : recopie l'entrée de données tant que l'enable est non nul
+                    ComposantsEnum::LatchGate(latch_gate) => {
+                        let inputs = &latch_gate.gate.circuit_element.inputs;
+                        if inputs.len() < 2 {
+                            current.clone()
+                        } else {
+                            let enable = solver.or_gate(state.element_bits(&inputs[0]));
+                            let data = state.element_bits(&inputs[1]).to_vec();
+                            (0..current.len())
+                                .map(|index| {
+                                    let data = bit(solver, &data, index);
+                                    solver.mux_gate(enable, data, current[index])
+                                })
+                                .collect()
+                        }
+                    }
+                    ComposantsEnum::TimerGate(timer_gate) if timer_gate.ticks == 0 => {
+                        let inputs = &timer_gate.gate.circuit_element.inputs;
+                        let input = bitwise(solver, state, inputs, width, SatSolver::or_gate);
+                        let differences: Vec<Lit> = input
+                            .iter()
+                            .zip(&memory[&id])
+                            .map(|(a, b)| solver.xor_gate(*a, *b))
+                            .collect();
+                        let changed = solver.or_gate(&differences);
+                        let next = (0..current.len())
+                            .map(|index| solver.mux_gate(changed, input[index], current[index]))
+                            .collect();
+                        next_memory.insert(id, input);
+                        next
+                    }
+                    ComposantsEnum::TimerGate(_) => context
+                        .timers
+                        .get(&id)
+                        .cloned()
+                        .unwrap_or_else(|| current.clone()),
+                    ComposantsEnum::Clock(clock) => {
+                        let inputs = &clock.gate.circuit_element.inputs;
+                        let level = if !clock.level_at(context.clock_tick) {
+                            solver.constant(false)
+                        } else if inputs.is_empty() {
+                            solver.constant(true)
+                        } else {
+                            let bits: Vec<Lit> = inputs
+                                .iter()
+                                .flat_map(|input| state.element_bits(input).to_vec())
+                                .collect();
+                            solver.or_gate(&bits)
+                        };
+                        let mut bits = constant_bits(solver, 0, width);
+                        if let Some(first) = bits.first_mut() {
+                            *first = level;
+                        }
+                        bits
+                    }
+                    ComposantsEnum::FlipFlop(_) if pin == 1 => vec![!output_bits[&(id, 0)][0]],
+                    ComposantsEnum::FlipFlop(flip_flop) => {
+                        let (q, clock) = Self::encode_flip_flop(
+                            solver,
+                            flip_flop,
+                            state,
+                            memory[&id][0],
+                            current[0],
+                        );
+                        next_memory.insert(id, vec![clock]);
+                        vec![q]
+                    }
+                    _ => Self::encode_output(solver, state, composant, pin, width)?,
+                };
+                output_bits.insert((id, pin), next);
+            }
+        }
+
+        for (id, bits) in next_memory {
+            memory.insert(id, bits);
+        }
+        Ok(CircuitCnf {
+            group_bits,
+            output_bits,
+        })
+    }
+
+    // Même règle que `FlipFlop::update_inputs`. Renvoie Q et le niveau d'horloge vu
+    fn encode_flip_flop(
+        solver: &mut SatSolver,
+        flip_flop: &FlipFlop,
+        state: &CircuitCnf,
+        last_clock: Lit,
+        q: Lit,
+    ) -> (Lit, Lit) {
+        let level = |solver: &mut SatSolver, pin: usize| {
+            let group = flip_flop.gate.input_groups.get(pin).copied().flatten();
+            bit(solver, state.group_input_bits(group), 0)
+        };
+        let clock_pin = flip_flop.clock_pin();
+        let clock = level(solver, clock_pin);
+        let edge = match flip_flop.edge {
+            ClockEdge::Rising => solver.and_gate(&[clock, !last_clock]),
+            ClockEdge::Falling => solver.and_gate(&[!clock, last_clock]),
+        };
+        let data: Vec<Lit> = (0..clock_pin).map(|pin| level(solver, pin)).collect();
+        let next = match flip_flop.kind {
+            FlipFlopKind::D => data[0],
+            FlipFlopKind::T => solver.xor_gate(q, data[0]),
+            FlipFlopKind::JK => {
+                let set = solver.and_gate(&[data[0], !q]);
+                let hold = solver.and_gate(&[!data[1], q]);
+                solver.or_gate(&[set, hold])
+            }
+            FlipFlopKind::SR => {
+                let set = solver.and_gate(&[data[0], !data[1]]);
+                let reset = solver.and_gate(&[data[1], !data[0]]);
+                let hold = solver.and_gate(&[q, !reset]);
+                solver.or_gate(&[set, hold])
+            }
+        };
+        let preset = level(solver, clock_pin + 1);
+        let clear = level(solver, clock_pin + 2);
+        let clocked = solver.mux_gate(edge, next, q);
+        let preset_or_clocked = solver.or_gate(&[preset, clocked]);
+        (solver.and_gate(&[!clear, preset_or_clocked]), clock)
+    }
+
+    // Littéral vrai si la propriété est violée au dernier tick déroulé, ou None si
+    // elle ne peut pas l'être
+    fn encode_violation(
+        &self,
+        solver: &mut SatSolver,
+        property: &Property,
+        frames: &[CircuitCnf],
+    ) -> Option<Lit> {
+        let high = |solver: &mut SatSolver, frame: &CircuitCnf, group: usize| {
+            solver.or_gate(&frame.group_bits[group])
+        };
+        let violation = match property {
+            Property::Never {
+                high: highs,
+                low: lows,
+            } => {
+                let frame = frames.last()?;
+                let mut conditions = Vec::new();
+                for group in highs {
+                    conditions.push(high(solver, frame, *group));
+                }
+                for group in lows {
+                    conditions.push(!high(solver, frame, *group));
+                }
+                solver.and_gate(&conditions)
+            }
+            Property::Response {
+                trigger,
+                response,
+                within,
+            } => {
+                // Front montant au tick `step`, sans réponse jusqu'au dernier tick
+                let step = frames.len().checked_sub(*within as usize + 1)?;
+                let before = match step.checked_sub(1) {
+                    Some(previous) => high(solver, &frames[previous], *trigger),
+                    None => solver.constant(self.wire_group_state(*trigger) != 0),
+                };
+                let mut conditions = vec![high(solver, &frames[step], *trigger), !before];
+                for frame in &frames[step..] {
+                    conditions.push(!high(solver, frame, *response));
+                }
+                solver.and_gate(&conditions)
+            }
+        };
+        Some(violation)
+    }
+}
+
+// Mémoire des composants séquentiels hors sorties : dernier niveau d'horloge vu
+// par les bascules, dernière entrée vue par les temporisations sans délai
+type Memory = HashMap<usize, Bits>;
+
+// Valeurs communes à toutes les passes d'un tick
+struct PassContext<'a> {
+    clock_tick: u32,
+    timers: &'a HashMap<usize, Bits>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::{ClockEdge, FlipFlopKind, Orientation, Position};
+    use crate::wire::Wire;
+    use crate::Simulation;
+
+    fn add_wire(sim: &mut Simulation, id: usize, positions: Vec<Position>) {
+        sim.wires_map.insert(id, Wire::new(id, positions));
+    }
+
+    // Bascule D (id 2) dont !Q revient sur D, cadencée par l'horloge 1 : Q sur le
+    // wire 12, !Q sur le wire 11
+    fn toggle_circuit() -> Simulation {
+        let mut sim = Simulation::new();
+        sim.add_clock(vec![0, -20], 1, 1, 0, Orientation::Right, 1);
+        sim.add_flip_flop(
+            vec![20, 0],
+            FlipFlopKind::D,
+            ClockEdge::Rising,
+            Orientation::Right,
+            2,
+        );
+        let clock = sim.composants_map[&1].output_positions()[0];
+        let pins = sim.composants_map[&2].pin_positions();
+        let (d, clk, q, not_q) = (pins[0], pins[1], pins[4], pins[5]);
+        add_wire(
+            &mut sim,
+            10,
+            vec![clock, [clock[0] + 1, clock[1]], [clock[0] + 1, clk[1]], clk],
+        );
+        add_wire(
+            &mut sim,
+            11,
+            vec![
+                not_q,
+                [not_q[0] + 2, not_q[1]],
+                [not_q[0] + 2, 10],
+                [d[0] - 2, 10],
+                [d[0] - 2, d[1]],
+                d,
+            ],
+        );
+        add_wire(&mut sim, 12, vec![q, [q[0] + 1, q[1]]]);
+        sim.compute_connections();
+        sim
+    }
+
+    // Le switch 1 passe par une temporisation d'un tick (id 2) : entrée sur le wire
+    // 10, sortie sur le wire 11
+    fn delayed_circuit() -> Simulation {
+        let mut sim = Simulation::new();
+        sim.add_switch(vec![0, 0], 1);
+        sim.add_timer(vec![10, 0], 1, Orientation::Right, 2);
+        let input = sim.composants_map[&2].input_positions()[0];
+        let output = sim.composants_map[&2].output_positions()[0];
+        add_wire(&mut sim, 10, vec![[0, 0], [0, input[1]], input]);
+        add_wire(&mut sim, 11, vec![output, [output[0] + 1, output[1]]]);
+        sim.compute_connections();
+        sim
+    }
+
+    #[test]
+    fn toggle_counterexample_is_found_and_replays() {
+        let mut sim = toggle_circuit();
+        sim.add_never_property(vec![12], Vec::new());
+        let result = sim.check_properties(4, Vec::new()).unwrap();
+        assert_eq!(result.violated_property(), Some(0));
+        assert!(result.confirmed());
+        let trace = result.trace();
+        // L'horloge est haute dès le premier tick : Q monte sur ce front
+        assert_eq!(trace.len(), 1);
+
+        let mut q_high = false;
+        for vector in trace {
+            assert!(sim.replay_step(Vec::new(), vector, 100).is_some());
+            q_high = sim.component_pin_state(2, 0) != 0;
+        }
+        assert!(q_high);
+        // Front montant suivant deux ticks plus tard : Q redescend
+        for _ in 0..2 {
+            assert!(sim.replay_step(Vec::new(), 0, 100).is_some());
+        }
+        assert_eq!(sim.component_pin_state(2, 0), 0);
+    }
+
+    #[test]
+    fn toggle_outputs_are_never_both_high() {
+        let mut sim = toggle_circuit();
+        sim.add_never_property(vec![11, 12], Vec::new());
+        let result = sim.check_properties(6, Vec::new()).unwrap();
+        assert!(result.holds());
+        assert!(result.trace().is_empty());
+    }
+
+    #[test]
+    fn response_within_zero_ticks_misses_the_delay() {
+        let mut sim = delayed_circuit();
+        sim.add_response_property(10, 11, 0);
+        let result = sim.check_properties(3, vec![1]).unwrap();
+        assert_eq!(result.violated_property(), Some(0));
+        assert!(result.confirmed());
+        let trace = result.trace();
+        assert_eq!(trace, vec![1]);
+
+        assert!(sim.replay_step(vec![1], trace[0], 100).is_some());
+        assert_eq!(sim.get_component_state(1), 1);
+        assert_eq!(sim.get_component_state(2), 0);
+    }
+
+    #[test]
+    fn response_within_one_tick_holds() {
+        let mut sim = delayed_circuit();
+        sim.add_response_property(10, 11, 1);
+        let result = sim.check_properties(4, vec![1]).unwrap();
+        assert!(result.holds());
+    }
+
+    #[test]
+    fn replay_step_validates_inputs() {
+        let mut sim = delayed_circuit();
+        assert!(sim.replay_step(vec![2], 1, 100).is_none());
+        assert!(sim.replay_step(vec![1; 65], 1, 100).is_none());
+        assert!(sim.replay_step(vec![1], 1, 100).is_some());
+    }
+}
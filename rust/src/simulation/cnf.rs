@@ -0,0 +1,239 @@
+use std::collections::HashMap;
+
+use crate::circuit_element::CircuitElementEnum;
+use crate::simulation::sat::{Lit, SatSolver};
+use crate::simulation::{Fault, FaultSite};
+use crate::types::PullKind;
+use crate::{ComposantsEnum, Simulation};
+
+// Bits d'un signal, poids faible en tête ; les bits absents valent 0
+pub(crate) type Bits = Vec<Lit>;
+
+// Copie du circuit encodée en CNF : un littéral par bit de chaque wire group
+// et de chaque sortie de composant, indexée par (id, pin)
+#[derive(Clone)]
+pub(crate) struct CircuitCnf {
+    pub group_bits: Vec<Bits>,
+    pub output_bits: HashMap<(usize, usize), Bits>,
+}
+
+impl CircuitCnf {
+    pub fn element_bits(&self, element: &CircuitElementEnum) -> &[Lit] {
+        let bits = match element {
+            CircuitElementEnum::WireGroup(id) => self.group_bits.get(*id),
+            CircuitElementEnum::Component(id) => self.output_bits.get(&(*id, 0)),
+            CircuitElementEnum::ComponentPin(id, pin) => self.output_bits.get(&(*id, *pin)),
+        };
+        bits.map(Vec::as_slice).unwrap_or_default()
+    }
+
+    pub fn group_input_bits(&self, group: Option<usize>) -> &[Lit] {
+        group
+            .and_then(|id| self.group_bits.get(id))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+}
+
+pub(crate) fn bit(solver: &mut SatSolver, bits: &[Lit], index: usize) -> Lit {
+    match bits.get(index) {
+        Some(lit) => *lit,
+        None => solver.constant(false),
+    }
+}
+
+pub(crate) fn constant_bits(solver: &mut SatSolver, value: u64, width: u8) -> Bits {
+    (0..width as usize)
+        .map(|index| solver.constant(index < 64 && (value >> index) & 1 == 1))
+        .collect()
+}
+
+fn xor_all(solver: &mut SatSolver, lits: &[Lit]) -> Lit {
+    let initial = solver.constant(false);
+    lits.iter()
+        .fold(initial, |acc, lit| solver.xor_gate(acc, *lit))
+}
+
+// Combine bit à bit les entrées d'une porte, sur `width` bits
+pub(crate) fn bitwise(
+    solver: &mut SatSolver,
+    cnf: &CircuitCnf,
+    inputs: &[CircuitElementEnum],
+    width: u8,
+    combine: fn(&mut SatSolver, &[Lit]) -> Lit,
+) -> Bits {
+    (0..width as usize)
+        .map(|index| {
+            let lits: Vec<Lit> = inputs
+                .iter()
+                .map(|input| bit(solver, cnf.element_bits(input), index))
+                .collect();
+            combine(solver, &lits)
+        })
+        .collect()
+}
+
+// Sorties d'un composant : (pin, largeur)
+pub(crate) fn output_pins(composant: &ComposantsEnum) -> Vec<(usize, u8)> {
+    match composant.multi_pin_gate() {
+        Some(gate) => gate.output_widths.iter().copied().enumerate().collect(),
+        None if composant.circuit_element().outputs.is_empty() => Vec::new(),
+        None => vec![(0, composant.circuit_element().width)],
+    }
+}
+
+impl Simulation {
+    // Encode l'état stable du circuit dans `solver`. `sources` donne les sorties des
+    // composants libres (switches) ou à mémoire ; les autres composants doivent être
+    // combinatoires, sinon le type du premier composant non supporté est renvoyé
+    pub(crate) fn encode_circuit(
+        &self,
+        solver: &mut SatSolver,
+        sources: &HashMap<(usize, usize), Bits>,
+        fault: Option<&Fault>,
+    ) -> Result<CircuitCnf, &'static str> {
+        let forced = |site: FaultSite| {
+            fault
+                .filter(|fault| fault.site == site)
+                .map(|fault| fault.stuck_at)
+        };
+
+        let mut cnf = CircuitCnf {
+            group_bits: self
+                .wire_groups
+                .iter()
+                .map(|group| {
+                    (0..group.circuit_element.width)
+                        .map(|_| solver.new_var())
+                        .collect()
+                })
+                .collect(),
+            output_bits: HashMap::new(),
+        };
+
+        let mut ids: Vec<usize> = self.composants_map.keys().copied().collect();
+        ids.sort_unstable();
+        for &id in &ids {
+            for (pin, width) in output_pins(&self.composants_map[&id]) {
+                let bits = match sources.get(&(id, pin)) {
+                    Some(bits) if forced(FaultSite::Output(id, pin)).is_none() => bits.clone(),
+                    _ => (0..width).map(|_| solver.new_var()).collect(),
+                };
+                cnf.output_bits.insert((id, pin), bits);
+            }
+        }
+
+        for index in 0..self.wire_groups.len() {
+            let value = self.group_value(solver, &cnf, index, fault);
+            for (lit, value) in cnf.group_bits[index].iter().zip(value) {
+                solver.add_equal(*lit, value);
+            }
+        }
+
+        for &id in &ids {
+            let composant = &self.composants_map[&id];
+            for (pin, width) in output_pins(composant) {
+                let value = match forced(FaultSite::Output(id, pin)) {
+                    Some(stuck_at) => vec![solver.constant(stuck_at); width as usize],
+                    None if sources.contains_key(&(id, pin)) => continue,
+                    None => Self::encode_output(solver, &cnf, composant, pin, width)?,
+                };
+                for (lit, value) in cnf.output_bits[&(id, pin)].iter().zip(value) {
+                    solver.add_equal(*lit, value);
+                }
+            }
+        }
+
+        Ok(cnf)
+    }
+
+    // Un wire group vaut le OU de ses pilotes, ou la valeur de sa résistance de tirage.
+    // Un groupe flottant vaut 0 comme dans la simulation, pour que les traces rejouent
+    pub(crate) fn group_value(
+        &self,
+        solver: &mut SatSolver,
+        cnf: &CircuitCnf,
+        index: usize,
+        fault: Option<&Fault>,
+    ) -> Bits {
+        let group = &self.wire_groups[index];
+        let width = group.circuit_element.width as usize;
+        let forced = fault
+            .filter(|fault| fault.site == FaultSite::WireGroup(index))
+            .map(|fault| fault.stuck_at);
+        match forced {
+            Some(stuck_at) => vec![solver.constant(stuck_at); width],
+            None if group.circuit_element.inputs.is_empty() && group.pull == Some(PullKind::Up) => {
+                vec![solver.constant(true); width]
+            }
+            None => (0..width)
+                .map(|index| {
+                    let drivers: Vec<Lit> = group
+                        .circuit_element
+                        .inputs
+                        .iter()
+                        .map(|input| bit(solver, cnf.element_bits(input), index))
+                        .collect();
+                    solver.or_gate(&drivers)
+                })
+                .collect(),
+        }
+    }
+
+    pub(crate) fn encode_output(
+        solver: &mut SatSolver,
+        cnf: &CircuitCnf,
+        composant: &ComposantsEnum,
+        pin: usize,
+        width: u8,
+    ) -> Result<Bits, &'static str> {
+        let inputs = &composant.circuit_element().inputs;
+        let bitwise = |solver: &mut SatSolver, combine: fn(&mut SatSolver, &[Lit]) -> Lit| {
+            bitwise(solver, cnf, inputs, width, combine)
+        };
+        let bits = match composant {
+            ComposantsEnum::OrGate(_) | ComposantsEnum::BufferGate(_) => {
+                bitwise(solver, SatSolver::or_gate)
+            }
+            ComposantsEnum::NorGate(_) | ComposantsEnum::NotGate(_) => {
+                bitwise(solver, |solver, lits| !solver.or_gate(lits))
+            }
+            ComposantsEnum::XorGate(_) => bitwise(solver, xor_all),
+            ComposantsEnum::XnorGate(_) => bitwise(solver, |solver, lits| !xor_all(solver, lits)),
+            // Une porte ET dont une entrée n'est pas reliée vaut 0
+            ComposantsEnum::AndGate(gate) if !gate.gate.are_all_inputs_connected() => {
+                constant_bits(solver, 0, width)
+            }
+            ComposantsEnum::NandGate(gate) if !gate.gate.are_all_inputs_connected() => {
+                constant_bits(solver, u64::MAX, width)
+            }
+            ComposantsEnum::AndGate(_) => bitwise(solver, SatSolver::and_gate),
+            ComposantsEnum::NandGate(_) => bitwise(solver, |solver, lits| !solver.and_gate(lits)),
+            ComposantsEnum::OutputPort(_) => bitwise(solver, SatSolver::or_gate),
+            ComposantsEnum::Constant(constant) => {
+                constant_bits(solver, if constant.high { u64::MAX } else { 0 }, width)
+            }
+            ComposantsEnum::Switch(switch) => {
+                constant_bits(solver, switch.circuit_element.state, width)
+            }
+            ComposantsEnum::Button(button) => {
+                constant_bits(solver, button.circuit_element.state, width)
+            }
+            ComposantsEnum::DipSwitch(dip_switch) => {
+                constant_bits(solver, dip_switch.gate.output_state(pin), width)
+            }
+            ComposantsEnum::Splitter(splitter) => {
+                let bus = cnf.group_input_bits(splitter.gate.input_groups[0]);
+                vec![bit(solver, bus, pin)]
+            }
+            ComposantsEnum::Merger(merger) => (0..merger.bits as usize)
+                .map(|index| {
+                    let input = cnf.group_input_bits(merger.gate.input_groups[index]);
+                    bit(solver, input, 0)
+                })
+                .collect(),
+            _ => return Err(composant.kind_name()),
+        };
+        Ok(bits)
+    }
+}
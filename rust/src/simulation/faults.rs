@@ -0,0 +1,438 @@
+use crate::circuit_element::CircuitElementEnum;
+use crate::js::FaultReport;
+use crate::utils::{console_log, width_mask};
+use crate::{ComposantsEnum, Simulation};
+
+use wasm_bindgen::prelude::*;
+
+pub const FAULT_SIMULATION_DEPTH: u32 = 1000;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum FaultSite {
+    WireGroup(usize),
+    // Sortie `pin` d'un composant, 0 pour les composants à une seule sortie
+    Output(usize, usize),
+}
+
+#[derive(Clone)]
+pub struct Fault {
+    pub site: FaultSite,
+    pub stuck_at: bool,
+    // Wires du groupe, pour que le front puisse situer un collage de wire group
+    pub wires: Vec<usize>,
+}
+
+// Point observé par la simulation de fautes
+#[derive(Clone, Copy)]
+pub(crate) enum Observation {
+    WireGroup(usize),
+    Component(usize),
+}
+
+#[wasm_bindgen]
+impl Simulation {
+    // Force le wire group du wire à 0 ou à 1 (tous les bits). À appeler après `compute_connections`
+    pub fn inject_wire_fault(&mut self, wire_index: usize, stuck_at: bool) -> bool {
+        let Some(wire_group) = self
+            .wire_groups
+            .iter()
+            .find(|wire_group| wire_group.wires.contains(&wire_index))
+        else {
+            console_log("Invalid wire index");
+            return false;
+        };
+        let site = FaultSite::WireGroup(wire_group.circuit_element.id);
+        self.inject_fault(site, stuck_at)
+    }
+
+    pub fn inject_output_fault(
+        &mut self,
+        component_index: usize,
+        pin: usize,
+        stuck_at: bool,
+    ) -> bool {
+        self.inject_fault(FaultSite::Output(component_index, pin), stuck_at)
+    }
+
+    pub fn clear_faults(&mut self) {
+        self.faults.clear();
+    }
+
+    // Chaque fault : { site: "wire_group" | "output", id, pin, stuck_at, wires }
+    pub fn fault_list(&self) -> js_sys::Array {
+        let array = js_sys::Array::new();
+        for fault in self.collect_faults() {
+            array.push(&FaultReport::fault_to_js(&fault));
+        }
+        array
+    }
+
+    // Applique chaque vecteur aux switches `inputs` (bit i → `inputs[i]`, tous les switches
+    // si vide) le temps d'un tick et compare les points observés au circuit sans faute.
+    // Sans `observed_wires`, les afficheurs et ports de sortie sont observés
+    pub fn fault_simulation(
+        &self,
+        inputs: Vec<usize>,
+        vectors: Vec<u64>,
+        observed_wires: Vec<usize>,
+    ) -> Option<FaultReport> {
+        let inputs = self.switch_inputs(inputs)?;
+        let (detected, undetected) = self.simulate_faults(&inputs, &vectors, &observed_wires);
+        Some(FaultReport::new(detected, undetected))
+    }
+}
+
+impl Simulation {
+    pub(crate) fn forced_state(&self, site: FaultSite) -> Option<u64> {
+        if self.faults.is_empty() {
+            return None;
+        }
+        self.faults.get(&site).copied()
+    }
+
+    fn fault_site_width(&self, site: FaultSite) -> Option<u8> {
+        match site {
+            FaultSite::WireGroup(id) => self
+                .wire_groups
+                .get(id)
+                .map(|wire_group| wire_group.circuit_element.width),
+            FaultSite::Output(id, pin) => {
+                let composant = self.composants_map.get(&id)?;
+                match composant.multi_pin_gate() {
+                    Some(gate) => gate.output_widths.get(pin).copied(),
+                    None if pin == 0 => Some(composant.circuit_element().width),
+                    None => None,
+                }
+            }
+        }
+    }
+
+    pub(crate) fn inject_fault(&mut self, site: FaultSite, stuck_at: bool) -> bool {
+        let Some(width) = self.fault_site_width(site) else {
+            console_log("Invalid fault site");
+            return false;
+        };
+        let value = if stuck_at { width_mask(width) } else { 0 };
+        self.faults.insert(site, value);
+        true
+    }
+
+    // Collages à 0 et à 1 de chaque wire group puis de chaque sortie connectée
+    pub(crate) fn collect_faults(&self) -> Vec<Fault> {
+        let mut sites: Vec<(FaultSite, Vec<usize>)> = self
+            .wire_groups
+            .iter()
+            .map(|wire_group| {
+                (
+                    FaultSite::WireGroup(wire_group.circuit_element.id),
+                    wire_group.wires.clone(),
+                )
+            })
+            .collect();
+
+        let mut ids: Vec<usize> = self.composants_map.keys().copied().collect();
+        ids.sort_unstable();
+        for id in ids {
+            let composant = &self.composants_map[&id];
+            let pins: Vec<usize> = match composant.multi_pin_gate() {
+                // Seules les pins qui pilotent un wire group ont un collage observable
+                Some(gate) => (0..gate.output_positions.len())
+                    .filter(|pin| self.drives_wire_group(id, *pin))
+                    .collect(),
+                None if !composant.circuit_element().outputs.is_empty() => vec![0],
+                None => Vec::new(),
+            };
+            sites.extend(
+                pins.into_iter()
+                    .map(|pin| (FaultSite::Output(id, pin), Vec::new())),
+            );
+        }
+
+        sites
+            .into_iter()
+            .flat_map(|(site, wires)| {
+                [false, true].map(|stuck_at| Fault {
+                    site,
+                    stuck_at,
+                    wires: wires.clone(),
+                })
+            })
+            .collect()
+    }
+
+    fn drives_wire_group(&self, id: usize, pin: usize) -> bool {
+        self.wire_groups.iter().any(|wire_group| {
+            wire_group.circuit_element.inputs.iter().any(|input| {
+                matches!(input, CircuitElementEnum::ComponentPin(input_id, input_pin)
+                    if *input_id == id && *input_pin == pin)
+            })
+        })
+    }
+
+    // Copie de l'état courant, sans définitions de sous-circuits ni modèle de timing
+    pub(crate) fn clone_state(&self) -> Simulation {
+        let mut circuit = Simulation::new();
+        circuit.composants_map = self.composants_map.clone();
+        circuit.wires_map = self.wires_map.clone();
+        circuit.wire_groups = self.wire_groups.clone();
+        circuit.tick_counter = self.tick_counter;
+        circuit.step_counter = self.step_counter;
+        circuit.clock_tick = self.clock_tick;
+        circuit.clocks_running = self.clocks_running;
+        circuit.clock_steps = self.clock_steps;
+        circuit.internal_ids = self.internal_ids.clone();
+        circuit.connection_mode = self.connection_mode;
+        circuit.junctions = self.junctions.clone();
+        circuit.faults = self.faults.clone();
+        circuit
+    }
+
+    pub(crate) fn observations(&self, observed_wires: &[usize]) -> Vec<Observation> {
+        if !observed_wires.is_empty() {
+            return observed_wires
+                .iter()
+                .filter_map(|wire| {
+                    self.wire_groups
+                        .iter()
+                        .find(|wire_group| wire_group.wires.contains(wire))
+                        .map(|wire_group| Observation::WireGroup(wire_group.circuit_element.id))
+                })
+                .collect();
+        }
+        let mut ids: Vec<usize> = self
+            .composants_map
+            .iter()
+            .filter(|(id, composant)| match composant {
+                ComposantsEnum::Led(_)
+                | ComposantsEnum::SevenSegment(_)
+                | ComposantsEnum::PixelMatrix(_)
+                | ComposantsEnum::Terminal(_) => true,
+                ComposantsEnum::OutputPort(_) => !self.internal_ids.contains(id),
+                _ => false,
+            })
+            .map(|(id, _)| *id)
+            .collect();
+        ids.sort_unstable();
+        ids.into_iter().map(Observation::Component).collect()
+    }
+
+    fn observe(&self, observations: &[Observation]) -> Vec<u64> {
+        observations
+            .iter()
+            .map(|observation| match observation {
+                Observation::WireGroup(id) => self.wire_group_state(*id),
+                Observation::Component(id) => self.component_state(*id),
+            })
+            .collect()
+    }
+
+    // Switches pilotés par des vecteurs : tous, par id croissant, si `inputs` est vide
+    pub(crate) fn switch_inputs(&self, inputs: Vec<usize>) -> Option<Vec<usize>> {
+        let inputs = if inputs.is_empty() {
+            let mut switches: Vec<usize> = self
+                .composants_map
+                .iter()
+                .filter(|(_, composant)| matches!(composant, ComposantsEnum::Switch(_)))
+                .map(|(id, _)| *id)
+                .collect();
+            switches.sort_unstable();
+            switches
+        } else {
+            inputs
+        };
+        let are_switches = inputs
+            .iter()
+            .all(|id| matches!(self.composants_map.get(id), Some(ComposantsEnum::Switch(_))));
+        if !are_switches || inputs.len() > 64 {
+            console_log("Invalid switch list");
+            return None;
+        }
+        Some(inputs)
+    }
+
+    // Bit i de `vector` → switch `inputs[i]`
+    pub(crate) fn set_switches(&mut self, inputs: &[usize], vector: u64) {
+        for (bit, id) in inputs.iter().enumerate() {
+            if let Some(ComposantsEnum::Switch(switch)) = self.composants_map.get_mut(id) {
+                switch.circuit_element.set_state((vector >> bit) & 1);
+            }
+        }
+    }
+
+    pub(crate) fn apply_vector(&mut self, inputs: &[usize], vector: u64) {
+        self.set_switches(inputs, vector);
+        self.run_until_stabilizes(FAULT_SIMULATION_DEPTH);
+    }
+
+    // Réponses observées après chaque vecteur, en s'arrêtant dès qu'une diffère de `expected`
+    fn run_vectors(
+        &self,
+        fault: Option<&Fault>,
+        inputs: &[usize],
+        vectors: &[u64],
+        observations: &[Observation],
+        expected: Option<&[Vec<u64>]>,
+    ) -> (Vec<Vec<u64>>, bool) {
+        // Les collages injectés par l'utilisateur ne comptent ni dans la référence sans
+        // faute ni dans les circuits fautifs, qui n'en portent qu'un
+        let mut circuit = self.clone_state();
+        circuit.faults.clear();
+        if let Some(fault) = fault {
+            circuit.inject_fault(fault.site, fault.stuck_at);
+        }
+        let mut responses = Vec::with_capacity(vectors.len());
+        for (step, vector) in vectors.iter().enumerate() {
+            circuit.apply_vector(inputs, *vector);
+            let response = circuit.observe(observations);
+            if expected.is_some_and(|expected| expected[step] != response) {
+                return (responses, true);
+            }
+            responses.push(response);
+        }
+        (responses, false)
+    }
+
+    pub(crate) fn fault_free_responses(
+        &self,
+        inputs: &[usize],
+        vectors: &[u64],
+        observations: &[Observation],
+    ) -> Vec<Vec<u64>> {
+        self.run_vectors(None, inputs, vectors, observations, None)
+            .0
+    }
+
+    // Vrai si la faute change une réponse par rapport à `expected`
+    pub(crate) fn detects(
+        &self,
+        fault: &Fault,
+        inputs: &[usize],
+        vectors: &[u64],
+        observations: &[Observation],
+        expected: &[Vec<u64>],
+    ) -> bool {
+        self.run_vectors(Some(fault), inputs, vectors, observations, Some(expected))
+            .1
+    }
+
+    // Indices des vecteurs dont la réponse diffère de `expected` en présence de la faute.
+    // Le circuit doit être combinatoire : chaque réponse ne dépend que de son vecteur
+    pub(crate) fn detecting_vectors(
+        &self,
+        fault: &Fault,
+        inputs: &[usize],
+        vectors: &[u64],
+        observations: &[Observation],
+        expected: &[Vec<u64>],
+    ) -> Vec<usize> {
+        let (responses, _) = self.run_vectors(Some(fault), inputs, vectors, observations, None);
+        responses
+            .iter()
+            .zip(expected)
+            .enumerate()
+            .filter(|(_, (response, expected))| response != expected)
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    // Simulation série : une copie du circuit par faute. Renvoie (détectées, non détectées)
+    pub(crate) fn simulate_faults(
+        &self,
+        inputs: &[usize],
+        vectors: &[u64],
+        observed_wires: &[usize],
+    ) -> (Vec<Fault>, Vec<Fault>) {
+        let observations = self.observations(observed_wires);
+        let expected = self.fault_free_responses(inputs, vectors, &observations);
+        self.collect_faults()
+            .into_iter()
+            .partition(|fault| self.detects(fault, inputs, vectors, &observations, &expected))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FaultSite;
+    use crate::types::{ClockEdge, FlipFlopKind, Orientation};
+    use crate::wire::Wire;
+    use crate::Simulation;
+
+    // Porte ET (id 3) pilotée par les switches 1 et 2, sortie sur le wire 12
+    fn and_circuit() -> Simulation {
+        let mut sim = Simulation::new();
+        sim.add_switch(vec![0, 1], 1);
+        sim.add_switch(vec![0, -1], 2);
+        sim.add_and_gate(vec![10, 0], Orientation::Right, 3);
+        let wires = [
+            (10, vec![[0, 1], [5, 1]]),
+            (11, vec![[0, -1], [5, -1]]),
+            (12, vec![[10, 0], [15, 0]]),
+        ];
+        for (id, positions) in wires {
+            sim.wires_map.insert(id, Wire::new(id, positions));
+        }
+        sim.compute_connections();
+        sim
+    }
+
+    fn detects_output_stuck_at_0(sim: &Simulation, vectors: &[u64]) -> bool {
+        let (detected, _) = sim.simulate_faults(&[1, 2], vectors, &[12]);
+        detected
+            .iter()
+            .any(|fault| fault.site == FaultSite::Output(3, 0) && !fault.stuck_at)
+    }
+
+    #[test]
+    fn and_output_stuck_at_0_needs_both_inputs_high() {
+        let sim = and_circuit();
+        assert!(detects_output_stuck_at_0(&sim, &[0b11]));
+        assert!(!detects_output_stuck_at_0(&sim, &[0b00, 0b01, 0b10]));
+    }
+
+    #[test]
+    fn injected_faults_stay_out_of_the_reference() {
+        let mut sim = and_circuit();
+        assert!(sim.inject_output_fault(3, 0, false));
+        assert!(detects_output_stuck_at_0(&sim, &[0b11]));
+    }
+
+    #[test]
+    fn inputs_are_validated() {
+        let sim = and_circuit();
+        let report = sim.fault_simulation(Vec::new(), vec![0b11], vec![12]);
+        assert!(report.is_some_and(|report| report.detected_count() > 0));
+        assert!(sim
+            .fault_simulation(vec![3], vec![0b11], vec![12])
+            .is_none());
+        assert!(sim
+            .fault_simulation(vec![1; 65], vec![0b11], vec![12])
+            .is_none());
+    }
+
+    #[test]
+    fn unconnected_output_pins_are_not_listed() {
+        let mut sim = Simulation::new();
+        sim.add_flip_flop(
+            vec![0, 0],
+            FlipFlopKind::D,
+            ClockEdge::Rising,
+            Orientation::Right,
+            1,
+        );
+        // Q seul est câblé, Q̄ reste en l'air
+        let q = sim.composants_map[&1]
+            .multi_pin_gate()
+            .unwrap()
+            .output_positions[0];
+        sim.wires_map
+            .insert(10, Wire::new(10, vec![q, [q[0] + 10, q[1]]]));
+        sim.compute_connections();
+        let sites: Vec<FaultSite> = sim
+            .collect_faults()
+            .into_iter()
+            .map(|fault| fault.site)
+            .filter(|site| matches!(site, FaultSite::Output(1, _)))
+            .collect();
+        assert_eq!(sites, vec![FaultSite::Output(1, 0); 2]);
+    }
+}
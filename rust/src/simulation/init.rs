@@ -1,11 +1,40 @@
+use std::collections::HashMap;
+
 use crate::circuit_element::CircuitElementEnum;
+use crate::components::{
+    Adder, Alu, AndGate, Clock, Comparator, Counter, Decoder, Demultiplexer, DipSwitch, FlipFlop,
+    InputPort, Keyboard, Led, Merger, MultiInputsGate, MultiPinGate, Multiplexer, NandGate,
+    NorGate, OneInputGate, OrGate, OutputPort, PixelMatrix, PriorityEncoder, Ram, Register, Rom,
+    SevenSegment, ShiftRegister, Splitter, SubCircuit, Terminal, XnorGate, XorGate,
+};
 use crate::console_log;
-use crate::wire::WireGroup;
+use crate::types::{ConnectionMode, Position};
+use crate::wire::{polyline_contains, WireGroup};
 use crate::ComposantsEnum;
 use crate::Simulation;
 
 use wasm_bindgen::prelude::*;
 
+fn find_root(parents: &mut [usize], group: usize) -> usize {
+    let mut root = group;
+    while parents[root] != root {
+        root = parents[root];
+    }
+    parents[group] = root;
+    root
+}
+
+// Largeur du plus large des wire groups connectés, 1 si aucun
+fn groups_width(wire_groups: &[WireGroup], groups: &[Option<usize>]) -> u8 {
+    groups
+        .iter()
+        .flatten()
+        .filter_map(|group| wire_groups.get(*group))
+        .map(|group| group.circuit_element.width)
+        .max()
+        .unwrap_or(1)
+}
+
 #[wasm_bindgen]
 impl Simulation {
     fn find_matching_wire(&mut self, wire_index: usize, wire_group_index: usize) {
@@ -24,35 +53,62 @@ impl Simulation {
             }
 
             // Extraire une copie des positions du wire candidat
-            let positions_opt = self
+            let Some((candidate_positions, candidate_width)) = self
                 .wires_map
                 .get(&wire_in_set)
-                .map(|w| w.positions.clone());
-            if positions_opt.is_none() {
+                .map(|w| (w.positions.clone(), w.circuit_element.width))
+            else {
                 continue;
-            }
-            let candidate_positions = positions_opt.unwrap();
+            };
 
-            for pos in &wire_positions {
-                let matching_pos = candidate_positions.iter().any(|p| p == pos);
-                if !matching_pos {
-                    continue;
-                }
+            if !self.wires_connect(&wire_positions, &candidate_positions)
+                || !self.wire_set.contains(&wire_in_set)
+            {
+                continue;
+            }
 
-                if !self.wire_set.contains(&wire_in_set) {
-                    continue;
-                }
+            // Ici plus aucun emprunt immuable → on peut muter
+            self.wire_groups[wire_group_index].add_wire(
+                wire_in_set,
+                candidate_positions,
+                candidate_width,
+            );
+            self.wire_set.remove(&wire_in_set);
 
-                // Ici plus aucun emprunt immuable → on peut muter
-                self.wire_groups[wire_group_index]
-                    .add_wire(wire_in_set, candidate_positions.clone());
-                self.wire_set.remove(&wire_in_set);
+            self.find_matching_wire(wire_in_set, wire_group_index);
+        }
+    }
 
-                self.find_matching_wire(wire_in_set, wire_group_index);
+    fn wires_connect(&self, wire: &[Position], candidate: &[Position]) -> bool {
+        match self.connection_mode {
+            ConnectionMode::Legacy => wire.iter().any(|pos| candidate.contains(pos)),
+            ConnectionMode::Endpoints => {
+                let touches = |from: &[Position], to: &[Position]| {
+                    [from.first(), from.last()]
+                        .into_iter()
+                        .flatten()
+                        .any(|end| polyline_contains(to, *end))
+                };
+                touches(wire, candidate)
+                    || touches(candidate, wire)
+                    || self.junctions.iter().any(|junction| {
+                        polyline_contains(wire, *junction)
+                            && polyline_contains(candidate, *junction)
+                    })
             }
         }
     }
 
+    // À appeler avant `compute_connections`. Le front ne choisit `Endpoints` que pour
+    // une sauvegarde dont la version de format le prévoit
+    pub fn set_connection_mode(&mut self, mode: ConnectionMode) {
+        self.connection_mode = mode;
+    }
+
+    pub fn get_connection_mode(&self) -> ConnectionMode {
+        self.connection_mode
+    }
+
     pub fn compute_connections(&mut self) {
         // Remplir le set avec tous les wires
         for wire in self.wires_map.values() {
@@ -71,7 +127,7 @@ impl Simulation {
             let mut wire_group = WireGroup::new(self.wire_groups.len());
 
             if let Some(w) = self.wires_map.get(&wire) {
-                wire_group.add_wire(wire, w.positions.clone());
+                wire_group.add_wire(wire, w.positions.clone(), w.circuit_element.width);
             }
 
             let wire_group_id = wire_group.circuit_element.id;
@@ -79,119 +135,13 @@ impl Simulation {
             self.find_matching_wire(wire, wire_group_id);
         }
 
+        self.merge_tunnel_groups();
+
         //
         // Connecter les wires et les composants
         //
         for (composant_id, composant) in self.composants_map.iter_mut() {
             match composant {
-                ComposantsEnum::OrGate(or_gate) => {
-                    for wire_group in self.wire_groups.iter_mut() {
-                        for pos in wire_group.positions.iter() {
-                            if or_gate.gate.output_position == *pos {
-                                console_log(&format!(
-                                    "Connected (OR gate) output {} to wire group {}",
-                                    composant_id, wire_group.circuit_element.id
-                                ));
-                                or_gate.gate.circuit_element.outputs.push(
-                                    CircuitElementEnum::WireGroup(wire_group.circuit_element.id),
-                                );
-                                wire_group
-                                    .circuit_element
-                                    .inputs
-                                    .push(CircuitElementEnum::Component(*composant_id));
-                            }
-                            for input_pos in or_gate.gate.input_positions.iter() {
-                                if *input_pos == *pos {
-                                    console_log(&format!(
-                                        "Connected (OR gate) input {} to wire group {}",
-                                        composant_id, wire_group.circuit_element.id
-                                    ));
-                                    or_gate.gate.circuit_element.inputs.push(
-                                        CircuitElementEnum::WireGroup(
-                                            wire_group.circuit_element.id,
-                                        ),
-                                    );
-                                    wire_group
-                                        .circuit_element
-                                        .outputs
-                                        .push(CircuitElementEnum::Component(*composant_id));
-                                }
-                            }
-                        }
-                    }
-                }
-                ComposantsEnum::AndGate(and_gate) => {
-                    for wire_group in self.wire_groups.iter_mut() {
-                        for pos in wire_group.positions.iter() {
-                            if and_gate.gate.output_position == *pos {
-                                console_log(&format!(
-                                    "Connected (AND gate) output {} to wire group {}",
-                                    composant_id, wire_group.circuit_element.id
-                                ));
-                                and_gate.gate.circuit_element.outputs.push(
-                                    CircuitElementEnum::WireGroup(wire_group.circuit_element.id),
-                                );
-                                wire_group
-                                    .circuit_element
-                                    .inputs
-                                    .push(CircuitElementEnum::Component(*composant_id));
-                            }
-                            for input_pos in and_gate.gate.input_positions.iter() {
-                                if *input_pos == *pos {
-                                    console_log(&format!(
-                                        "Connected (AND gate) input {} to wire group {}",
-                                        composant_id, wire_group.circuit_element.id
-                                    ));
-                                    and_gate.gate.circuit_element.inputs.push(
-                                        CircuitElementEnum::WireGroup(
-                                            wire_group.circuit_element.id,
-                                        ),
-                                    );
-                                    wire_group
-                                        .circuit_element
-                                        .outputs
-                                        .push(CircuitElementEnum::Component(*composant_id));
-                                }
-                            }
-                        }
-                    }
-                }
-                ComposantsEnum::XorGate(xor_gate) => {
-                    for wire_group in self.wire_groups.iter_mut() {
-                        for pos in wire_group.positions.iter() {
-                            if xor_gate.gate.output_position == *pos {
-                                console_log(&format!(
-                                    "Connected (XOR gate) output {} to wire group {}",
-                                    composant_id, wire_group.circuit_element.id
-                                ));
-                                xor_gate.gate.circuit_element.outputs.push(
-                                    CircuitElementEnum::WireGroup(wire_group.circuit_element.id),
-                                );
-                                wire_group
-                                    .circuit_element
-                                    .inputs
-                                    .push(CircuitElementEnum::Component(*composant_id));
-                            }
-                            for input_pos in xor_gate.gate.input_positions.iter() {
-                                if *input_pos == *pos {
-                                    console_log(&format!(
-                                        "Connected (XOR gate) input {} to wire group {}",
-                                        composant_id, wire_group.circuit_element.id
-                                    ));
-                                    xor_gate.gate.circuit_element.inputs.push(
-                                        CircuitElementEnum::WireGroup(
-                                            wire_group.circuit_element.id,
-                                        ),
-                                    );
-                                    wire_group
-                                        .circuit_element
-                                        .outputs
-                                        .push(CircuitElementEnum::Component(*composant_id));
-                                }
-                            }
-                        }
-                    }
-                }
                 ComposantsEnum::LatchGate(latch_gate) => {
                     for wire_group in self.wire_groups.iter_mut() {
                         for pos in wire_group.positions.iter() {
@@ -324,6 +274,126 @@ impl Simulation {
                         }
                     }
                 }
+                ComposantsEnum::Splitter(Splitter { gate, .. })
+                | ComposantsEnum::Merger(Merger { gate, .. })
+                | ComposantsEnum::SubCircuit(SubCircuit { gate, .. })
+                | ComposantsEnum::FlipFlop(FlipFlop { gate, .. })
+                | ComposantsEnum::Rom(Rom { gate, .. })
+                | ComposantsEnum::Ram(Ram { gate, .. })
+                | ComposantsEnum::Register(Register { gate, .. })
+                | ComposantsEnum::Counter(Counter { gate, .. })
+                | ComposantsEnum::ShiftRegister(ShiftRegister { gate, .. })
+                | ComposantsEnum::Multiplexer(Multiplexer { gate, .. })
+                | ComposantsEnum::Demultiplexer(Demultiplexer { gate, .. })
+                | ComposantsEnum::Decoder(Decoder { gate, .. })
+                | ComposantsEnum::PriorityEncoder(PriorityEncoder { gate, .. })
+                | ComposantsEnum::Adder(Adder { gate, .. })
+                | ComposantsEnum::Comparator(Comparator { gate, .. })
+                | ComposantsEnum::Alu(Alu { gate, .. })
+                | ComposantsEnum::DipSwitch(DipSwitch { gate, .. })
+                | ComposantsEnum::Led(Led { gate, .. })
+                | ComposantsEnum::SevenSegment(SevenSegment { gate, .. })
+                | ComposantsEnum::PixelMatrix(PixelMatrix { gate, .. })
+                | ComposantsEnum::Keyboard(Keyboard { gate, .. })
+                | ComposantsEnum::Terminal(Terminal { gate, .. }) => {
+                    Self::connect_multi_pin_gate(gate, *composant_id, &mut self.wire_groups);
+                }
+                ComposantsEnum::AndGate(AndGate { gate })
+                | ComposantsEnum::OrGate(OrGate { gate })
+                | ComposantsEnum::XorGate(XorGate { gate })
+                | ComposantsEnum::NandGate(NandGate { gate })
+                | ComposantsEnum::NorGate(NorGate { gate })
+                | ComposantsEnum::XnorGate(XnorGate { gate }) => {
+                    Self::connect_multi_inputs_gate(gate, *composant_id, &mut self.wire_groups);
+                }
+                ComposantsEnum::Clock(Clock { gate, .. }) => {
+                    Self::connect_one_input_gate(gate, *composant_id, &mut self.wire_groups);
+                }
+                ComposantsEnum::InputPort(InputPort { port }) => {
+                    for wire_group in self.wire_groups.iter_mut() {
+                        if wire_group.positions.contains(&port.position) {
+                            console_log(&format!(
+                                "Connected (Input port) {} to wire group {}",
+                                composant_id, wire_group.circuit_element.id
+                            ));
+                            port.circuit_element
+                                .outputs
+                                .push(CircuitElementEnum::WireGroup(wire_group.circuit_element.id));
+                            wire_group
+                                .circuit_element
+                                .inputs
+                                .push(CircuitElementEnum::Component(*composant_id));
+                        }
+                    }
+                }
+                ComposantsEnum::Constant(constant) => {
+                    for wire_group in self.wire_groups.iter_mut() {
+                        if wire_group.positions.contains(&constant.position) {
+                            console_log(&format!(
+                                "Connected (Constant) {} to wire group {}",
+                                composant_id, wire_group.circuit_element.id
+                            ));
+                            constant
+                                .circuit_element
+                                .outputs
+                                .push(CircuitElementEnum::WireGroup(wire_group.circuit_element.id));
+                            wire_group
+                                .circuit_element
+                                .inputs
+                                .push(CircuitElementEnum::Component(*composant_id));
+                        }
+                    }
+                }
+                ComposantsEnum::Pull(pull) => {
+                    for wire_group in self.wire_groups.iter_mut() {
+                        if wire_group.positions.contains(&pull.position) {
+                            if wire_group.pull.is_some_and(|kind| kind != pull.kind) {
+                                console_log(&format!(
+                                    "Conflicting pulls on wire group {}",
+                                    wire_group.circuit_element.id
+                                ));
+                            }
+                            wire_group.pull = Some(pull.kind);
+                        }
+                    }
+                }
+                // Déjà traités par `merge_tunnel_groups`
+                ComposantsEnum::Tunnel(_) => (),
+                ComposantsEnum::Button(button) => {
+                    for wire_group in self.wire_groups.iter_mut() {
+                        if wire_group.positions.contains(&button.position) {
+                            console_log(&format!(
+                                "Connected (Button) {} to wire group {}",
+                                composant_id, wire_group.circuit_element.id
+                            ));
+                            button
+                                .circuit_element
+                                .outputs
+                                .push(CircuitElementEnum::WireGroup(wire_group.circuit_element.id));
+                            wire_group
+                                .circuit_element
+                                .inputs
+                                .push(CircuitElementEnum::Component(*composant_id));
+                        }
+                    }
+                }
+                ComposantsEnum::OutputPort(OutputPort { port }) => {
+                    for wire_group in self.wire_groups.iter_mut() {
+                        if wire_group.positions.contains(&port.position) {
+                            console_log(&format!(
+                                "Connected (Output port) {} to wire group {}",
+                                composant_id, wire_group.circuit_element.id
+                            ));
+                            port.circuit_element
+                                .inputs
+                                .push(CircuitElementEnum::WireGroup(wire_group.circuit_element.id));
+                            wire_group
+                                .circuit_element
+                                .outputs
+                                .push(CircuitElementEnum::Component(*composant_id));
+                        }
+                    }
+                }
                 ComposantsEnum::Switch(switch) => {
                     for wire_group in self.wire_groups.iter_mut() {
                         for pos in wire_group.positions.iter() {
@@ -348,5 +418,346 @@ impl Simulation {
                 }
             }
         }
+
+        self.expand_sub_circuits();
+        self.compute_component_widths();
+    }
+
+    // Fusionne les wire groups reliés par des tunnels de même label. Les groupes
+    // sont renumérotés, ce qui doit être fait avant de connecter les composants
+    fn merge_tunnel_groups(&mut self) {
+        let mut groups_by_label: HashMap<&str, Vec<usize>> = HashMap::new();
+        for composant in self.composants_map.values() {
+            let ComposantsEnum::Tunnel(tunnel) = composant else {
+                continue;
+            };
+            let groups = groups_by_label.entry(tunnel.label.as_str()).or_default();
+            for wire_group in self.wire_groups.iter() {
+                if wire_group.positions.contains(&tunnel.position) {
+                    groups.push(wire_group.circuit_element.id);
+                }
+            }
+        }
+
+        // Union des groupes : chaque groupe pointe vers le plus petit id de son réseau
+        let mut parents: Vec<usize> = (0..self.wire_groups.len()).collect();
+        for groups in groups_by_label.values() {
+            for pair in groups.windows(2) {
+                let (a, b) = (
+                    find_root(&mut parents, pair[0]),
+                    find_root(&mut parents, pair[1]),
+                );
+                parents[a.max(b)] = a.min(b);
+            }
+        }
+        if (0..parents.len()).all(|group| parents[group] == group) {
+            return;
+        }
+
+        let mut merged: Vec<Option<WireGroup>> = self.wire_groups.drain(..).map(Some).collect();
+        for group in (0..merged.len()).rev() {
+            let root = find_root(&mut parents, group);
+            if root != group {
+                let wire_group = merged[group].take().unwrap();
+                if let Some(target) = merged[root].as_mut() {
+                    console_log(&format!("Tunnel joins wire group {} to {}", group, root));
+                    target.absorb(wire_group);
+                }
+            }
+        }
+        self.wire_groups = merged.into_iter().flatten().collect();
+        for (index, wire_group) in self.wire_groups.iter_mut().enumerate() {
+            wire_group.circuit_element.id = index;
+        }
+    }
+
+    // Les portes logiques prennent la largeur du plus large de leurs wire groups d'entrée
+    pub(crate) fn compute_component_widths(&mut self) {
+        let wire_groups = &self.wire_groups;
+        for composant in self.composants_map.values_mut() {
+            // Les sorties d'un (dé)multiplexeur ont la largeur des données qu'il aiguille
+            match composant {
+                ComposantsEnum::Multiplexer(multiplexer) => {
+                    let ways = 1usize << multiplexer.select_bits;
+                    let width = groups_width(wire_groups, &multiplexer.gate.input_groups[..ways]);
+                    multiplexer.gate.output_widths = vec![width];
+                    continue;
+                }
+                ComposantsEnum::Demultiplexer(demultiplexer) => {
+                    let ways = 1usize << demultiplexer.select_bits;
+                    let width = groups_width(wire_groups, &demultiplexer.gate.input_groups[..1]);
+                    demultiplexer.gate.output_widths = vec![width; ways];
+                    continue;
+                }
+                _ => {}
+            }
+            if composant.multi_pin_gate().is_some() {
+                continue;
+            }
+            // Une constante n'a pas d'entrée : elle prend la largeur de ce qu'elle alimente
+            let is_source = matches!(composant, ComposantsEnum::Constant(_));
+            let circuit_element = composant.circuit_element_mut();
+            let connections = if is_source {
+                &circuit_element.outputs
+            } else {
+                &circuit_element.inputs
+            };
+            let width = connections
+                .iter()
+                .filter_map(|input| match input {
+                    CircuitElementEnum::WireGroup(id) => wire_groups.get(*id),
+                    _ => None,
+                })
+                .map(|group| group.circuit_element.width)
+                .max()
+                .unwrap_or(1);
+            circuit_element.width = width;
+        }
+    }
+
+    fn connect_multi_inputs_gate(
+        gate: &mut MultiInputsGate,
+        composant_id: usize,
+        wire_groups: &mut [WireGroup],
+    ) {
+        for wire_group in wire_groups.iter_mut() {
+            let group_id = wire_group.circuit_element.id;
+            if wire_group.positions.contains(&gate.output_position) {
+                console_log(&format!(
+                    "Connected output {} to wire group {}",
+                    composant_id, group_id
+                ));
+                gate.circuit_element
+                    .outputs
+                    .push(CircuitElementEnum::WireGroup(group_id));
+                wire_group
+                    .circuit_element
+                    .inputs
+                    .push(CircuitElementEnum::Component(composant_id));
+            }
+            for (pin, input_pos) in gate.input_positions.iter().enumerate() {
+                if wire_group.positions.contains(input_pos) {
+                    console_log(&format!(
+                        "Connected input {} of {} to wire group {}",
+                        pin, composant_id, group_id
+                    ));
+                    gate.input_groups[pin] = Some(group_id);
+                    gate.circuit_element
+                        .inputs
+                        .push(CircuitElementEnum::WireGroup(group_id));
+                    wire_group
+                        .circuit_element
+                        .outputs
+                        .push(CircuitElementEnum::Component(composant_id));
+                }
+            }
+        }
+    }
+
+    fn connect_one_input_gate(
+        gate: &mut OneInputGate,
+        composant_id: usize,
+        wire_groups: &mut [WireGroup],
+    ) {
+        for wire_group in wire_groups.iter_mut() {
+            let group_id = wire_group.circuit_element.id;
+            if wire_group.positions.contains(&gate.output_position) {
+                console_log(&format!(
+                    "Connected output {} to wire group {}",
+                    composant_id, group_id
+                ));
+                gate.circuit_element
+                    .outputs
+                    .push(CircuitElementEnum::WireGroup(group_id));
+                wire_group
+                    .circuit_element
+                    .inputs
+                    .push(CircuitElementEnum::Component(composant_id));
+            }
+            if wire_group.positions.contains(&gate.input_position) {
+                console_log(&format!(
+                    "Connected input {} to wire group {}",
+                    composant_id, group_id
+                ));
+                gate.circuit_element
+                    .inputs
+                    .push(CircuitElementEnum::WireGroup(group_id));
+                wire_group
+                    .circuit_element
+                    .outputs
+                    .push(CircuitElementEnum::Component(composant_id));
+            }
+        }
+    }
+
+    fn connect_multi_pin_gate(
+        gate: &mut MultiPinGate,
+        composant_id: usize,
+        wire_groups: &mut [WireGroup],
+    ) {
+        for wire_group in wire_groups.iter_mut() {
+            let group_id = wire_group.circuit_element.id;
+            for (pin, input_pos) in gate.input_positions.iter().enumerate() {
+                if wire_group.positions.contains(input_pos) {
+                    console_log(&format!(
+                        "Connected input {} of {} to wire group {}",
+                        pin, composant_id, group_id
+                    ));
+                    gate.input_groups[pin] = Some(group_id);
+                    gate.circuit_element
+                        .inputs
+                        .push(CircuitElementEnum::WireGroup(group_id));
+                    wire_group
+                        .circuit_element
+                        .outputs
+                        .push(CircuitElementEnum::Component(composant_id));
+                }
+            }
+            for (pin, output_pos) in gate.output_positions.iter().enumerate() {
+                if wire_group.positions.contains(output_pos) {
+                    console_log(&format!(
+                        "Connected output {} of {} to wire group {}",
+                        pin, composant_id, group_id
+                    ));
+                    gate.circuit_element
+                        .outputs
+                        .push(CircuitElementEnum::WireGroup(group_id));
+                    wire_group
+                        .circuit_element
+                        .inputs
+                        .push(CircuitElementEnum::ComponentPin(composant_id, pin));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::{ConnectionMode, Orientation};
+    use crate::wire::Wire;
+    use crate::Simulation;
+
+    fn wire_state(sim: &Simulation, wire: usize) -> u64 {
+        sim.wire_groups
+            .iter()
+            .find(|wire_group| wire_group.wires.contains(&wire))
+            .map_or(0, |wire_group| {
+                sim.wire_group_state(wire_group.circuit_element.id)
+            })
+    }
+
+    #[test]
+    fn constants_take_the_width_of_the_bus_they_drive() {
+        let mut sim = Simulation::new();
+        sim.add_constant_high(vec![0, 0], 1);
+        sim.add_constant_low(vec![0, 10], 2);
+        sim.wires_map
+            .insert(10, Wire::new_bus(10, vec![[0, 0], [10, 0]], 4));
+        sim.wires_map
+            .insert(11, Wire::new_bus(11, vec![[0, 10], [10, 10]], 4));
+        sim.compute_connections();
+        sim.compute_frame(100, 1);
+        assert_eq!(wire_state(&sim, 10), 0b1111);
+        assert_eq!(wire_state(&sim, 11), 0);
+        assert!(sim.floating_wires().is_empty());
+    }
+
+    #[test]
+    fn pulls_set_undriven_groups_and_yield_to_drivers() {
+        let mut sim = Simulation::new();
+        sim.add_pull_up(vec![10, 0], 1);
+        sim.add_pull_down(vec![10, 10], 2);
+        sim.add_pull_up(vec![10, 20], 3);
+        sim.add_switch(vec![0, 20], 4);
+        sim.wires_map
+            .insert(10, Wire::new_bus(10, vec![[0, 0], [10, 0]], 8));
+        sim.wires_map
+            .insert(11, Wire::new(11, vec![[0, 10], [10, 10]]));
+        sim.wires_map
+            .insert(12, Wire::new(12, vec![[0, 20], [10, 20]]));
+        sim.wires_map
+            .insert(13, Wire::new(13, vec![[0, 30], [10, 30]]));
+        sim.compute_connections();
+        sim.compute_frame(100, 1);
+        assert_eq!(wire_state(&sim, 10), 0xff);
+        assert_eq!(wire_state(&sim, 11), 0);
+        // Le switch éteint pilote le groupe à 0 malgré le tirage vers le haut
+        assert_eq!(wire_state(&sim, 12), 0);
+        assert_eq!(sim.floating_wires(), vec![13]);
+    }
+
+    #[test]
+    fn tunnels_with_the_same_label_join_their_groups() {
+        let mut sim = Simulation::new();
+        sim.add_switch(vec![0, 0], 1);
+        sim.add_tunnel(vec![10, 0], "clk".to_string(), 2);
+        sim.add_tunnel(vec![50, 0], "clk".to_string(), 3);
+        sim.add_tunnel(vec![50, 20], "data".to_string(), 4);
+        sim.add_led(vec![60, 0], 0, Orientation::Right, 5);
+        sim.add_led(vec![60, 20], 0, Orientation::Right, 6);
+        sim.wires_map
+            .insert(10, Wire::new(10, vec![[0, 0], [10, 0]]));
+        sim.wires_map
+            .insert(11, Wire::new(11, vec![[50, 0], [60, 0]]));
+        sim.wires_map
+            .insert(12, Wire::new(12, vec![[50, 20], [60, 20]]));
+        sim.compute_connections();
+        assert_eq!(sim.wire_groups.len(), 2);
+
+        sim.update_switch_state(1, true);
+        sim.compute_frame(100, 1);
+        assert_eq!(sim.get_component_state(5), 1);
+        // Un label sans second tunnel ne relie rien
+        assert_eq!(sim.get_component_state(6), 0);
+        assert_eq!(sim.floating_wires(), vec![12]);
+    }
+
+    // Un wire horizontal et un wire vertical dont l'extrémité touche le milieu du premier,
+    // plus un wire qui croise le premier sans s'y arrêter. Le switch 1 pilote le premier,
+    // les LEDs 2 et 3 sont au bout des deux autres
+    fn crossing_circuit(mode: ConnectionMode, junction: bool) -> Simulation {
+        let mut sim = Simulation::new();
+        sim.set_connection_mode(mode);
+        sim.add_switch(vec![0, 0], 1);
+        sim.add_led(vec![10, 10], 0, Orientation::Right, 2);
+        sim.add_led(vec![15, 10], 0, Orientation::Right, 3);
+        sim.wires_map
+            .insert(10, Wire::new(10, vec![[0, 0], [20, 0]]));
+        sim.wires_map
+            .insert(11, Wire::new(11, vec![[10, 0], [10, 10]]));
+        sim.wires_map
+            .insert(12, Wire::new(12, vec![[15, -10], [15, 10]]));
+        if junction {
+            sim.add_junction(vec![15, 0]);
+        }
+        sim.compute_connections();
+        sim.update_switch_state(1, true);
+        sim.compute_frame(100, 1);
+        sim
+    }
+
+    fn group_of(sim: &Simulation, wire: usize) -> Option<usize> {
+        sim.wire_groups
+            .iter()
+            .position(|wire_group| wire_group.wires.contains(&wire))
+    }
+
+    #[test]
+    fn endpoints_mode_joins_tees_and_junctions_but_not_crossings() {
+        let sim = crossing_circuit(ConnectionMode::Legacy, false);
+        assert_eq!(sim.get_connection_mode(), ConnectionMode::Legacy);
+        // Sans sommet commun, le mode historique ne relie rien
+        assert_eq!(sim.wire_groups.len(), 3);
+
+        let sim = crossing_circuit(ConnectionMode::Endpoints, false);
+        assert_eq!(group_of(&sim, 10), group_of(&sim, 11));
+        assert_ne!(group_of(&sim, 10), group_of(&sim, 12));
+        assert_eq!(sim.get_component_state(2), 1);
+        assert_eq!(sim.get_component_state(3), 0);
+
+        let sim = crossing_circuit(ConnectionMode::Endpoints, true);
+        assert_eq!(sim.wire_groups.len(), 1);
+        assert_eq!(sim.get_component_state(3), 1);
     }
 }
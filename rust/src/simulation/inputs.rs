@@ -0,0 +1,196 @@
+use crate::components::{Button, DipSwitch, Keyboard};
+use crate::utils::console_log;
+use crate::ComposantsEnum;
+use crate::Simulation;
+
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+impl Simulation {
+    pub fn press_button(&mut self, component_index: usize) {
+        if let Some(button) = self.button_mut(component_index) {
+            button.press();
+        }
+    }
+
+    pub fn release_button(&mut self, component_index: usize) {
+        if let Some(button) = self.button_mut(component_index) {
+            button.release();
+        }
+    }
+
+    // `step` est comparé à `get_step_counter` : l'évènement est appliqué au début de ce pas
+    pub fn schedule_button_event(&mut self, component_index: usize, step: u32, pressed: bool) {
+        if let Some(button) = self.button_mut(component_index) {
+            button.schedule(step, pressed);
+        }
+    }
+
+    pub fn set_dip_switch_value(&mut self, component_index: usize, value: u64) {
+        if let Some(dip_switch) = self.dip_switch_mut(component_index) {
+            dip_switch.set_value(value);
+        }
+    }
+
+    pub fn set_dip_switch_bit(&mut self, component_index: usize, bit: u8, state: bool) {
+        if let Some(dip_switch) = self.dip_switch_mut(component_index) {
+            dip_switch.set_bit(bit, state);
+        }
+    }
+
+    // Renvoie le nombre de caractères acceptés avant que la file soit pleine
+    pub fn push_keyboard_text(&mut self, component_index: usize, text: String) -> usize {
+        match self.keyboard_mut(component_index) {
+            Some(keyboard) => text.bytes().take_while(|key| keyboard.push(*key)).count(),
+            None => 0,
+        }
+    }
+
+    pub fn push_keyboard_key(&mut self, component_index: usize, key: u8) -> bool {
+        self.keyboard_mut(component_index)
+            .is_some_and(|keyboard| keyboard.push(key))
+    }
+
+    pub fn get_keyboard_pending(&self, component_index: usize) -> usize {
+        match self.composants_map.get(&component_index) {
+            Some(ComposantsEnum::Keyboard(keyboard)) => keyboard.buffer.len(),
+            _ => 0,
+        }
+    }
+
+    pub fn get_dip_switch_value(&self, component_index: usize) -> Option<u64> {
+        match self.composants_map.get(&component_index) {
+            Some(ComposantsEnum::DipSwitch(dip_switch)) => Some(dip_switch.value),
+            _ => None,
+        }
+    }
+}
+
+impl Simulation {
+    fn button_mut(&mut self, component_index: usize) -> Option<&mut Button> {
+        match self.composants_map.get_mut(&component_index) {
+            Some(ComposantsEnum::Button(button)) => Some(button),
+            _ => {
+                console_log("Invalid button index");
+                None
+            }
+        }
+    }
+
+    fn keyboard_mut(&mut self, component_index: usize) -> Option<&mut Keyboard> {
+        match self.composants_map.get_mut(&component_index) {
+            Some(ComposantsEnum::Keyboard(keyboard)) => Some(keyboard),
+            _ => {
+                console_log("Invalid keyboard index");
+                None
+            }
+        }
+    }
+
+    fn dip_switch_mut(&mut self, component_index: usize) -> Option<&mut DipSwitch> {
+        match self.composants_map.get_mut(&component_index) {
+            Some(ComposantsEnum::DipSwitch(dip_switch)) => Some(dip_switch),
+            _ => {
+                console_log("Invalid dip switch index");
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::simulation::bench::{apply, drive_inputs, outputs};
+    use crate::types::Orientation;
+    use crate::wire::Wire;
+    use crate::Simulation;
+
+    // Bouton 1 relié à la LED 2
+    fn button_circuit(hold_ticks: u32) -> Simulation {
+        let mut sim = Simulation::new();
+        sim.add_button(vec![-10, 0], hold_ticks, 1);
+        sim.add_led(vec![0, 0], 0, Orientation::Right, 2);
+        sim.wires_map
+            .insert(10, Wire::new(10, vec![[-10, 0], [0, 0]]));
+        sim.compute_connections();
+        sim
+    }
+
+    fn led_levels(sim: &mut Simulation, frames: usize) -> Vec<u64> {
+        (0..frames)
+            .map(|_| {
+                sim.compute_frame(100, 1);
+                sim.get_component_state(2)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn button_is_high_while_held_or_for_its_hold_ticks() {
+        let mut sim = button_circuit(0);
+        sim.press_button(1);
+        assert_eq!(led_levels(&mut sim, 2), vec![1, 1]);
+        sim.release_button(1);
+        assert_eq!(led_levels(&mut sim, 1), vec![0]);
+
+        let mut sim = button_circuit(2);
+        sim.press_button(1);
+        assert_eq!(led_levels(&mut sim, 3), vec![1, 1, 0]);
+    }
+
+    #[test]
+    fn press_and_release_in_the_same_step_keep_the_pulse() {
+        let mut sim = button_circuit(0);
+        sim.press_button(1);
+        sim.release_button(1);
+        assert_eq!(led_levels(&mut sim, 2), vec![1, 0]);
+
+        let mut sim = button_circuit(0);
+        sim.schedule_button_event(1, 2, false);
+        sim.schedule_button_event(1, 2, true);
+        sim.schedule_button_event(1, 2, false);
+        assert_eq!(led_levels(&mut sim, 4), vec![0, 0, 1, 0]);
+    }
+
+    #[test]
+    fn dip_switch_sets_several_bits_at_once() {
+        let mut sim = Simulation::new();
+        sim.add_dip_switch(vec![0, 0], 4, false, Orientation::Right, 1);
+        sim.add_dip_switch(vec![0, 20], 4, true, Orientation::Right, 2);
+        sim.compute_connections();
+        sim.set_dip_switch_value(1, 0b1_0110);
+        sim.set_dip_switch_bit(1, 0, true);
+        sim.set_dip_switch_value(2, 0b0110);
+        sim.compute_frame(100, 1);
+        assert_eq!(sim.get_dip_switch_value(1), Some(0b0111));
+        assert_eq!(outputs(&sim, 1), vec![1, 1, 1, 0]);
+        // En mode radio, un seul interrupteur reste fermé
+        assert_eq!(sim.get_dip_switch_value(2), Some(0b0010));
+        sim.set_dip_switch_bit(2, 3, true);
+        sim.compute_frame(100, 1);
+        assert_eq!(outputs(&sim, 2), vec![0, 0, 0, 1]);
+    }
+
+    #[test]
+    fn keyboard_pops_a_key_on_each_read_edge() {
+        let mut sim = Simulation::new();
+        sim.add_keyboard(vec![0, 0], 3, Orientation::Right, 1);
+        // [READ, CLR]
+        let sources = drive_inputs(&mut sim, 1, &[]);
+        sim.compute_connections();
+        assert_eq!(sim.push_keyboard_text(1, "abcd".to_string()), 3);
+        assert!(!sim.push_keyboard_key(1, b'e'));
+        apply(&mut sim, &sources, &[0, 0]);
+        assert_eq!(outputs(&sim, 1), vec![b'a' as u64, 1]);
+
+        apply(&mut sim, &sources, &[1, 0]);
+        assert_eq!(outputs(&sim, 1), vec![b'b' as u64, 1]);
+        // READ maintenu haut : pas de nouveau retrait
+        apply(&mut sim, &sources, &[1, 0]);
+        assert_eq!(sim.get_keyboard_pending(1), 2);
+
+        apply(&mut sim, &sources, &[0, 1]);
+        assert_eq!(sim.get_keyboard_pending(1), 0);
+        assert_eq!(outputs(&sim, 1), vec![0, 0]);
+    }
+}
@@ -0,0 +1,457 @@
+use std::collections::{BTreeMap, HashSet};
+
+use crate::utils::console_log;
+
+// Au-delà, la table de vérité et la minimisation deviennent trop coûteuses
+pub const MAX_SYNTHESIS_VARIABLES: usize = 12;
+
+// Fonction booléenne sur des variables indexées. Les constructeurs `not`, `and`, `or`
+// et `xor` simplifient et trient leurs opérandes, ce qui permet de partager les
+// sous-expressions identiques
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub(crate) enum Logic {
+    Constant(bool),
+    Variable(usize),
+    Not(Box<Logic>),
+    And(Vec<Logic>),
+    Or(Vec<Logic>),
+    Xor(Vec<Logic>),
+}
+
+impl Logic {
+    pub fn literal(variable: usize, positive: bool) -> Logic {
+        if positive {
+            Logic::Variable(variable)
+        } else {
+            Logic::not(Logic::Variable(variable))
+        }
+    }
+
+    pub fn not(logic: Logic) -> Logic {
+        match logic {
+            Logic::Constant(value) => Logic::Constant(!value),
+            Logic::Not(inner) => *inner,
+            logic => Logic::Not(Box::new(logic)),
+        }
+    }
+
+    pub fn and(terms: Vec<Logic>) -> Logic {
+        Self::associative(terms, false)
+    }
+
+    pub fn or(terms: Vec<Logic>) -> Logic {
+        let logic = Self::associative(terms, true);
+        // x·y + !x·!y = !(x ⊕ y)
+        if let Logic::Or(terms) = &logic {
+            if let [Logic::And(first), Logic::And(second)] = terms.as_slice() {
+                if let ([x, y], [u, v]) = (first.as_slice(), second.as_slice()) {
+                    let (not_x, not_y) = (Logic::not(x.clone()), Logic::not(y.clone()));
+                    if (*u == not_x && *v == not_y) || (*u == not_y && *v == not_x) {
+                        return Logic::not(Logic::xor(vec![x.clone(), y.clone()]));
+                    }
+                }
+            }
+        }
+        logic
+    }
+
+    // ET (`is_or` faux) ou OU de `terms`, aplati, trié et sans doublon
+    fn associative(terms: Vec<Logic>, is_or: bool) -> Logic {
+        let mut flat = Vec::new();
+        for term in terms {
+            match term {
+                Logic::Constant(value) if value == is_or => return Logic::Constant(is_or),
+                Logic::Constant(_) => (),
+                Logic::And(inner) if !is_or => flat.extend(inner),
+                Logic::Or(inner) if is_or => flat.extend(inner),
+                term => flat.push(term),
+            }
+        }
+        flat.sort();
+        flat.dedup();
+        // x·!x = 0, x + !x = 1
+        if flat
+            .iter()
+            .any(|term| flat.binary_search(&Logic::not(term.clone())).is_ok())
+        {
+            return Logic::Constant(is_or);
+        }
+        match flat.len() {
+            0 => Logic::Constant(!is_or),
+            1 => flat.remove(0),
+            _ if is_or => Logic::Or(flat),
+            _ => Logic::And(flat),
+        }
+    }
+
+    // Les négations des opérandes sont sorties du OU exclusif
+    pub fn xor(terms: Vec<Logic>) -> Logic {
+        let mut inverted = false;
+        let mut flat: Vec<Logic> = Vec::new();
+        let mut pending = terms;
+        while let Some(term) = pending.pop() {
+            match term {
+                Logic::Constant(value) => inverted ^= value,
+                Logic::Not(inner) => {
+                    inverted = !inverted;
+                    pending.push(*inner);
+                }
+                Logic::Xor(inner) => pending.extend(inner),
+                term => flat.push(term),
+            }
+        }
+        flat.sort();
+        // x ⊕ x = 0
+        let mut kept: Vec<Logic> = Vec::with_capacity(flat.len());
+        for term in flat {
+            if kept.last() == Some(&term) {
+                kept.pop();
+            } else {
+                kept.push(term);
+            }
+        }
+        let logic = match kept.len() {
+            0 => Logic::Constant(false),
+            1 => kept.remove(0),
+            _ => Logic::Xor(kept),
+        };
+        if inverted {
+            Logic::not(logic)
+        } else {
+            logic
+        }
+    }
+
+    // Valeur pour la ligne `row` d'une table de vérité à `count` variables,
+    // la première variable étant le bit de poids fort
+    pub fn eval(&self, row: u32, count: usize) -> bool {
+        match self {
+            Logic::Constant(value) => *value,
+            Logic::Variable(index) => (row >> (count - 1 - index)) & 1 == 1,
+            Logic::Not(inner) => !inner.eval(row, count),
+            Logic::And(terms) => terms.iter().all(|term| term.eval(row, count)),
+            Logic::Or(terms) => terms.iter().any(|term| term.eval(row, count)),
+            Logic::Xor(terms) => terms
+                .iter()
+                .fold(false, |acc, term| acc ^ term.eval(row, count)),
+        }
+    }
+
+    fn rename(self, indices: &[usize]) -> Logic {
+        let rename_all =
+            |terms: Vec<Logic>| terms.into_iter().map(|term| term.rename(indices)).collect();
+        match self {
+            Logic::Constant(value) => Logic::Constant(value),
+            Logic::Variable(index) => Logic::Variable(indices[index]),
+            Logic::Not(inner) => Logic::not(inner.rename(indices)),
+            Logic::And(terms) => Logic::and(rename_all(terms)),
+            Logic::Or(terms) => Logic::or(rename_all(terms)),
+            Logic::Xor(terms) => Logic::xor(rename_all(terms)),
+        }
+    }
+
+    // Écriture relisible par `parse_expression`
+    pub fn to_text(&self, names: &[String]) -> String {
+        self.text_with_precedence(names, 0)
+    }
+
+    // Précédences : OU 1, OU exclusif 2, ET 3
+    fn text_with_precedence(&self, names: &[String], outer: u8) -> String {
+        let join = |terms: &[Logic], separator: &str, precedence: u8| {
+            let text = terms
+                .iter()
+                .map(|term| term.text_with_precedence(names, precedence))
+                .collect::<Vec<String>>()
+                .join(separator);
+            if precedence < outer {
+                format!("({})", text)
+            } else {
+                text
+            }
+        };
+        match self {
+            Logic::Constant(value) => (*value as u8).to_string(),
+            Logic::Variable(index) => names[*index].clone(),
+            Logic::Not(inner) => format!("!{}", inner.text_with_precedence(names, 4)),
+            Logic::Or(terms) => join(terms, " | ", 1),
+            Logic::Xor(terms) => join(terms, " ^ ", 2),
+            Logic::And(terms) => join(terms, " & ", 3),
+        }
+    }
+}
+
+// Analyse une expression : variables [A-Za-z_][A-Za-z0-9_]*, constantes 0 et 1,
+// négation `!`, `~` ou `'` postfixé, ET `&`, `*` ou `.`, OU exclusif `^`, OU `|` ou `+`,
+// par précédence décroissante. Renvoie l'expression et ses variables par ordre alphabétique
+pub(crate) fn parse_expression(expression: &str) -> Option<(Logic, Vec<String>)> {
+    let mut parser = Parser {
+        chars: expression.chars().collect(),
+        position: 0,
+        variables: Vec::new(),
+    };
+    let logic = parser.parse_or();
+    parser.skip_spaces();
+    let logic = match logic {
+        Some(logic) if parser.position == parser.chars.len() => logic,
+        _ => {
+            console_log(&format!(
+                "Invalid expression at character {}",
+                parser.position
+            ));
+            return None;
+        }
+    };
+
+    let mut names = parser.variables.clone();
+    names.sort();
+    let indices: Vec<usize> = parser
+        .variables
+        .iter()
+        .map(|name| names.binary_search(name).unwrap_or(0))
+        .collect();
+    Some((logic.rename(&indices), names))
+}
+
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+    variables: Vec<String>,
+}
+
+impl Parser {
+    fn skip_spaces(&mut self) {
+        while self.position < self.chars.len() && self.chars[self.position].is_whitespace() {
+            self.position += 1;
+        }
+    }
+
+    // Consomme le prochain caractère s'il fait partie de `operators`
+    fn eat(&mut self, operators: &[char]) -> bool {
+        self.skip_spaces();
+        match self.chars.get(self.position) {
+            Some(c) if operators.contains(c) => {
+                self.position += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn parse_or(&mut self) -> Option<Logic> {
+        let mut terms = vec![self.parse_xor()?];
+        while self.eat(&['|', '+']) {
+            terms.push(self.parse_xor()?);
+        }
+        Some(Logic::or(terms))
+    }
+
+    fn parse_xor(&mut self) -> Option<Logic> {
+        let mut terms = vec![self.parse_and()?];
+        while self.eat(&['^']) {
+            terms.push(self.parse_and()?);
+        }
+        Some(Logic::xor(terms))
+    }
+
+    fn parse_and(&mut self) -> Option<Logic> {
+        let mut terms = vec![self.parse_unary()?];
+        while self.eat(&['&', '*', '.']) {
+            terms.push(self.parse_unary()?);
+        }
+        Some(Logic::and(terms))
+    }
+
+    fn parse_unary(&mut self) -> Option<Logic> {
+        if self.eat(&['!', '~']) {
+            return Some(Logic::not(self.parse_unary()?));
+        }
+        let mut logic = self.parse_primary()?;
+        while self.eat(&['\'']) {
+            logic = Logic::not(logic);
+        }
+        Some(logic)
+    }
+
+    fn parse_primary(&mut self) -> Option<Logic> {
+        if self.eat(&['(']) {
+            let logic = self.parse_or()?;
+            return self.eat(&[')']).then_some(logic);
+        }
+        if self.eat(&['0']) {
+            return Some(Logic::Constant(false));
+        }
+        if self.eat(&['1']) {
+            return Some(Logic::Constant(true));
+        }
+        let start = self.position;
+        while let Some(c) = self.chars.get(self.position) {
+            let is_name_char = c.is_ascii_alphabetic()
+                || *c == '_'
+                || (self.position > start && c.is_ascii_digit());
+            if !is_name_char {
+                break;
+            }
+            self.position += 1;
+        }
+        if self.position == start {
+            return None;
+        }
+        let name: String = self.chars[start..self.position].iter().collect();
+        let index = match self.variables.iter().position(|variable| *variable == name) {
+            Some(index) => index,
+            None => {
+                self.variables.push(name);
+                self.variables.len() - 1
+            }
+        };
+        Some(Logic::Variable(index))
+    }
+}
+
+// Cube de Quine-McCluskey : les bits de `dashes` sont libres, ceux de `value` fixés
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct Cube {
+    value: u32,
+    dashes: u32,
+}
+
+impl Cube {
+    fn covers(&self, row: u32) -> bool {
+        row & !self.dashes == self.value
+    }
+
+    // Littéraux (variable, polarité), la première variable étant le bit de poids fort
+    fn literals(&self, count: usize) -> Vec<(usize, bool)> {
+        (0..count)
+            .filter_map(|variable| {
+                let bit = 1 << (count - 1 - variable);
+                (self.dashes & bit == 0).then_some((variable, self.value & bit != 0))
+            })
+            .collect()
+    }
+}
+
+// Couverture minimale en somme de produits des lignes `ones`, les lignes `dont_cares`
+// pouvant valoir 0 ou 1 : impliquants premiers de Quine-McCluskey, puis les essentiels
+// et, à défaut, celui qui couvre le plus de lignes restantes
+pub(crate) fn minimize(count: usize, ones: &[u32], dont_cares: &[u32]) -> Vec<Vec<(usize, bool)>> {
+    let mut current: HashSet<Cube> = ones
+        .iter()
+        .chain(dont_cares)
+        .map(|row| Cube {
+            value: *row,
+            dashes: 0,
+        })
+        .collect();
+    let mut primes: Vec<Cube> = Vec::new();
+    while !current.is_empty() {
+        let mut next = HashSet::new();
+        let mut combined = HashSet::new();
+        for cube in &current {
+            for variable in 0..count {
+                let bit = 1 << variable;
+                if (cube.dashes | cube.value) & bit != 0 {
+                    continue;
+                }
+                let partner = Cube {
+                    value: cube.value | bit,
+                    dashes: cube.dashes,
+                };
+                if current.contains(&partner) {
+                    next.insert(Cube {
+                        value: cube.value,
+                        dashes: cube.dashes | bit,
+                    });
+                    combined.insert(*cube);
+                    combined.insert(partner);
+                }
+            }
+        }
+        primes.extend(current.difference(&combined));
+        current = next;
+    }
+    primes.sort();
+
+    let mut uncovered: Vec<u32> = ones.to_vec();
+    let mut cover: Vec<Cube> = Vec::new();
+    for row in ones {
+        let mut covering = primes.iter().filter(|prime| prime.covers(*row));
+        if let (Some(prime), None) = (covering.next(), covering.next()) {
+            if !cover.contains(prime) {
+                cover.push(*prime);
+            }
+        }
+    }
+    uncovered.retain(|row| !cover.iter().any(|cube| cube.covers(*row)));
+    while !uncovered.is_empty() {
+        let best = primes
+            .iter()
+            .max_by_key(|prime| {
+                let covered = uncovered.iter().filter(|row| prime.covers(**row)).count();
+                (covered, prime.dashes.count_ones())
+            })
+            .copied();
+        let Some(best) = best else {
+            break;
+        };
+        uncovered.retain(|row| !best.covers(*row));
+        cover.push(best);
+    }
+    cover.sort();
+    cover.iter().map(|cube| cube.literals(count)).collect()
+}
+
+pub(crate) fn sum_of_products(cubes: &[Vec<(usize, bool)>]) -> Logic {
+    Logic::or(
+        cubes
+            .iter()
+            .map(|cube| {
+                Logic::and(
+                    cube.iter()
+                        .map(|(variable, positive)| Logic::literal(*variable, *positive))
+                        .collect(),
+                )
+            })
+            .collect(),
+    )
+}
+
+// Forme factorisée : le littéral le plus fréquent est mis en facteur, récursivement.
+// Les motifs x·y + !x·!y deviennent des OU exclusifs
+pub(crate) fn factor(cubes: &[Vec<(usize, bool)>]) -> Logic {
+    if cubes.len() <= 1 || cubes.iter().any(Vec::is_empty) {
+        return sum_of_products(cubes);
+    }
+    let mut counts: BTreeMap<(usize, bool), usize> = BTreeMap::new();
+    for literal in cubes.iter().flatten() {
+        *counts.entry(*literal).or_default() += 1;
+    }
+    let mut best: Option<((usize, bool), usize)> = None;
+    for (literal, count) in counts {
+        if best.is_none_or(|(_, best_count)| count > best_count) {
+            best = Some((literal, count));
+        }
+    }
+    let Some((literal, count)) = best.filter(|(_, count)| *count >= 2) else {
+        return sum_of_products(cubes);
+    };
+
+    let mut quotient = Vec::with_capacity(count);
+    let mut remainder = Vec::new();
+    for cube in cubes {
+        if cube.contains(&literal) {
+            quotient.push(cube.iter().copied().filter(|l| *l != literal).collect());
+        } else {
+            remainder.push(cube.clone());
+        }
+    }
+    let factored = Logic::and(vec![
+        Logic::literal(literal.0, literal.1),
+        factor(&quotient),
+    ]);
+    if remainder.is_empty() {
+        factored
+    } else {
+        Logic::or(vec![factored, factor(&remainder)])
+    }
+}
@@ -0,0 +1,140 @@
+use crate::components::{parse_intel_hex, parse_logisim_raw, MemoryBank};
+use crate::utils::console_log;
+use crate::Simulation;
+
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+impl Simulation {
+    pub fn read_memory(&self, component_index: usize, address: usize, len: usize) -> Vec<u64> {
+        match self
+            .composants_map
+            .get(&component_index)
+            .and_then(|composant| composant.memory_bank())
+        {
+            Some(memory) => memory.read(address, len),
+            None => {
+                console_log("Invalid memory index");
+                Vec::new()
+            }
+        }
+    }
+
+    pub fn write_memory(&mut self, component_index: usize, address: usize, values: Vec<u64>) {
+        if let Some(memory) = self.memory_bank_mut(component_index) {
+            memory.write(address, &values);
+        }
+    }
+
+    // Octets bruts, `ceil(data_bits / 8)` octets par mot en commençant par le poids faible
+    pub fn load_memory_bytes(&mut self, component_index: usize, bytes: Vec<u8>) {
+        if let Some(memory) = self.memory_bank_mut(component_index) {
+            memory.load_bytes(0, &bytes);
+        }
+    }
+
+    // Les adresses du fichier sont en octets, comme pour `load_memory_bytes`
+    pub fn load_memory_intel_hex(&mut self, component_index: usize, text: String) -> bool {
+        let blocks = match parse_intel_hex(&text) {
+            Ok(blocks) => blocks,
+            Err(error) => {
+                console_log(&format!("Invalid Intel HEX file: {}", error));
+                return false;
+            }
+        };
+        let Some(memory) = self.memory_bank_mut(component_index) else {
+            return false;
+        };
+        let bytes_per_word = memory.data_bits.div_ceil(8) as usize;
+        let mut bytes = vec![0u8; memory.words.len() * bytes_per_word];
+        for (address, data) in blocks {
+            for (index, byte) in data.iter().enumerate() {
+                if let Some(slot) = bytes.get_mut(address + index) {
+                    *slot = *byte;
+                }
+            }
+        }
+        memory.load_bytes(0, &bytes);
+        true
+    }
+
+    pub fn load_memory_logisim(&mut self, component_index: usize, text: String) -> bool {
+        let Some(memory) = self.memory_bank_mut(component_index) else {
+            return false;
+        };
+        let words = match parse_logisim_raw(&text, memory.words.len()) {
+            Ok(words) => words,
+            Err(error) => {
+                console_log(&format!("Invalid Logisim image: {}", error));
+                return false;
+            }
+        };
+        memory.words.iter_mut().for_each(|word| *word = 0);
+        memory.write(0, &words);
+        true
+    }
+}
+
+impl Simulation {
+    fn memory_bank_mut(&mut self, component_index: usize) -> Option<&mut MemoryBank> {
+        let memory = self
+            .composants_map
+            .get_mut(&component_index)
+            .and_then(|composant| composant.memory_bank_mut());
+        if memory.is_none() {
+            console_log("Invalid memory index");
+        }
+        memory
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::simulation::bench::{apply, drive_inputs, outputs};
+    use crate::types::Orientation;
+    use crate::Simulation;
+
+    #[test]
+    fn rom_reads_a_logisim_image_through_its_address_bus() {
+        let mut sim = Simulation::new();
+        sim.add_rom(vec![0, 0], 3, 8, true, Orientation::Right, 1);
+        let sources = drive_inputs(&mut sim, 1, &[3]);
+        sim.compute_connections();
+        assert!(sim.load_memory_logisim(1, "v2.0 raw\n10 20 2*33 ff\n".to_string()));
+        let words: Vec<u64> = (0..8)
+            .map(|address| {
+                apply(&mut sim, &sources, &[address]);
+                outputs(&sim, 1)[0]
+            })
+            .collect();
+        assert_eq!(words, vec![0x10, 0x20, 0x33, 0x33, 0xff, 0, 0, 0]);
+
+        // Une image trop grande est refusée sans toucher au contenu
+        assert!(!sim.load_memory_logisim(1, "v2.0 raw\n9*1\n".to_string()));
+        assert_eq!(sim.read_memory(1, 0, 2), vec![0x10, 0x20]);
+    }
+
+    #[test]
+    fn ram_writes_on_the_rising_edge_when_enabled() {
+        let mut sim = Simulation::new();
+        sim.add_ram(vec![0, 0], 2, 2, false, Orientation::Right, 1);
+        // [A0, A1, D0, D1, WE, CLK], un bit par pin
+        let sources = drive_inputs(&mut sim, 1, &[]);
+        sim.compute_connections();
+
+        apply(&mut sim, &sources, &[1, 1, 0, 1, 1, 0]);
+        apply(&mut sim, &sources, &[1, 1, 0, 1, 1, 1]);
+        assert_eq!(outputs(&sim, 1), vec![0, 1]);
+        // Sans WE, le front ne change rien
+        apply(&mut sim, &sources, &[0, 0, 1, 1, 0, 0]);
+        apply(&mut sim, &sources, &[0, 0, 1, 1, 0, 1]);
+        assert_eq!(outputs(&sim, 1), vec![0, 0]);
+        assert_eq!(sim.read_memory(1, 0, 4), vec![0, 0, 0, 0b10]);
+
+        // Lecture asynchrone d'un mot écrit depuis le front
+        sim.write_memory(1, 1, vec![0b111]);
+        apply(&mut sim, &sources, &[1, 0, 0, 0, 0, 1]);
+        assert_eq!(outputs(&sim, 1), vec![1, 1]);
+        assert_eq!(sim.read_memory(1, 1, 1), vec![0b11]);
+    }
+}
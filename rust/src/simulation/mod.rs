@@ -1,2 +1,26 @@
 mod add;
+mod atpg;
+#[cfg(test)]
+pub(crate) mod bench;
+mod bmc;
+mod cnf;
+mod faults;
 mod init;
+mod inputs;
+mod logic;
+mod memory;
+mod optimize;
+mod outputs;
+mod placement;
+mod routing;
+mod sat;
+mod selection;
+mod sub_circuit;
+mod synthesis;
+mod timing;
+mod timing_analysis;
+
+pub(crate) use bmc::Property;
+pub(crate) use faults::{Fault, FaultSite, Observation};
+pub(crate) use timing::TimingModel;
+pub(crate) use timing_analysis::TimingPath;
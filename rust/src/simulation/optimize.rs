@@ -0,0 +1,950 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::circuit_element::CircuitElementEnum;
+use crate::components::{Constant, MultiInputsGate, NandGate, NorGate};
+use crate::js::OptimizationResult;
+use crate::simulation::routing::{segment_cells, split_at};
+use crate::types::{GateMapping, Orientation, Position, PullKind};
+use crate::utils::console_log;
+use crate::wire::{polyline_contains, WireGroup};
+use crate::{ComposantsEnum, Simulation};
+
+use wasm_bindgen::prelude::*;
+
+// Nombre maximal de passes de `tick` pour stabiliser le circuit optimisé
+const OPTIMIZE_SETTLE_DEPTH: u32 = 1000;
+// Écart entre le circuit et les portes placées par la réécriture en NAND ou NOR
+const MAPPED_GATES_GAP: i32 = 10;
+// Points d'arrivée essayés pour relier une porte fusionnée à la porte conservée
+const MERGE_ROUTE_ATTEMPTS: usize = 16;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum GateFunction {
+    And,
+    Or,
+    Xor,
+}
+
+// Porte combinatoire vue par l'optimiseur : NOT et BUFFER sont des OU à une entrée
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct GateShape {
+    function: GateFunction,
+    inverted: bool,
+}
+
+impl GateShape {
+    fn of(composant: &ComposantsEnum) -> Option<GateShape> {
+        let (function, inverted) = match composant {
+            ComposantsEnum::AndGate(_) => (GateFunction::And, false),
+            ComposantsEnum::NandGate(_) => (GateFunction::And, true),
+            ComposantsEnum::OrGate(_) | ComposantsEnum::BufferGate(_) => (GateFunction::Or, false),
+            ComposantsEnum::NorGate(_) | ComposantsEnum::NotGate(_) => (GateFunction::Or, true),
+            ComposantsEnum::XorGate(_) => (GateFunction::Xor, false),
+            ComposantsEnum::XnorGate(_) => (GateFunction::Xor, true),
+            _ => return None,
+        };
+        Some(GateShape { function, inverted })
+    }
+
+    // Sortie de la porte si les entrées constantes suffisent à la déterminer
+    fn fold(self, constants: &[Option<u64>], is_connected: bool, mask: u64) -> Option<u64> {
+        let known: Vec<u64> = constants
+            .iter()
+            .flatten()
+            .map(|value| value & mask)
+            .collect();
+        let is_known = known.len() == constants.len();
+        let value = match self.function {
+            GateFunction::And if !is_connected || known.contains(&0) => 0,
+            GateFunction::Or if known.contains(&mask) => mask,
+            _ if !is_known => return None,
+            GateFunction::And => known.iter().fold(mask, |acc, value| acc & value),
+            GateFunction::Or => known.iter().fold(0, |acc, value| acc | value),
+            GateFunction::Xor => known.iter().fold(0, |acc, value| acc ^ value),
+        };
+        Some(if self.inverted { !value & mask } else { value })
+    }
+}
+
+// Porte NAND (ou NOR) à créer, sur les wire groups `Input`
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum MappedNode {
+    Input(usize),
+    Gate(Vec<MappedNode>),
+}
+
+impl MappedNode {
+    fn invert(self) -> MappedNode {
+        MappedNode::Gate(vec![self.clone(), self])
+    }
+}
+
+fn is_component(element: &CircuitElementEnum, id: usize) -> bool {
+    matches!(element,
+        CircuitElementEnum::Component(other) | CircuitElementEnum::ComponentPin(other, _)
+            if *other == id)
+}
+
+fn gate_position(composant: &ComposantsEnum) -> Position {
+    match composant {
+        ComposantsEnum::AndGate(gate) => gate.gate.position,
+        ComposantsEnum::OrGate(gate) => gate.gate.position,
+        ComposantsEnum::XorGate(gate) => gate.gate.position,
+        ComposantsEnum::NandGate(gate) => gate.gate.position,
+        ComposantsEnum::NorGate(gate) => gate.gate.position,
+        ComposantsEnum::XnorGate(gate) => gate.gate.position,
+        ComposantsEnum::NotGate(gate) => gate.gate.position,
+        ComposantsEnum::BufferGate(gate) => gate.gate.position,
+        _ => [0, 0],
+    }
+}
+
+fn multi_inputs_gate(composant: &ComposantsEnum) -> Option<&MultiInputsGate> {
+    match composant {
+        ComposantsEnum::AndGate(gate) => Some(&gate.gate),
+        ComposantsEnum::OrGate(gate) => Some(&gate.gate),
+        ComposantsEnum::XorGate(gate) => Some(&gate.gate),
+        ComposantsEnum::NandGate(gate) => Some(&gate.gate),
+        ComposantsEnum::NorGate(gate) => Some(&gate.gate),
+        ComposantsEnum::XnorGate(gate) => Some(&gate.gate),
+        _ => None,
+    }
+}
+
+#[wasm_bindgen]
+impl Simulation {
+    // Copie optimisée du circuit connecté : portes à entrées constantes remplacées par
+    // des constantes de même id, portes identiques fusionnées, logique ne menant à aucun
+    // autre composant supprimée, puis portes remplacées par des NAND ou des NOR selon
+    // `mapping`. Les composants conservés gardent leur id et leurs wires ; un wire relie
+    // la sortie d'une porte fusionnée à celle qui la remplace, et les portes réécrites et
+    // ajoutées sont placées à droite du circuit et reliées par de nouveaux wires. Si l'un
+    // de ces derniers ne peut être routé, aucun circuit n'est renvoyé
+    pub fn optimize(&self, mapping: GateMapping) -> OptimizationResult {
+        let mut circuit = self.clone_state();
+        circuit.faults.clear();
+        circuit.sub_circuit_definitions = self
+            .sub_circuit_definitions
+            .iter()
+            .map(|(name, definition)| (name.clone(), definition.clone_circuit()))
+            .collect();
+        let gates_before = circuit.gate_count();
+
+        let folded = circuit.fold_constants();
+        let (merged, merge_wires) = circuit.merge_duplicate_gates();
+        let (removed, removed_wires) = circuit.remove_dead_logic();
+        let (mapped, added, anchors) = match mapping {
+            GateMapping::Keep => (Vec::new(), Vec::new(), HashMap::new()),
+            GateMapping::NandOnly => circuit.map_gates(GateFunction::And),
+            GateMapping::NorOnly => circuit.map_gates(GateFunction::Or),
+        };
+        let gates: Vec<usize> = mapped.iter().chain(&added).copied().collect();
+        let added_wires = circuit
+            .place_mapped_gates(&gates, &anchors)
+            .map(|wires| merge_wires.into_iter().chain(wires).collect::<Vec<usize>>());
+        if added_wires.is_none() {
+            console_log("Could not route the mapped gates");
+        }
+        for _ in 0..OPTIMIZE_SETTLE_DEPTH {
+            if !circuit.tick() {
+                break;
+            }
+        }
+
+        OptimizationResult {
+            gates_before,
+            gates_after: circuit.gate_count(),
+            simulation: added_wires.is_some().then_some(circuit),
+            folded,
+            merged,
+            removed,
+            removed_wires,
+            mapped,
+            added,
+            added_wires: added_wires.unwrap_or_default(),
+        }
+    }
+}
+
+impl Simulation {
+    fn gate_count(&self) -> usize {
+        self.composants_map
+            .values()
+            .filter(|composant| GateShape::of(composant).is_some())
+            .count()
+    }
+
+    fn sorted_gate_ids(&self) -> Vec<usize> {
+        let mut ids: Vec<usize> = self
+            .composants_map
+            .iter()
+            .filter(|(_, composant)| GateShape::of(composant).is_some())
+            .map(|(id, _)| *id)
+            .collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    fn element_groups(elements: &[CircuitElementEnum]) -> Vec<usize> {
+        elements
+            .iter()
+            .filter_map(|element| match element {
+                CircuitElementEnum::WireGroup(group) => Some(*group),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn input_groups_of(&self, id: usize) -> Vec<usize> {
+        let composant = &self.composants_map[&id];
+        let mut groups = Self::element_groups(&composant.circuit_element().inputs);
+        if let Some(gate) = composant.multi_pin_gate() {
+            groups.extend(gate.input_groups.iter().flatten());
+        }
+        groups
+    }
+
+    fn output_groups_of(&self, id: usize) -> Vec<usize> {
+        Self::element_groups(&self.composants_map[&id].circuit_element().outputs)
+    }
+
+    // Valeur d'un wire group piloté uniquement par des constantes, ou seulement tiré.
+    // Un groupe flottant n'a pas de valeur définie et n'est pas propagé
+    fn group_constant(&self, group: usize) -> Option<u64> {
+        let wire_group = &self.wire_groups[group];
+        let mask = wire_group.circuit_element.mask();
+        if wire_group.is_floating() {
+            return None;
+        }
+        if wire_group.circuit_element.inputs.is_empty() {
+            return Some(if wire_group.pull == Some(PullKind::Up) {
+                mask
+            } else {
+                0
+            });
+        }
+        wire_group
+            .circuit_element
+            .inputs
+            .iter()
+            .try_fold(0, |acc, input| match input {
+                CircuitElementEnum::Component(id) => match self.composants_map.get(id) {
+                    Some(ComposantsEnum::Constant(constant)) if constant.high => Some(mask),
+                    Some(ComposantsEnum::Constant(_)) => Some(acc),
+                    _ => None,
+                },
+                _ => None,
+            })
+    }
+
+    fn remove_component(&mut self, id: usize) {
+        self.composants_map.remove(&id);
+        self.internal_ids.remove(&id);
+        for wire_group in self.wire_groups.iter_mut() {
+            let element = &mut wire_group.circuit_element;
+            element.inputs.retain(|input| !is_component(input, id));
+            element.outputs.retain(|output| !is_component(output, id));
+        }
+    }
+
+    // Remplace les portes dont la sortie est constante, jusqu'à ce que plus rien ne change
+    fn fold_constants(&mut self) -> Vec<usize> {
+        let mut folded = Vec::new();
+        loop {
+            let mut is_something_different = false;
+            for id in self.sorted_gate_ids() {
+                let composant = &self.composants_map[&id];
+                let Some(shape) = GateShape::of(composant) else {
+                    continue;
+                };
+                let constants: Vec<Option<u64>> = self
+                    .input_groups_of(id)
+                    .iter()
+                    .map(|group| self.group_constant(*group))
+                    .collect();
+                let is_connected =
+                    multi_inputs_gate(composant).is_none_or(|gate| gate.are_all_inputs_connected());
+                let mut element = composant.circuit_element().clone();
+                let mask = element.mask();
+                // Une constante ne fournit que 0 ou tous ses bits à 1
+                let Some(value) = shape
+                    .fold(&constants, is_connected, mask)
+                    .filter(|value| *value == 0 || *value == mask)
+                else {
+                    continue;
+                };
+
+                let position = gate_position(composant);
+                for group in self.input_groups_of(id) {
+                    self.wire_groups[group]
+                        .circuit_element
+                        .outputs
+                        .retain(|output| !is_component(output, id));
+                }
+                element.inputs.clear();
+                element.state = value;
+                let constant = Constant {
+                    circuit_element: element,
+                    position,
+                    high: value != 0,
+                };
+                self.composants_map
+                    .insert(id, ComposantsEnum::Constant(constant));
+                folded.push(id);
+                is_something_different = true;
+            }
+            if !is_something_different {
+                break;
+            }
+        }
+        folded
+    }
+
+    // Garde la porte de plus petit id parmi celles de même type, largeur et entrées ;
+    // les wire groups pilotés par les autres sont fusionnés avec le sien et reliés à lui
+    // par un wire routé. Une porte dont le wire ne peut être routé est conservée.
+    // Renvoie les paires (supprimée, conservée) et les wires créés
+    fn merge_duplicate_gates(&mut self) -> (Vec<(usize, usize)>, Vec<usize>) {
+        let mut merged = Vec::new();
+        let mut wires = Vec::new();
+        let mut unroutable = HashSet::new();
+        loop {
+            let mut kept_by_key: HashMap<(GateShape, u8, Vec<usize>), usize> = HashMap::new();
+            let mut duplicate = None;
+            for id in self.sorted_gate_ids() {
+                let composant = &self.composants_map[&id];
+                let Some(shape) = GateShape::of(composant) else {
+                    continue;
+                };
+                if self.output_groups_of(id).is_empty() || unroutable.contains(&id) {
+                    continue;
+                }
+                let mut inputs = self.input_groups_of(id);
+                inputs.sort_unstable();
+                let key = (shape, composant.circuit_element().width, inputs);
+                match kept_by_key.get(&key) {
+                    Some(kept) => {
+                        duplicate = Some((id, *kept));
+                        break;
+                    }
+                    None => {
+                        kept_by_key.insert(key, id);
+                    }
+                }
+            }
+            let Some((id, kept)) = duplicate else {
+                break;
+            };
+            let target = self.output_groups_of(kept)[0];
+            let Some(added) = self.connect_merged_gate(id, kept, target) else {
+                console_log(&format!("Could not route gate {} to gate {}", id, kept));
+                unroutable.insert(id);
+                continue;
+            };
+            for group in self.output_groups_of(id) {
+                self.redirect_group(group, target);
+            }
+            self.remove_component(id);
+            merged.push((id, kept));
+            wires.extend(added);
+        }
+        (merged, wires)
+    }
+
+    // Relie la sortie de la porte `id`, retirée le temps du routage, au wire group
+    // `target`. Les portes internes aux sous-circuits n'ont pas de géométrie
+    fn connect_merged_gate(&mut self, id: usize, kept: usize, target: usize) -> Option<Vec<usize>> {
+        match (
+            self.internal_ids.contains(&id),
+            self.internal_ids.contains(&kept),
+        ) {
+            (true, true) => return Some(Vec::new()),
+            (false, false) => {}
+            _ => return None,
+        }
+        let gate = self.composants_map.remove(&id)?;
+        let wire = self.route_to_group(gate_position(&gate), target);
+        self.composants_map.insert(id, gate);
+        wire.map(|wire| vec![wire])
+    }
+
+    // Route un wire de `pin` jusqu'à une case d'un wire du groupe, la plus proche
+    // d'abord, qui ne touche ni pin ni autre wire. Ce wire reçoit un sommet au point
+    // d'arrivée pour que la connexion tienne aussi en mode `Legacy`
+    fn route_to_group(&mut self, pin: Position, group: usize) -> Option<usize> {
+        let group_wires = &self.wire_groups[group].wires;
+        let pins: HashSet<Position> = self
+            .composants_map
+            .values()
+            .flat_map(|composant| composant.pin_positions())
+            .collect();
+        let mut cells: Vec<(Position, usize)> = group_wires
+            .iter()
+            .filter_map(|wire| self.wires_map.get(wire))
+            .flat_map(|wire| {
+                wire.positions.windows(2).flat_map(move |segment| {
+                    segment_cells(segment[0], segment[1])
+                        .into_iter()
+                        .map(move |cell| (cell, wire.circuit_element.id))
+                })
+            })
+            .filter(|(cell, _)| {
+                !pins.contains(cell)
+                    && !self.junctions.contains(cell)
+                    && self.wires_map.values().all(|other| {
+                        group_wires.contains(&other.circuit_element.id)
+                            || !polyline_contains(&other.positions, *cell)
+                    })
+            })
+            .collect();
+        let distance = |cell: &Position| (cell[0] - pin[0]).abs() + (cell[1] - pin[1]).abs();
+        cells.sort_by_key(|(cell, wire)| (distance(cell), *wire, *cell));
+        cells.dedup_by_key(|(cell, _)| *cell);
+
+        for (cell, wire) in cells.into_iter().take(MERGE_ROUTE_ATTEMPTS) {
+            let routing = self.route_nets_from(&[vec![cell, pin]], self.next_free_id());
+            let Some((id, positions)) = routing.routed_wires().first().cloned() else {
+                continue;
+            };
+            if let Some(existing) = self.wires_map.get_mut(&wire) {
+                split_at(std::slice::from_mut(&mut existing.positions), cell);
+            }
+            let width = self.wire_groups[group].circuit_element.width;
+            if let Some(routed) = self.wires_map.get_mut(&id) {
+                routed.circuit_element.width = width;
+            }
+            self.wire_groups[group].positions.push(cell);
+            self.wire_groups[group].add_wire(id, positions, width);
+            return Some(id);
+        }
+        None
+    }
+
+    // Fusionne le wire group `from` dans `to` ; `from` reste vide à son index
+    fn redirect_group(&mut self, from: usize, to: usize) {
+        if from == to {
+            return;
+        }
+        let moved = std::mem::replace(&mut self.wire_groups[from], WireGroup::new(from));
+        let target = &mut self.wire_groups[to];
+        target
+            .circuit_element
+            .inputs
+            .extend(moved.circuit_element.inputs.iter().cloned());
+        target
+            .circuit_element
+            .outputs
+            .extend(moved.circuit_element.outputs.iter().cloned());
+        target.absorb(moved);
+
+        for composant in self.composants_map.values_mut() {
+            let element = composant.circuit_element_mut();
+            for connection in element.inputs.iter_mut().chain(element.outputs.iter_mut()) {
+                if let CircuitElementEnum::WireGroup(group) = connection {
+                    if *group == from {
+                        *group = to;
+                    }
+                }
+            }
+            if let Some(groups) = composant.input_groups_mut() {
+                for group in groups.iter_mut() {
+                    if *group == Some(from) {
+                        *group = Some(to);
+                    }
+                }
+            }
+            if let ComposantsEnum::SubCircuit(sub_circuit) = composant {
+                for group in sub_circuit.wire_groups.values_mut() {
+                    if *group == from {
+                        *group = to;
+                    }
+                }
+            }
+        }
+    }
+
+    // Portes et constantes dont la sortie n'atteint aucun autre composant. Les wires
+    // des wire groups qui ne relient plus rien sont supprimés
+    fn remove_dead_logic(&mut self) -> (Vec<usize>, Vec<usize>) {
+        let is_removable = |composant: &ComposantsEnum| {
+            GateShape::of(composant).is_some() || matches!(composant, ComposantsEnum::Constant(_))
+        };
+        let mut pending: Vec<usize> = self
+            .wire_groups
+            .iter()
+            .filter(|wire_group| {
+                wire_group
+                    .circuit_element
+                    .outputs
+                    .iter()
+                    .any(|output| match output {
+                        CircuitElementEnum::Component(id)
+                        | CircuitElementEnum::ComponentPin(id, _) => self
+                            .composants_map
+                            .get(id)
+                            .is_some_and(|composant| !is_removable(composant)),
+                        CircuitElementEnum::WireGroup(_) => false,
+                    })
+            })
+            .map(|wire_group| wire_group.circuit_element.id)
+            .collect();
+        let mut live_groups = HashSet::new();
+        let mut live = HashSet::new();
+        while let Some(group) = pending.pop() {
+            if !live_groups.insert(group) {
+                continue;
+            }
+            for input in &self.wire_groups[group].circuit_element.inputs {
+                if let CircuitElementEnum::Component(id) | CircuitElementEnum::ComponentPin(id, _) =
+                    input
+                {
+                    let is_new =
+                        self.composants_map.get(id).is_some_and(&is_removable) && live.insert(*id);
+                    if is_new {
+                        pending.extend(self.input_groups_of(*id));
+                    }
+                }
+            }
+        }
+
+        let mut removed: Vec<usize> = self
+            .composants_map
+            .iter()
+            .filter(|(id, composant)| is_removable(composant) && !live.contains(id))
+            .map(|(id, _)| *id)
+            .collect();
+        removed.sort_unstable();
+        let mut touched = Vec::new();
+        for id in &removed {
+            touched.extend(self.input_groups_of(*id));
+            touched.extend(self.output_groups_of(*id));
+            self.remove_component(*id);
+        }
+
+        let mut removed_wires = Vec::new();
+        touched.sort_unstable();
+        touched.dedup();
+        for group in touched {
+            let wire_group = &mut self.wire_groups[group];
+            if wire_group.circuit_element.inputs.is_empty()
+                && wire_group.circuit_element.outputs.is_empty()
+            {
+                for wire in wire_group.wires.drain(..) {
+                    self.wires_map.remove(&wire);
+                    removed_wires.push(wire);
+                }
+                wire_group.positions.clear();
+            }
+        }
+        removed_wires.sort_unstable();
+        (removed, removed_wires)
+    }
+
+    // Réécrit chaque porte avec des NAND (`universal` ET) ou des NOR (`universal` OU).
+    // La porte qui pilote la sortie d'origine garde l'id de la porte remplacée. Renvoie
+    // aussi, pour chaque wire group relié à une porte remplacée, la position d'une de
+    // ses pins, par laquelle les nouvelles portes seront rattachées au circuit
+    fn map_gates(
+        &mut self,
+        universal: GateFunction,
+    ) -> (Vec<usize>, Vec<usize>, HashMap<usize, Position>) {
+        let mut mapped = Vec::new();
+        let mut added = Vec::new();
+        let mut anchors = HashMap::new();
+        let mut next_id = self.next_free_id();
+        let mut emitted: HashMap<MappedNode, usize> = HashMap::new();
+        for id in self.sorted_gate_ids() {
+            let composant = &self.composants_map[&id];
+            let Some(shape) = GateShape::of(composant) else {
+                continue;
+            };
+            let is_universal = match universal {
+                GateFunction::And => matches!(composant, ComposantsEnum::NandGate(_)),
+                _ => matches!(composant, ComposantsEnum::NorGate(_)),
+            };
+            let inputs = self.input_groups_of(id);
+            if is_universal || inputs.is_empty() {
+                continue;
+            }
+            let MappedNode::Gate(children) = Self::mapped_node(shape, &inputs, universal) else {
+                continue;
+            };
+
+            let Some(original) = self.composants_map.remove(&id) else {
+                continue;
+            };
+            let width = original.circuit_element().width;
+            let position = gate_position(&original);
+            let is_internal = self.internal_ids.contains(&id);
+            if !is_internal {
+                let outputs = Self::element_groups(&original.circuit_element().outputs);
+                for pin in original.pin_positions() {
+                    let group = inputs
+                        .iter()
+                        .chain(&outputs)
+                        .find(|group| self.wire_groups[**group].positions.contains(&pin));
+                    if let Some(group) = group {
+                        anchors.entry(*group).or_insert(pin);
+                    }
+                }
+            }
+            for group in &inputs {
+                self.wire_groups[*group]
+                    .circuit_element
+                    .outputs
+                    .retain(|output| !is_component(output, id));
+            }
+            let mut groups = Vec::with_capacity(children.len());
+            for child in children {
+                let before = next_id;
+                groups.push(self.emit_node(
+                    child,
+                    universal,
+                    width,
+                    position,
+                    &mut next_id,
+                    &mut emitted,
+                ));
+                added.extend(before..next_id);
+                if is_internal {
+                    self.internal_ids.extend(before..next_id);
+                }
+            }
+
+            let mut root = Self::universal_gate(universal, id, position, &groups, width);
+            root.circuit_element_mut().outputs = original.circuit_element().outputs.clone();
+            root.circuit_element_mut().state = original.circuit_element().state;
+            self.composants_map.insert(id, root);
+            mapped.push(id);
+        }
+        (mapped, added, anchors)
+    }
+
+    // Place à droite du circuit les portes réécrites ou créées par `map_gates`, puis
+    // route chaque wire group qui les touche depuis sa position d'ancrage. Renvoie les
+    // wires créés, ou None si un wire group n'a pas pu être relié
+    fn place_mapped_gates(
+        &mut self,
+        gates: &[usize],
+        anchors: &HashMap<usize, Position>,
+    ) -> Option<Vec<usize>> {
+        // Les portes internes aux sous-circuits n'ont pas de géométrie
+        let gates: Vec<usize> = gates
+            .iter()
+            .copied()
+            .filter(|id| !self.internal_ids.contains(id))
+            .collect();
+        let mut pins_by_group: BTreeMap<usize, Vec<(usize, usize)>> = BTreeMap::new();
+        for id in &gates {
+            let composant = &self.composants_map[id];
+            let element = composant.circuit_element();
+            for (pin, input) in element.inputs.iter().enumerate() {
+                if let CircuitElementEnum::WireGroup(group) = input {
+                    pins_by_group.entry(*group).or_default().push((*id, pin));
+                }
+            }
+            let output = composant.input_positions().len();
+            for group in Self::element_groups(&element.outputs) {
+                pins_by_group.entry(group).or_default().push((*id, output));
+            }
+        }
+        if pins_by_group.is_empty() {
+            return Some(Vec::new());
+        }
+
+        let points: Vec<Position> = self
+            .composants_map
+            .iter()
+            .filter(|(id, _)| !gates.contains(id) && !self.internal_ids.contains(id))
+            .flat_map(|(_, composant)| composant.pin_positions())
+            .chain(
+                self.wires_map
+                    .values()
+                    .flat_map(|wire| wire.positions.clone()),
+            )
+            .collect();
+        let origin = match points.iter().map(|p| p[0]).max() {
+            Some(x) => [
+                x + MAPPED_GATES_GAP,
+                points.iter().map(|p| p[1]).min().unwrap_or(0),
+            ],
+            None => [0, 0],
+        };
+        let nets: Vec<Vec<(usize, usize)>> = pins_by_group.values().cloned().collect();
+        self.arrange_components(&nets, origin);
+
+        let mut wires = Vec::new();
+        let mut next_id = self.next_free_id();
+        for (group, pins) in &pins_by_group {
+            let mut net: Vec<Position> = anchors.get(group).copied().into_iter().collect();
+            net.extend(
+                pins.iter()
+                    .map(|(id, pin)| self.composants_map[id].pin_positions()[*pin]),
+            );
+            let routing = self.route_nets_from(&[net], next_id);
+            let width = self.wire_groups[*group].circuit_element.width;
+            for (id, positions) in routing.routed_wires() {
+                if let Some(wire) = self.wires_map.get_mut(id) {
+                    wire.circuit_element.width = width;
+                }
+                self.wire_groups[*group].add_wire(*id, positions.clone(), width);
+                wires.push(*id);
+            }
+            if !routing.failed().is_empty() {
+                return None;
+            }
+            next_id = routing.next_id();
+        }
+        Some(wires)
+    }
+
+    fn mapped_node(shape: GateShape, inputs: &[usize], universal: GateFunction) -> MappedNode {
+        let xs: Vec<MappedNode> = inputs
+            .iter()
+            .map(|group| MappedNode::Input(*group))
+            .collect();
+        match shape.function {
+            _ if xs.len() == 1 && shape.inverted => xs[0].clone().invert(),
+            _ if xs.len() == 1 => xs[0].clone().invert().invert(),
+            function if function == universal => {
+                let gate = MappedNode::Gate(xs);
+                if shape.inverted {
+                    gate
+                } else {
+                    gate.invert()
+                }
+            }
+            // Chaque étage vaut a ⊕ b avec des NAND, !(a ⊕ b) avec des NOR
+            GateFunction::Xor => {
+                let mut accumulator = xs[0].clone();
+                for x in &xs[1..] {
+                    let both = MappedNode::Gate(vec![accumulator.clone(), x.clone()]);
+                    accumulator = MappedNode::Gate(vec![
+                        MappedNode::Gate(vec![accumulator, both.clone()]),
+                        MappedNode::Gate(vec![x.clone(), both]),
+                    ]);
+                }
+                let is_inverted = universal == GateFunction::Or && xs.len().is_multiple_of(2);
+                if is_inverted != shape.inverted {
+                    accumulator.invert()
+                } else {
+                    accumulator
+                }
+            }
+            // Fonction duale de la porte universelle : De Morgan
+            _ => {
+                let gate = MappedNode::Gate(xs.into_iter().map(MappedNode::invert).collect());
+                if shape.inverted {
+                    gate.invert()
+                } else {
+                    gate
+                }
+            }
+        }
+    }
+
+    // Crée les portes d'un nœud et renvoie le wire group de sa sortie
+    fn emit_node(
+        &mut self,
+        node: MappedNode,
+        universal: GateFunction,
+        width: u8,
+        position: Position,
+        next_id: &mut usize,
+        emitted: &mut HashMap<MappedNode, usize>,
+    ) -> usize {
+        let children = match &node {
+            MappedNode::Input(group) => return *group,
+            MappedNode::Gate(children) => children.clone(),
+        };
+        if let Some(group) = emitted.get(&node) {
+            return *group;
+        }
+        let groups: Vec<usize> = children
+            .into_iter()
+            .map(|child| self.emit_node(child, universal, width, position, next_id, emitted))
+            .collect();
+
+        let id = *next_id;
+        *next_id += 1;
+        let group = self.wire_groups.len();
+        let mut wire_group = WireGroup::new(group);
+        wire_group.circuit_element.width = width;
+        wire_group
+            .circuit_element
+            .inputs
+            .push(CircuitElementEnum::Component(id));
+        self.wire_groups.push(wire_group);
+        for input in &groups {
+            self.wire_groups[*input]
+                .circuit_element
+                .outputs
+                .push(CircuitElementEnum::Component(id));
+        }
+
+        let mut gate = Self::universal_gate(universal, id, position, &groups, width);
+        gate.circuit_element_mut()
+            .outputs
+            .push(CircuitElementEnum::WireGroup(group));
+        self.composants_map.insert(id, gate);
+        emitted.insert(node, group);
+        group
+    }
+
+    fn universal_gate(
+        universal: GateFunction,
+        id: usize,
+        position: Position,
+        inputs: &[usize],
+        width: u8,
+    ) -> ComposantsEnum {
+        let mut gate = MultiInputsGate::new(id, position, Orientation::Right, inputs.len() as u8);
+        gate.circuit_element.width = width;
+        gate.circuit_element.inputs = inputs
+            .iter()
+            .map(|group| CircuitElementEnum::WireGroup(*group))
+            .collect();
+        gate.input_groups = inputs.iter().map(|group| Some(*group)).collect();
+        match universal {
+            GateFunction::And => ComposantsEnum::NandGate(NandGate { gate }),
+            _ => ComposantsEnum::NorGate(NorGate { gate }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::types::{GateMapping, Orientation};
+    use crate::wire::Wire;
+    use crate::Simulation;
+
+    // (a ^ b) & c | a, placé et routé : switches 1 à 3, portes 4 à 6, LED 7
+    fn circuit() -> Simulation {
+        let mut sim = Simulation::new();
+        for id in 1..=3 {
+            sim.add_switch(vec![0, 0], id);
+        }
+        sim.add_xor_gate(vec![0, 0], Orientation::Right, 4);
+        sim.add_and_gate(vec![0, 0], Orientation::Right, 5);
+        sim.add_or_gate(vec![0, 0], Orientation::Right, 6);
+        sim.add_led(vec![0, 0], 0, Orientation::Right, 7);
+        let nets = [
+            vec![(1, 0), (4, 0), (6, 1)],
+            vec![(2, 0), (4, 1)],
+            vec![(3, 0), (5, 1)],
+            vec![(4, 2), (5, 0)],
+            vec![(5, 2), (6, 0)],
+            vec![(6, 2), (7, 0)],
+        ];
+        let placement = sim.place_components(&nets, [0, 0], 100);
+        assert!(placement.failed().is_empty());
+        sim.compute_connections();
+        sim
+    }
+
+    // Deux portes ET identiques sur les switches 1 et 2, chacune vers sa LED (6 et 7).
+    // Le switch 3, à l'écart, n'est relié à rien
+    fn duplicate_circuit() -> Simulation {
+        let mut sim = Simulation::new();
+        sim.add_switch(vec![0, 0], 1);
+        sim.add_switch(vec![0, 0], 2);
+        sim.add_switch(vec![-100, -100], 3);
+        sim.add_and_gate(vec![0, 0], Orientation::Right, 4);
+        sim.add_and_gate(vec![0, 0], Orientation::Right, 5);
+        sim.add_led(vec![0, 0], 0, Orientation::Right, 6);
+        sim.add_led(vec![0, 0], 0, Orientation::Right, 7);
+        let nets = [
+            vec![(1, 0), (4, 0), (5, 0)],
+            vec![(2, 0), (4, 1), (5, 1)],
+            vec![(4, 2), (6, 0)],
+            vec![(5, 2), (7, 0)],
+        ];
+        let placement = sim.place_components(&nets, [0, 0], 100);
+        assert!(placement.failed().is_empty());
+        sim.compute_connections();
+        sim
+    }
+
+    // Même circuit reconstruit à partir de sa seule géométrie, comme au rechargement
+    fn reconnected(sim: &Simulation) -> Simulation {
+        let mut copy = Simulation::new();
+        for (id, composant) in &sim.composants_map {
+            let mut composant = composant.clone();
+            let element = composant.circuit_element_mut();
+            element.inputs.clear();
+            element.outputs.clear();
+            element.state = 0;
+            if let Some(groups) = composant.input_groups_mut() {
+                groups.fill(None);
+            }
+            copy.composants_map.insert(*id, composant);
+        }
+        for (id, wire) in &sim.wires_map {
+            let wire = Wire::new_bus(*id, wire.positions.clone(), wire.circuit_element.width);
+            copy.wires_map.insert(*id, wire);
+        }
+        copy.junctions = sim.junctions.clone();
+        copy.compute_connections();
+        copy
+    }
+
+    // États des LEDs, LED i au bit i, pour chaque combinaison des switches
+    fn outputs(sim: &mut Simulation, leds: &[usize]) -> Vec<u64> {
+        (0..8)
+            .map(|row| {
+                for (bit, id) in (1..=3).enumerate() {
+                    sim.update_switch_state(id, (row >> bit) & 1 == 1);
+                }
+                sim.compute_frame(100, 1);
+                leds.iter().enumerate().fold(0, |acc, (bit, id)| {
+                    acc | sim.get_component_state(*id) << bit
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn optimized_circuit_matches_the_original() {
+        for (sim, leds, expected, merged) in [
+            (circuit(), vec![7], vec![0, 1, 0, 1, 0, 1, 1, 1], 0),
+            (
+                duplicate_circuit(),
+                vec![6, 7],
+                vec![0, 0, 0, 3, 0, 0, 0, 3],
+                1,
+            ),
+        ] {
+            let mut sim = sim;
+            assert_eq!(outputs(&mut sim, &leds), expected);
+            for mapping in [
+                GateMapping::Keep,
+                GateMapping::NandOnly,
+                GateMapping::NorOnly,
+            ] {
+                let mut result = sim.optimize(mapping);
+                assert_eq!(result.merged().len(), merged);
+                let mut optimized = result.take_simulation().unwrap();
+                assert_eq!(outputs(&mut reconnected(&optimized), &leds), expected);
+                assert_eq!(outputs(&mut optimized, &leds), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn mapped_gates_are_placed_and_wired() {
+        let sim = circuit();
+        let mut result = sim.optimize(GateMapping::NandOnly);
+        assert!(!result.added().is_empty());
+        assert!(!result.added_wires().is_empty());
+        let optimized = result.take_simulation().unwrap();
+        let mut positions = HashSet::new();
+        for composant in optimized.composants_map.values() {
+            assert!(positions.insert(composant.position()));
+            if composant.input_positions().len() > 1 {
+                assert_eq!(composant.kind_name(), "NandGate");
+            }
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use crate::components::{PixelMatrix, Terminal};
+use crate::utils::console_log;
+use crate::ComposantsEnum;
+use crate::Simulation;
+
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+impl Simulation {
+    // Une valeur par ligne, bit c = colonne c
+    pub fn get_pixel_matrix(&self, component_index: usize) -> Vec<u64> {
+        match self.composants_map.get(&component_index) {
+            Some(ComposantsEnum::PixelMatrix(pixel_matrix)) => pixel_matrix.pixels.clone(),
+            _ => {
+                console_log("Invalid pixel matrix index");
+                Vec::new()
+            }
+        }
+    }
+
+    pub fn clear_pixel_matrix(&mut self, component_index: usize) {
+        if let Some(pixel_matrix) = self.pixel_matrix_mut(component_index) {
+            pixel_matrix.clear();
+        }
+    }
+
+    // Texte écrit par le terminal depuis le dernier appel. Un saut de page (0x0C)
+    // indique que l'écran a été effacé
+    pub fn drain_terminal(&mut self, component_index: usize) -> String {
+        match self.terminal_mut(component_index) {
+            Some(terminal) => terminal.drain(),
+            None => String::new(),
+        }
+    }
+
+    // Wires dont le groupe est flottant : leur état 0 n'est pas une valeur tirée
+    pub fn floating_wires(&self) -> Vec<usize> {
+        let mut wires: Vec<usize> = self
+            .wire_groups
+            .iter()
+            .filter(|wire_group| wire_group.is_floating())
+            .flat_map(|wire_group| wire_group.wires.iter().copied())
+            .collect();
+        wires.sort_unstable();
+        wires
+    }
+}
+
+impl Simulation {
+    fn pixel_matrix_mut(&mut self, component_index: usize) -> Option<&mut PixelMatrix> {
+        match self.composants_map.get_mut(&component_index) {
+            Some(ComposantsEnum::PixelMatrix(pixel_matrix)) => Some(pixel_matrix),
+            _ => {
+                console_log("Invalid pixel matrix index");
+                None
+            }
+        }
+    }
+
+    fn terminal_mut(&mut self, component_index: usize) -> Option<&mut Terminal> {
+        match self.composants_map.get_mut(&component_index) {
+            Some(ComposantsEnum::Terminal(terminal)) => Some(terminal),
+            _ => {
+                console_log("Invalid terminal index");
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::simulation::bench::{apply, drive_inputs};
+    use crate::types::{DisplayData, Orientation, PixelMatrixMode, SevenSegmentMode};
+    use crate::Simulation;
+
+    #[test]
+    fn seven_segment_decodes_hex_digits() {
+        let mut sim = Simulation::new();
+        sim.add_seven_segment(vec![0, 0], SevenSegmentMode::Hex, Orientation::Right, 1);
+        // [chiffre (bus 4 bits), DP]
+        let sources = drive_inputs(&mut sim, 1, &[4]);
+        sim.compute_connections();
+        for (digit, dp, segments) in [(0x0, 0, 0x3F), (0x7, 1, 0x87), (0xb, 0, 0x7C)] {
+            apply(&mut sim, &sources, &[digit, dp]);
+            assert_eq!(sim.get_component_state(1), segments);
+        }
+    }
+
+    #[test]
+    fn pixel_matrix_scans_rows_and_reports_its_image() {
+        let mut sim = Simulation::new();
+        sim.add_pixel_matrix(
+            vec![0, 0],
+            3,
+            4,
+            PixelMatrixMode::RowColumn,
+            Orientation::Right,
+            1,
+        );
+        // [ROW, COL] en bus
+        let sources = drive_inputs(&mut sim, 1, &[3, 4]);
+        sim.compute_connections();
+        apply(&mut sim, &sources, &[0b101, 0b0011]);
+        let old = sim.create_old_components_copy();
+        apply(&mut sim, &sources, &[0b010, 0b1000]);
+        assert_eq!(sim.get_pixel_matrix(1), vec![0b0011, 0b1000, 0b0011]);
+
+        let changed = sim.create_changed_components_copy(old);
+        let displays = sim.create_changed_displays(&changed);
+        assert!(matches!(
+            displays.as_slice(),
+            [display] if display.id == 1
+                && matches!(&display.data, DisplayData::PixelMatrix { pixels, .. }
+                    if pixels == &vec![0b0011, 0b1000, 0b0011])
+        ));
+
+        sim.clear_pixel_matrix(1);
+        assert_eq!(sim.get_pixel_matrix(1), vec![0; 3]);
+    }
+
+    #[test]
+    fn framebuffer_writes_a_row_on_the_clock_edge() {
+        let mut sim = Simulation::new();
+        sim.add_pixel_matrix(
+            vec![0, 0],
+            4,
+            8,
+            PixelMatrixMode::Framebuffer,
+            Orientation::Right,
+            1,
+        );
+        // [ADDR, DATA, WE, CLK]
+        let sources = drive_inputs(&mut sim, 1, &[2, 8]);
+        sim.compute_connections();
+        apply(&mut sim, &sources, &[2, 0xa5, 1, 0]);
+        assert_eq!(sim.get_pixel_matrix(1), vec![0; 4]);
+        apply(&mut sim, &sources, &[2, 0xa5, 1, 1]);
+        apply(&mut sim, &sources, &[3, 0xff, 0, 0]);
+        apply(&mut sim, &sources, &[3, 0xff, 0, 1]);
+        assert_eq!(sim.get_pixel_matrix(1), vec![0, 0, 0xa5, 0]);
+    }
+
+    #[test]
+    fn terminal_writes_on_edges_and_clears_with_a_form_feed() {
+        let mut sim = Simulation::new();
+        sim.add_terminal(vec![0, 0], Orientation::Right, 1);
+        // [DATA (bus 8 bits), WRITE, CLR]
+        let sources = drive_inputs(&mut sim, 1, &[8]);
+        sim.compute_connections();
+        let write = |sim: &mut Simulation, byte: u8| {
+            apply(sim, &sources, &[byte as u64, 0, 0]);
+            apply(sim, &sources, &[byte as u64, 1, 0]);
+        };
+        // « é » s'écrit 0xC3 0xA9 : l'octet incomplet attend le suivant
+        write(&mut sim, b'h');
+        write(&mut sim, 0xC3);
+        assert_eq!(sim.get_component_state(1), 2);
+        assert_eq!(sim.drain_terminal(1), "h");
+        write(&mut sim, 0xA9);
+        assert_eq!(sim.drain_terminal(1), "é");
+
+        write(&mut sim, b'x');
+        apply(&mut sim, &sources, &[0, 0, 1]);
+        assert_eq!(sim.drain_terminal(1), "\u{c}");
+        assert_eq!(sim.drain_terminal(1), "");
+    }
+}
@@ -0,0 +1,418 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::js::PlacementResult;
+use crate::types::{Orientation, PlacedComponent, Position};
+use crate::utils::console_log;
+use crate::Simulation;
+
+use js_sys::Array;
+use wasm_bindgen::prelude::*;
+
+// Écart vertical entre deux composants d'une même couche
+const PLACEMENT_ROW_GAP: i32 = 4;
+// Canal de routage entre deux couches : une piste par net qui y entre, au minimum 2
+const PLACEMENT_TRACK_SPACING: i32 = 2;
+const MIN_CHANNEL_TRACKS: i32 = 2;
+// Passes alternées de la méthode du barycentre
+const ORDERING_SWEEPS: usize = 8;
+
+// Décalage d'un point exprimé pour l'orientation `orientation`, ramené à `Right`
+// (inverse de `oriented_position`)
+pub(crate) fn right_offset(offset: [i32; 2], orientation: Orientation) -> [i32; 2] {
+    match orientation {
+        Orientation::Right => offset,
+        Orientation::Up => [offset[1], -offset[0]],
+        Orientation::Down => [-offset[1], offset[0]],
+        Orientation::Left => [-offset[0], -offset[1]],
+    }
+}
+
+// Pins `[id, pin]` d'un net, lues directement comme des entiers positifs
+fn pins_from_js(net: Array) -> Option<Vec<(usize, usize)>> {
+    net.iter()
+        .map(|pin| {
+            let pin = pin.dyn_into::<Array>().ok()?;
+            let index = |index: u32| {
+                pin.get(index)
+                    .as_f64()
+                    .filter(|value| *value >= 0.0 && value.fract() == 0.0)
+                    .map(|value| value as usize)
+            };
+            Some((index(0)?, index(1)?))
+        })
+        .collect()
+}
+
+#[wasm_bindgen]
+impl Simulation {
+    // Place en couches les composants d'un netlist sans géométrie, de gauche à droite
+    // par profondeur logique, tous orientés à droite, puis route les nets. Chaque net
+    // est un tableau de pins `[id, pin]`, `pin` indexant les entrées puis les sorties du
+    // composant. Le coin bas gauche du circuit est à `origin` ; les wires reçoivent les
+    // ids libres à partir de `first_wire_id`
+    pub fn place_netlist(
+        &mut self,
+        nets: Array,
+        origin: Vec<i32>,
+        first_wire_id: usize,
+    ) -> Option<PlacementResult> {
+        let nets: Option<Vec<Vec<(usize, usize)>>> = nets
+            .iter()
+            .map(|net| net.dyn_into::<Array>().ok().and_then(pins_from_js))
+            .collect();
+        let Some(nets) = nets else {
+            console_log("Invalid netlist");
+            return None;
+        };
+        for (id, pin) in nets.iter().flatten() {
+            let pins = self
+                .composants_map
+                .get(id)
+                .map_or(0, |composant| composant.pin_positions().len());
+            if *pin >= pins {
+                console_log(&format!("Unknown pin {} of component {}", pin, id));
+                return None;
+            }
+        }
+        Some(self.place_components(&nets, [origin[0], origin[1]], first_wire_id))
+    }
+}
+
+impl Simulation {
+    pub(crate) fn place_components(
+        &mut self,
+        nets: &[Vec<(usize, usize)>],
+        origin: Position,
+        first_wire_id: usize,
+    ) -> PlacementResult {
+        let (nodes, layer_of) = self.arrange_components(nets, origin);
+        let pins: Vec<Vec<Position>> = nets
+            .iter()
+            .map(|net| {
+                net.iter()
+                    .map(|(id, pin)| self.composants_map[id].pin_positions()[*pin])
+                    .collect()
+            })
+            .collect();
+        let routing = self.route_nets_from(&pins, first_wire_id);
+
+        let components = nodes
+            .iter()
+            .map(|id| {
+                let composant = &self.composants_map[id];
+                PlacedComponent {
+                    id: *id,
+                    kind: composant.kind_name(),
+                    position: composant.position(),
+                    orientation: Orientation::Right,
+                    inputs: composant.input_positions().len(),
+                }
+            })
+            .collect();
+        let layers = nodes.iter().map(|id| layer_of[id]).collect();
+        PlacementResult::new(components, layers, routing)
+    }
+
+    // Oriente à droite et range en couches les composants des nets, sans les relier.
+    // Renvoie les composants triés par id et la couche de chacun
+    pub(crate) fn arrange_components(
+        &mut self,
+        nets: &[Vec<(usize, usize)>],
+        origin: Position,
+    ) -> (Vec<usize>, HashMap<usize, usize>) {
+        let mut nodes: Vec<usize> = nets.iter().flatten().map(|(id, _)| *id).collect();
+        nodes.sort_unstable();
+        nodes.dedup();
+        for id in &nodes {
+            self.orient_right(*id);
+        }
+
+        let edges = self.netlist_edges(nets, &nodes);
+        let layer_of = Self::assign_layers(&nodes, &edges, |id| {
+            self.composants_map[&id].output_positions().is_empty()
+        });
+        let layers = Self::order_layers(&nodes, &edges, &layer_of);
+
+        // Rectangle englobant chaque composant, relatif à sa position
+        let bounds: HashMap<usize, (Position, Position)> = nodes
+            .iter()
+            .map(|id| {
+                let composant = &self.composants_map[id];
+                let position = composant.position();
+                let (low, high) = composant.pin_positions().iter().fold(
+                    (position, position),
+                    |(low, high), pin| {
+                        (
+                            [low[0].min(pin[0]), low[1].min(pin[1])],
+                            [high[0].max(pin[0]), high[1].max(pin[1])],
+                        )
+                    },
+                );
+                let offset = |p: Position| [p[0] - position[0], p[1] - position[1]];
+                (*id, (offset(low), offset(high)))
+            })
+            .collect();
+
+        let mut x = origin[0];
+        for (index, layer) in layers.iter().enumerate() {
+            if index > 0 {
+                // Nets reliant cette couche aux précédentes
+                let entering = nets
+                    .iter()
+                    .filter(|net| {
+                        net.iter().any(|(id, _)| layer_of[id] == index)
+                            && net.iter().any(|(id, _)| layer_of[id] < index)
+                    })
+                    .count() as i32;
+                x += PLACEMENT_TRACK_SPACING * (entering.max(MIN_CHANNEL_TRACKS) + 1);
+            }
+            let width = layer
+                .iter()
+                .map(|id| bounds[id].1[0] - bounds[id].0[0])
+                .max()
+                .unwrap_or(0);
+            let mut y = origin[1];
+            for id in layer {
+                let (low, high) = bounds[id];
+                let position = self.composants_map[id].position();
+                let delta = [x - (position[0] + low[0]), y - (position[1] + low[1])];
+                if let Some(composant) = self.composants_map.get_mut(id) {
+                    composant.map_positions(|p| [p[0] + delta[0], p[1] + delta[1]]);
+                }
+                y += high[1] - low[1] + PLACEMENT_ROW_GAP;
+            }
+            x += width;
+        }
+        (nodes, layer_of)
+    }
+
+    // Tourne le composant autour de sa position pour que ses sorties soient à droite
+    fn orient_right(&mut self, id: usize) {
+        let Some(composant) = self.composants_map.get_mut(&id) else {
+            return;
+        };
+        let orientation = composant.orientation();
+        let position = composant.position();
+        composant.map_positions(|p| {
+            let offset = right_offset([p[0] - position[0], p[1] - position[1]], orientation);
+            [position[0] + offset[0], position[1] + offset[1]]
+        });
+        if let Some(gate) = composant.multi_pin_gate_mut() {
+            gate.orientation = Orientation::Right;
+        }
+    }
+
+    // Arcs du composant qui pilote chaque net vers les autres composants du net. Un net
+    // sans sortie est piloté par le composant de sa première pin
+    fn netlist_edges(&self, nets: &[Vec<(usize, usize)>], nodes: &[usize]) -> Vec<(usize, usize)> {
+        let input_counts: HashMap<usize, usize> = nodes
+            .iter()
+            .map(|id| (*id, self.composants_map[id].input_positions().len()))
+            .collect();
+        let mut edges = Vec::new();
+        for net in nets {
+            let mut drivers: Vec<usize> = net
+                .iter()
+                .filter(|(id, pin)| *pin >= input_counts[id])
+                .map(|(id, _)| *id)
+                .collect();
+            if drivers.is_empty() {
+                drivers.extend(net.first().map(|(id, _)| *id));
+            }
+            for driver in &drivers {
+                for (id, _) in net {
+                    if id != driver && !drivers.contains(id) {
+                        edges.push((*driver, *id));
+                    }
+                }
+            }
+        }
+        edges.sort_unstable();
+        edges.dedup();
+        edges
+    }
+
+    // Couche = plus long chemin depuis une source, les arcs qui ferment une boucle
+    // étant ignorés. Les composants sans sortie (LEDs, ports) vont dans la dernière couche
+    fn assign_layers(
+        nodes: &[usize],
+        edges: &[(usize, usize)],
+        is_sink: impl Fn(usize) -> bool,
+    ) -> HashMap<usize, usize> {
+        let mut successors: HashMap<usize, Vec<usize>> = HashMap::new();
+        for (from, to) in edges {
+            successors.entry(*from).or_default().push(*to);
+        }
+
+        // Parcours en profondeur : ordre topologique inverse, sans les arcs retour
+        let mut visited = HashSet::new();
+        let mut on_stack = HashSet::new();
+        let mut post_order = Vec::new();
+        let mut forward = Vec::new();
+        for root in nodes {
+            if visited.contains(root) {
+                continue;
+            }
+            let mut stack = vec![(*root, 0)];
+            visited.insert(*root);
+            on_stack.insert(*root);
+            while let Some((node, next)) = stack.pop() {
+                let children = successors.get(&node).map_or(&[][..], Vec::as_slice);
+                if let Some(child) = children.get(next) {
+                    stack.push((node, next + 1));
+                    if on_stack.contains(child) {
+                        continue;
+                    }
+                    forward.push((node, *child));
+                    if visited.insert(*child) {
+                        on_stack.insert(*child);
+                        stack.push((*child, 0));
+                    }
+                } else {
+                    on_stack.remove(&node);
+                    post_order.push(node);
+                }
+            }
+        }
+
+        let mut layer_of: HashMap<usize, usize> = nodes.iter().map(|id| (*id, 0)).collect();
+        for node in post_order.iter().rev() {
+            for (from, to) in forward.iter().filter(|(from, _)| from == node) {
+                let layer = layer_of[from] + 1;
+                if layer > layer_of[to] {
+                    layer_of.insert(*to, layer);
+                }
+            }
+        }
+        let last = layer_of.values().copied().max().unwrap_or(0);
+        for (_, to) in &forward {
+            if is_sink(*to) {
+                layer_of.insert(*to, last);
+            }
+        }
+        layer_of
+    }
+
+    // Ordre des composants dans chaque couche par la méthode du barycentre, en
+    // alternant les passes vers la droite (prédécesseurs) et vers la gauche (successeurs)
+    fn order_layers(
+        nodes: &[usize],
+        edges: &[(usize, usize)],
+        layer_of: &HashMap<usize, usize>,
+    ) -> Vec<Vec<usize>> {
+        let count = layer_of.values().copied().max().map_or(0, |last| last + 1);
+        let mut layers: Vec<Vec<usize>> = vec![Vec::new(); count];
+        for id in nodes {
+            layers[layer_of[id]].push(*id);
+        }
+        // Position relative de chaque composant dans sa couche, entre 0 et 1
+        let relative = |layers: &[Vec<usize>]| -> HashMap<usize, f64> {
+            layers
+                .iter()
+                .flat_map(|layer| {
+                    let size = layer.len() as f64;
+                    layer
+                        .iter()
+                        .enumerate()
+                        .map(move |(rank, id)| (*id, (rank as f64 + 0.5) / size))
+                })
+                .collect()
+        };
+
+        for sweep in 0..ORDERING_SWEEPS {
+            let is_forward = sweep % 2 == 0;
+            let indexes: Vec<usize> = if is_forward {
+                (1..count).collect()
+            } else {
+                (0..count.saturating_sub(1)).rev().collect()
+            };
+            for index in indexes {
+                let positions = relative(&layers);
+                let barycenter = |id: usize| {
+                    let neighbours: Vec<f64> = edges
+                        .iter()
+                        .filter_map(|(from, to)| match is_forward {
+                            true if *to == id && layer_of[from] < index => Some(positions[from]),
+                            false if *from == id && layer_of[to] > index => Some(positions[to]),
+                            _ => None,
+                        })
+                        .collect();
+                    if neighbours.is_empty() {
+                        positions[&id]
+                    } else {
+                        neighbours.iter().sum::<f64>() / neighbours.len() as f64
+                    }
+                };
+                let mut keyed: Vec<(f64, usize)> = layers[index]
+                    .iter()
+                    .map(|id| (barycenter(*id), *id))
+                    .collect();
+                keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+                layers[index] = keyed.into_iter().map(|(_, id)| id).collect();
+            }
+        }
+        layers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::{Orientation, Position};
+    use crate::Simulation;
+
+    fn bounds(sim: &Simulation, id: usize) -> (Position, Position) {
+        let composant = &sim.composants_map[&id];
+        let mut points = composant.pin_positions();
+        points.push(composant.position());
+        points
+            .iter()
+            .fold(([i32::MAX; 2], [i32::MIN; 2]), |(low, high), p| {
+                (
+                    [low[0].min(p[0]), low[1].min(p[1])],
+                    [high[0].max(p[0]), high[1].max(p[1])],
+                )
+            })
+    }
+
+    #[test]
+    fn layers_go_left_to_right_without_overlap() {
+        // !(a & b) | b : switches 1 et 2, ET 3, NON 4, OU 5, LED 6, tous empilés
+        let mut sim = Simulation::new();
+        sim.add_switch(vec![0, 0], 1);
+        sim.add_switch(vec![0, 0], 2);
+        sim.add_and_gate(vec![0, 0], Orientation::Up, 3);
+        sim.add_not_gate(vec![0, 0], Orientation::Left, 4);
+        sim.add_or_gate(vec![0, 0], Orientation::Right, 5);
+        sim.add_led(vec![0, 0], 0, Orientation::Right, 6);
+        let nets = [
+            vec![(1, 0), (3, 0)],
+            vec![(2, 0), (3, 1), (5, 1)],
+            vec![(3, 2), (4, 0)],
+            vec![(4, 1), (5, 0)],
+            vec![(5, 2), (6, 0)],
+        ];
+        let placement = sim.place_components(&nets, [0, 0], 100);
+        assert!(placement.failed().is_empty());
+
+        // Composants par id croissant
+        let layers = placement.layers();
+        assert_eq!(layers, vec![0, 0, 1, 2, 3, 4]);
+        let ids: Vec<usize> = (1..=6).collect();
+        for (a, layer_a) in ids.iter().zip(&layers) {
+            let (low_a, high_a) = bounds(&sim, *a);
+            for (b, layer_b) in ids.iter().zip(&layers) {
+                let (low_b, high_b) = bounds(&sim, *b);
+                if layer_a < layer_b {
+                    assert!(high_a[0] < low_b[0], "{} is not left of {}", a, b);
+                }
+                if a < b {
+                    let overlaps = low_a[0] <= high_b[0]
+                        && low_b[0] <= high_a[0]
+                        && low_a[1] <= high_b[1]
+                        && low_b[1] <= high_a[1];
+                    assert!(!overlaps, "{} overlaps {}", a, b);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,392 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+use crate::js::RoutingResult;
+use crate::types::Position;
+use crate::utils::console_log;
+use crate::wire::Wire;
+use crate::Simulation;
+
+use js_sys::Array;
+use wasm_bindgen::prelude::*;
+
+// Marge autour du circuit dans laquelle les wires peuvent passer
+const ROUTING_MARGIN: i32 = 4;
+// Coûts ajoutés à un pas de longueur 1 : changement de direction, croisement d'un wire
+const BEND_COST: u32 = 2;
+const CROSSING_COST: u32 = 1;
+
+// Occupation d'une case par les wires déjà posés
+const HORIZONTAL: u8 = 1;
+const VERTICAL: u8 = 2;
+const VERTEX: u8 = 4;
+
+// Droite, gauche, haut, bas : `index ^ 1` est la direction opposée
+const DIRECTIONS: [[i32; 2]; 4] = [[1, 0], [-1, 0], [0, 1], [0, -1]];
+const NO_DIRECTION: u8 = 4;
+
+// Points entiers d'un segment, extrémités comprises
+pub(crate) fn segment_cells(from: Position, to: Position) -> Vec<Position> {
+    let (dx, dy) = (to[0] - from[0], to[1] - from[1]);
+    let steps = gcd(dx.abs(), dy.abs()).max(1);
+    (0..=steps)
+        .map(|step| [from[0] + dx / steps * step, from[1] + dy / steps * step])
+        .collect()
+}
+
+fn gcd(a: i32, b: i32) -> i32 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+fn distance(a: Position, b: Position) -> u32 {
+    a[0].abs_diff(b[0]) + a[1].abs_diff(b[1])
+}
+
+// Corps des composants et wires existants. Un nouveau wire ne passe ni sur un corps
+// ni sur un sommet ou une jonction, ne longe aucun wire et ne le croise qu'à angle
+// droit, sans y tourner : il ne touche ainsi aucun autre wire group, quel que soit
+// le mode de connexion
+struct RoutingGrid {
+    blocked: HashSet<Position>,
+    occupied: HashMap<Position, u8>,
+    min: Position,
+    max: Position,
+}
+
+impl RoutingGrid {
+    fn include(&mut self, position: Position) {
+        self.min = [self.min[0].min(position[0]), self.min[1].min(position[1])];
+        self.max = [self.max[0].max(position[0]), self.max[1].max(position[1])];
+    }
+
+    fn mark_path(&mut self, positions: &[Position]) {
+        for segment in positions.windows(2) {
+            let flag = match (segment[0], segment[1]) {
+                (from, to) if from[1] == to[1] => HORIZONTAL,
+                (from, to) if from[0] == to[0] => VERTICAL,
+                // Un segment en biais est infranchissable
+                _ => VERTEX,
+            };
+            for cell in segment_cells(segment[0], segment[1]) {
+                *self.occupied.entry(cell).or_default() |= flag;
+            }
+        }
+        for position in positions {
+            *self.occupied.entry(*position).or_default() |= VERTEX;
+            self.include(*position);
+        }
+    }
+
+    fn is_inside(&self, position: Position) -> bool {
+        (self.min[0] - ROUTING_MARGIN..=self.max[0] + ROUTING_MARGIN).contains(&position[0])
+            && (self.min[1] - ROUTING_MARGIN..=self.max[1] + ROUTING_MARGIN).contains(&position[1])
+    }
+
+    // A* de `start` jusqu'à la première case de `targets` ; renvoie les sommets du chemin
+    fn find_path(&self, start: Position, targets: &HashSet<Position>) -> Option<Vec<Position>> {
+        // Heuristique : distance au rectangle englobant les cibles
+        let (low, high) = targets
+            .iter()
+            .fold(([i32::MAX; 2], [i32::MIN; 2]), |(low, high), p| {
+                (
+                    [low[0].min(p[0]), low[1].min(p[1])],
+                    [high[0].max(p[0]), high[1].max(p[1])],
+                )
+            });
+        let estimate = |p: Position| {
+            distance(
+                p,
+                [p[0].clamp(low[0], high[0]), p[1].clamp(low[1], high[1])],
+            )
+        };
+
+        let mut costs: HashMap<(Position, u8), u32> = HashMap::new();
+        let mut previous: HashMap<(Position, u8), (Position, u8)> = HashMap::new();
+        let mut heap = BinaryHeap::new();
+        costs.insert((start, NO_DIRECTION), 0);
+        heap.push(Reverse((estimate(start), 0, start, NO_DIRECTION)));
+
+        while let Some(Reverse((_, cost, position, direction))) = heap.pop() {
+            if position != start && targets.contains(&position) {
+                let mut cells = vec![position];
+                let mut state = (position, direction);
+                while let Some(before) = previous.get(&state) {
+                    cells.push(before.0);
+                    state = *before;
+                }
+                cells.reverse();
+                return Some(Self::vertices(&cells));
+            }
+            if costs
+                .get(&(position, direction))
+                .is_some_and(|best| cost > *best)
+            {
+                continue;
+            }
+            let here = self.occupied.get(&position).copied().unwrap_or(0);
+            for (index, delta) in DIRECTIONS.iter().enumerate() {
+                let index = index as u8;
+                let is_bend = direction != NO_DIRECTION && index != direction;
+                if is_bend && (index ^ 1 == direction || here != 0) {
+                    continue;
+                }
+                let next = [position[0] + delta[0], position[1] + delta[1]];
+                if !self.is_inside(next) {
+                    continue;
+                }
+                let mut step = 1 + if is_bend { BEND_COST } else { 0 };
+                if !targets.contains(&next) {
+                    let along = if index < 2 { HORIZONTAL } else { VERTICAL };
+                    let flags = self.occupied.get(&next).copied().unwrap_or(0);
+                    if self.blocked.contains(&next) || flags & (VERTEX | along) != 0 {
+                        continue;
+                    }
+                    if flags != 0 {
+                        step += CROSSING_COST;
+                    }
+                }
+                let next_cost = cost + step;
+                let state = (next, index);
+                if costs.get(&state).is_some_and(|best| *best <= next_cost) {
+                    continue;
+                }
+                costs.insert(state, next_cost);
+                previous.insert(state, (position, direction));
+                heap.push(Reverse((
+                    next_cost + estimate(next),
+                    next_cost,
+                    next,
+                    index,
+                )));
+            }
+        }
+        None
+    }
+
+    // Ne garde que les extrémités et les coudes
+    fn vertices(cells: &[Position]) -> Vec<Position> {
+        let mut vertices = vec![cells[0]];
+        for window in cells.windows(3) {
+            let (a, b, c) = (window[0], window[1], window[2]);
+            if (b[0] - a[0], b[1] - a[1]) != (c[0] - b[0], c[1] - b[1]) {
+                vertices.push(b);
+            }
+        }
+        if cells.len() > 1 {
+            vertices.push(cells[cells.len() - 1]);
+        }
+        vertices
+    }
+}
+
+// Ajoute `point` comme sommet du chemin qui le contient, pour qu'une branche qui
+// s'y termine soit connectée aussi en mode `Legacy`
+pub(crate) fn split_at(paths: &mut [Vec<Position>], point: Position) {
+    for path in paths.iter_mut() {
+        if path.contains(&point) {
+            return;
+        }
+        let segment = path
+            .windows(2)
+            .position(|segment| segment_cells(segment[0], segment[1]).contains(&point));
+        if let Some(index) = segment {
+            path.insert(index + 1, point);
+            return;
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Simulation {
+    // Relie deux pins par un wire orthogonal d'id `id`, qui contourne les composants
+    // et ne touche aucun wire existant. Appeler ensuite `compute_connections`
+    pub fn route_wire(&mut self, from: Vec<i32>, to: Vec<i32>, id: usize) -> Option<RoutingResult> {
+        if self.wires_map.contains_key(&id) || self.composants_map.contains_key(&id) {
+            console_log(&format!("Id {} already in use", id));
+            return None;
+        }
+        let nets = vec![vec![[from[0], from[1]], [to[0], to[1]]]];
+        let result = self.route_nets_from(&nets, id);
+        if !result.failed().is_empty() {
+            console_log(&format!(
+                "No route from {:?} to {:?}",
+                nets[0][0], nets[0][1]
+            ));
+            return None;
+        }
+        Some(result)
+    }
+
+    // Route un netlist : chaque net est un tableau de positions de pins, reliées en
+    // arbre. Les wires reçoivent les ids libres à partir de `first_id` ; les nets qui n'ont
+    // pu être routés entièrement sont indiqués par `failed`
+    pub fn route_nets(&mut self, nets: Array, first_id: usize) -> RoutingResult {
+        let nets: Vec<Vec<Position>> = nets
+            .iter()
+            .map(|net| Self::positions_from_js(net.unchecked_into()))
+            .collect();
+        self.route_nets_from(&nets, first_id)
+    }
+}
+
+impl Simulation {
+    fn routing_grid(&self, pins: &[Position]) -> RoutingGrid {
+        let mut grid = RoutingGrid {
+            blocked: HashSet::new(),
+            occupied: HashMap::new(),
+            min: [i32::MAX; 2],
+            max: [i32::MIN; 2],
+        };
+        // Corps d'un composant : rectangle englobant sa position et ses pins
+        for composant in self.composants_map.values() {
+            let mut points = composant.pin_positions();
+            points.push(composant.position());
+            let low = points
+                .iter()
+                .fold([i32::MAX; 2], |low, p| [low[0].min(p[0]), low[1].min(p[1])]);
+            let high = points.iter().fold([i32::MIN; 2], |high, p| {
+                [high[0].max(p[0]), high[1].max(p[1])]
+            });
+            for x in low[0]..=high[0] {
+                for y in low[1]..=high[1] {
+                    grid.blocked.insert([x, y]);
+                }
+            }
+            grid.include(low);
+            grid.include(high);
+        }
+        for wire in self.wires_map.values() {
+            grid.mark_path(&wire.positions);
+        }
+        for junction in &self.junctions {
+            *grid.occupied.entry(*junction).or_default() |= VERTEX;
+        }
+        for pin in pins {
+            grid.include(*pin);
+        }
+        grid
+    }
+
+    // Les nets les plus courts sont routés en premier ; dans un net, chaque pin est
+    // reliée, de la plus proche à la plus éloignée de la première, à l'arbre déjà posé
+    pub(crate) fn route_nets_from(
+        &mut self,
+        nets: &[Vec<Position>],
+        first_id: usize,
+    ) -> RoutingResult {
+        let pins: Vec<Position> = nets.iter().flatten().copied().collect();
+        let mut grid = self.routing_grid(&pins);
+        let span = |net: &Vec<Position>| {
+            net.iter()
+                .flat_map(|a| net.iter().map(move |b| distance(*a, *b)))
+                .max()
+                .unwrap_or(0)
+        };
+        let mut order: Vec<usize> = (0..nets.len()).collect();
+        order.sort_by_key(|index| (span(&nets[*index]), *index));
+
+        let mut wires = Vec::new();
+        let mut failed = Vec::new();
+        let mut next_id = first_id;
+        for index in order {
+            let Some(first) = nets[index].first().copied() else {
+                continue;
+            };
+            let mut remaining = nets[index][1..].to_vec();
+            remaining.sort_by_key(|pin| distance(first, *pin));
+            // Une branche se termine sur un wire du net, jamais sur une pin : chaque pin
+            // n'est ainsi le sommet que d'un seul wire et n'est connectée qu'une fois
+            let pins: HashSet<Position> = nets[index].iter().copied().collect();
+            let mut tree = HashSet::from([first]);
+            let mut paths: Vec<Vec<Position>> = Vec::new();
+            for pin in remaining {
+                if pin == first {
+                    continue;
+                }
+                let Some(path) = grid.find_path(pin, &tree) else {
+                    if !failed.contains(&index) {
+                        failed.push(index);
+                    }
+                    continue;
+                };
+                split_at(&mut paths, path[path.len() - 1]);
+                if paths.is_empty() {
+                    tree.clear();
+                }
+                for segment in path.windows(2) {
+                    tree.extend(segment_cells(segment[0], segment[1]));
+                }
+                tree.retain(|cell| !pins.contains(cell));
+                paths.push(path);
+            }
+            for path in paths {
+                while self.wires_map.contains_key(&next_id)
+                    || self.composants_map.contains_key(&next_id)
+                {
+                    next_id += 1;
+                }
+                grid.mark_path(&path);
+                self.wires_map
+                    .insert(next_id, Wire::new(next_id, path.clone()));
+                wires.push((next_id, path));
+                next_id += 1;
+            }
+        }
+        failed.sort_unstable();
+        RoutingResult::new(wires, failed, next_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::segment_cells;
+    use crate::types::{Orientation, Position};
+    use crate::wire::{polyline_contains, Wire};
+    use crate::Simulation;
+
+    #[test]
+    fn route_goes_around_a_gate_without_touching_wires() {
+        let mut sim = Simulation::new();
+        // Porte ET entre les deux pins et wire vertical à traverser
+        sim.add_and_gate(vec![10, 0], Orientation::Right, 1);
+        sim.wires_map.insert(2, Wire::new(2, vec![[3, -6], [3, 6]]));
+        let (from, to): (Position, Position) = ([0, 0], [15, 0]);
+        let result = sim.route_wire(from.to_vec(), to.to_vec(), 10).unwrap();
+        let [(id, path)] = result.routed_wires() else {
+            panic!("expected a single wire");
+        };
+        assert_eq!(*id, 10);
+        // Le chemin part de la seconde pin vers l'arbre issu de la première
+        assert_eq!(path.first(), Some(&to));
+        assert_eq!(path.last(), Some(&from));
+        assert!(path.len() > 2);
+
+        let gate = &sim.composants_map[&1];
+        let mut body = gate.pin_positions();
+        body.push(gate.position());
+        let (low, high) = body
+            .iter()
+            .fold(([i32::MAX; 2], [i32::MIN; 2]), |(low, high), p| {
+                (
+                    [low[0].min(p[0]), low[1].min(p[1])],
+                    [high[0].max(p[0]), high[1].max(p[1])],
+                )
+            });
+        for segment in path.windows(2) {
+            assert!(segment[0][0] == segment[1][0] || segment[0][1] == segment[1][1]);
+            for cell in segment_cells(segment[0], segment[1]) {
+                let inside =
+                    (low[0]..=high[0]).contains(&cell[0]) && (low[1]..=high[1]).contains(&cell[1]);
+                assert!(!inside, "{:?} is inside the gate", cell);
+            }
+        }
+        let existing = &sim.wires_map[&2].positions;
+        for vertex in path {
+            assert!(!polyline_contains(existing, *vertex));
+        }
+    }
+}
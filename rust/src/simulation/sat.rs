@@ -0,0 +1,588 @@
+use std::ops::Not;
+
+// Littéral : variable `index >> 1`, négation dans le bit de poids faible
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Lit(u32);
+
+impl Lit {
+    fn var(self) -> usize {
+        (self.0 >> 1) as usize
+    }
+
+    fn is_negated(self) -> bool {
+        self.0 & 1 == 1
+    }
+
+    fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl Not for Lit {
+    type Output = Lit;
+
+    fn not(self) -> Lit {
+        Lit(self.0 ^ 1)
+    }
+}
+
+// Solveur CDCL minimal : watched literals, clauses apprises au premier UIP,
+// choix de variable par activité et mémorisation de la dernière polarité
+#[derive(Default)]
+pub struct SatSolver {
+    clauses: Vec<Vec<Lit>>,
+    // Clauses surveillant chaque littéral, visitées quand il devient faux
+    watches: Vec<Vec<usize>>,
+    assigns: Vec<Option<bool>>,
+    levels: Vec<usize>,
+    reasons: Vec<Option<usize>>,
+    phases: Vec<bool>,
+    activity: Vec<f64>,
+    bump: f64,
+    trail: Vec<Lit>,
+    trail_limits: Vec<usize>,
+    queue_head: usize,
+    seen: Vec<bool>,
+    true_lit: Option<Lit>,
+    model: Vec<bool>,
+    unsat: bool,
+}
+
+impl SatSolver {
+    pub fn new() -> Self {
+        SatSolver {
+            bump: 1.0,
+            ..Default::default()
+        }
+    }
+
+    pub fn new_var(&mut self) -> Lit {
+        let var = self.assigns.len();
+        self.assigns.push(None);
+        self.levels.push(0);
+        self.reasons.push(None);
+        self.phases.push(false);
+        self.activity.push(0.0);
+        self.seen.push(false);
+        self.watches.push(Vec::new());
+        self.watches.push(Vec::new());
+        Lit((var as u32) << 1)
+    }
+
+    pub fn constant(&mut self, value: bool) -> Lit {
+        let lit = match self.true_lit {
+            Some(lit) => lit,
+            None => {
+                let lit = self.new_var();
+                self.add_clause(&[lit]);
+                self.true_lit = Some(lit);
+                lit
+            }
+        };
+        if value {
+            lit
+        } else {
+            !lit
+        }
+    }
+
+    fn lit_value(&self, lit: Lit) -> Option<bool> {
+        self.assigns[lit.var()].map(|value| value != lit.is_negated())
+    }
+
+    pub fn add_clause(&mut self, lits: &[Lit]) {
+        if self.unsat {
+            return;
+        }
+        self.backtrack(0);
+        let mut clause: Vec<Lit> = Vec::with_capacity(lits.len());
+        for &lit in lits {
+            match self.lit_value(lit) {
+                Some(true) => return,
+                Some(false) => continue,
+                None if clause.contains(&!lit) => return,
+                None if clause.contains(&lit) => continue,
+                None => clause.push(lit),
+            }
+        }
+        match clause.len() {
+            0 => self.unsat = true,
+            1 => {
+                self.enqueue(clause[0], None);
+                if self.propagate().is_some() {
+                    self.unsat = true;
+                }
+            }
+            _ => {
+                self.attach(clause);
+            }
+        }
+    }
+
+    fn attach(&mut self, clause: Vec<Lit>) -> usize {
+        let index = self.clauses.len();
+        self.watches[clause[0].index()].push(index);
+        self.watches[clause[1].index()].push(index);
+        self.clauses.push(clause);
+        index
+    }
+
+    fn enqueue(&mut self, lit: Lit, reason: Option<usize>) {
+        let var = lit.var();
+        self.assigns[var] = Some(!lit.is_negated());
+        self.levels[var] = self.trail_limits.len();
+        self.reasons[var] = reason;
+        self.trail.push(lit);
+    }
+
+    fn backtrack(&mut self, level: usize) {
+        if self.trail_limits.len() <= level {
+            return;
+        }
+        let limit = self.trail_limits[level];
+        for lit in self.trail.drain(limit..) {
+            let var = lit.var();
+            self.phases[var] = !lit.is_negated();
+            self.assigns[var] = None;
+            self.reasons[var] = None;
+        }
+        self.trail_limits.truncate(level);
+        self.queue_head = self.trail.len();
+    }
+
+    // Renvoie la clause en conflit, s'il y en a une
+    fn propagate(&mut self) -> Option<usize> {
+        while self.queue_head < self.trail.len() {
+            let false_lit = !self.trail[self.queue_head];
+            self.queue_head += 1;
+            let mut watchers = std::mem::take(&mut self.watches[false_lit.index()]);
+            let mut i = 0;
+            while i < watchers.len() {
+                let clause_index = watchers[i];
+                if self.clauses[clause_index][0] == false_lit {
+                    self.clauses[clause_index].swap(0, 1);
+                }
+                let first = self.clauses[clause_index][0];
+                if self.lit_value(first) == Some(true) {
+                    i += 1;
+                    continue;
+                }
+                let replacement = (2..self.clauses[clause_index].len())
+                    .find(|&k| self.lit_value(self.clauses[clause_index][k]) != Some(false));
+                if let Some(k) = replacement {
+                    let clause = &mut self.clauses[clause_index];
+                    clause.swap(1, k);
+                    self.watches[clause[1].index()].push(clause_index);
+                    watchers.swap_remove(i);
+                    continue;
+                }
+                if self.lit_value(first) == Some(false) {
+                    self.watches[false_lit.index()] = watchers;
+                    return Some(clause_index);
+                }
+                self.enqueue(first, Some(clause_index));
+                i += 1;
+            }
+            self.watches[false_lit.index()] = watchers;
+        }
+        None
+    }
+
+    fn bump_activity(&mut self, var: usize) {
+        self.activity[var] += self.bump;
+        if self.activity[var] > 1e100 {
+            for activity in self.activity.iter_mut() {
+                *activity *= 1e-100;
+            }
+            self.bump *= 1e-100;
+        }
+    }
+
+    // Clause apprise (littéral assertif en tête) et niveau de retour
+    fn analyze(&mut self, conflict: usize) -> (Vec<Lit>, usize) {
+        let level = self.trail_limits.len();
+        let mut learnt = vec![Lit(0)];
+        let mut pending = 0;
+        let mut clause_index = conflict;
+        let mut pivot: Option<Lit> = None;
+        let mut trail_index = self.trail.len();
+        loop {
+            let skip = usize::from(pivot.is_some());
+            for k in skip..self.clauses[clause_index].len() {
+                let lit = self.clauses[clause_index][k];
+                let var = lit.var();
+                if self.seen[var] || self.levels[var] == 0 {
+                    continue;
+                }
+                self.seen[var] = true;
+                self.bump_activity(var);
+                if self.levels[var] == level {
+                    pending += 1;
+                } else {
+                    learnt.push(lit);
+                }
+            }
+            loop {
+                trail_index -= 1;
+                if self.seen[self.trail[trail_index].var()] {
+                    break;
+                }
+            }
+            let lit = self.trail[trail_index];
+            self.seen[lit.var()] = false;
+            pending -= 1;
+            if pending == 0 {
+                learnt[0] = !lit;
+                break;
+            }
+            pivot = Some(lit);
+            clause_index = self.reasons[lit.var()].unwrap_or(conflict);
+        }
+        for lit in &learnt[1..] {
+            self.seen[lit.var()] = false;
+        }
+
+        let mut backtrack_level = 0;
+        if let Some((position, _)) = learnt
+            .iter()
+            .enumerate()
+            .skip(1)
+            .max_by_key(|(_, lit)| self.levels[lit.var()])
+        {
+            learnt.swap(1, position);
+            backtrack_level = self.levels[learnt[1].var()];
+        }
+        (learnt, backtrack_level)
+    }
+
+    fn pick_branch(&self) -> Option<Lit> {
+        let var = (0..self.assigns.len())
+            .filter(|&var| self.assigns[var].is_none())
+            .max_by(|&a, &b| self.activity[a].total_cmp(&self.activity[b]))?;
+        let lit = Lit((var as u32) << 1);
+        Some(if self.phases[var] { lit } else { !lit })
+    }
+
+    // Some(true) si satisfiable, Some(false) sinon, None si `max_conflicts` est atteint
+    pub fn solve(&mut self, max_conflicts: usize) -> Option<bool> {
+        if self.unsat {
+            return Some(false);
+        }
+        self.backtrack(0);
+        let mut conflicts = 0;
+        loop {
+            if let Some(conflict) = self.propagate() {
+                if self.trail_limits.is_empty() {
+                    self.unsat = true;
+                    return Some(false);
+                }
+                conflicts += 1;
+                if conflicts > max_conflicts {
+                    self.backtrack(0);
+                    return None;
+                }
+                let (learnt, level) = self.analyze(conflict);
+                self.backtrack(level);
+                if learnt.len() == 1 {
+                    self.enqueue(learnt[0], None);
+                } else {
+                    let asserting = learnt[0];
+                    let index = self.attach(learnt);
+                    self.enqueue(asserting, Some(index));
+                }
+                self.bump /= 0.95;
+                continue;
+            }
+            match self.pick_branch() {
+                Some(lit) => {
+                    self.trail_limits.push(self.trail.len());
+                    self.enqueue(lit, None);
+                }
+                None => {
+                    self.model = self.assigns.iter().map(|v| v.unwrap_or(false)).collect();
+                    self.backtrack(0);
+                    return Some(true);
+                }
+            }
+        }
+    }
+
+    // Valeur du littéral dans le dernier modèle trouvé
+    pub fn model_value(&self, lit: Lit) -> bool {
+        self.model.get(lit.var()).copied().unwrap_or(false) != lit.is_negated()
+    }
+
+    pub fn add_equal(&mut self, a: Lit, b: Lit) {
+        self.add_clause(&[!a, b]);
+        self.add_clause(&[a, !b]);
+    }
+
+    pub fn and_gate(&mut self, inputs: &[Lit]) -> Lit {
+        match inputs {
+            [] => self.constant(true),
+            [lit] => *lit,
+            _ => {
+                let output = self.new_var();
+                let mut clause: Vec<Lit> = inputs.iter().map(|lit| !*lit).collect();
+                clause.push(output);
+                self.add_clause(&clause);
+                for &input in inputs {
+                    self.add_clause(&[!output, input]);
+                }
+                output
+            }
+        }
+    }
+
+    pub fn or_gate(&mut self, inputs: &[Lit]) -> Lit {
+        let negated: Vec<Lit> = inputs.iter().map(|lit| !*lit).collect();
+        !self.and_gate(&negated)
+    }
+
+    pub fn xor_gate(&mut self, a: Lit, b: Lit) -> Lit {
+        let output = self.new_var();
+        self.add_clause(&[!a, !b, !output]);
+        self.add_clause(&[a, b, !output]);
+        self.add_clause(&[a, !b, output]);
+        self.add_clause(&[!a, b, output]);
+        output
+    }
+
+    // `select ? high : low`
+    pub fn mux_gate(&mut self, select: Lit, high: Lit, low: Lit) -> Lit {
+        let output = self.new_var();
+        self.add_clause(&[!select, !high, output]);
+        self.add_clause(&[!select, high, !output]);
+        self.add_clause(&[select, !low, output]);
+        self.add_clause(&[select, low, !output]);
+        output
+    }
+
+    // Au plus `k` littéraux vrais (compteur séquentiel de Sinz). `counts[j]` est forcé
+    // à vrai dès que `j + 1` des littéraux déjà vus le sont
+    pub fn at_most(&mut self, lits: &[Lit], k: usize) {
+        if k >= lits.len() {
+            return;
+        }
+        if k == 0 {
+            for &lit in lits {
+                self.add_clause(&[!lit]);
+            }
+            return;
+        }
+        let mut counts: Vec<Lit> = Vec::new();
+        for (index, &lit) in lits.iter().enumerate() {
+            if let Some(&full) = counts.last() {
+                self.add_clause(&[!lit, !full]);
+            }
+            if index + 1 == lits.len() {
+                break;
+            }
+            let next: Vec<Lit> = (0..k).map(|_| self.new_var()).collect();
+            for (j, &count) in next.iter().enumerate() {
+                if let Some(&previous) = counts.get(j) {
+                    self.add_clause(&[!previous, count]);
+                }
+                match j.checked_sub(1).and_then(|below| counts.get(below)) {
+                    Some(&below) => self.add_clause(&[!lit, !below, count]),
+                    None if j == 0 => self.add_clause(&[!lit, count]),
+                    None => {}
+                }
+            }
+            counts = next;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Lit, SatSolver};
+
+    fn vars(solver: &mut SatSolver, count: usize) -> Vec<Lit> {
+        (0..count).map(|_| solver.new_var()).collect()
+    }
+
+    // Trois pigeons, deux trous : `pigeons[pigeon][hole]`
+    fn pigeonhole(solver: &mut SatSolver) {
+        let pigeons: Vec<Vec<Lit>> = (0..3).map(|_| vars(solver, 2)).collect();
+        for holes in &pigeons {
+            solver.add_clause(holes);
+        }
+        for (index, first) in pigeons.iter().enumerate() {
+            for second in &pigeons[index + 1..] {
+                for (a, b) in first.iter().zip(second) {
+                    solver.add_clause(&[!*a, !*b]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn satisfiable_formula_has_a_model() {
+        let mut solver = SatSolver::new();
+        let lits = vars(&mut solver, 3);
+        let (a, b, c) = (lits[0], lits[1], lits[2]);
+        let clauses = [vec![a, b], vec![!a, b], vec![!b, c], vec![!c, !a]];
+        for clause in &clauses {
+            solver.add_clause(clause);
+        }
+        assert_eq!(solver.solve(1000), Some(true));
+        for clause in &clauses {
+            assert!(clause.iter().any(|lit| solver.model_value(*lit)));
+        }
+    }
+
+    #[test]
+    fn pigeonhole_is_unsatisfiable() {
+        let mut solver = SatSolver::new();
+        pigeonhole(&mut solver);
+        assert_eq!(solver.solve(1000), Some(false));
+    }
+
+    #[test]
+    fn conflict_at_level_zero() {
+        // Les clauses unitaires se propagent dès l'ajout
+        let mut solver = SatSolver::new();
+        let lits = vars(&mut solver, 2);
+        let (a, b) = (lits[0], lits[1]);
+        solver.add_clause(&[a]);
+        solver.add_clause(&[!a, b]);
+        solver.add_clause(&[!b]);
+        assert_eq!(solver.solve(1000), Some(false));
+
+        // Ici le conflit au niveau 0 vient de clauses apprises
+        let mut solver = SatSolver::new();
+        let lits = vars(&mut solver, 2);
+        let (a, b) = (lits[0], lits[1]);
+        for clause in [[a, b], [a, !b], [!a, b], [!a, !b]] {
+            solver.add_clause(&clause);
+        }
+        assert_eq!(solver.solve(1000), Some(false));
+    }
+
+    #[test]
+    fn max_conflicts_aborts() {
+        let mut solver = SatSolver::new();
+        pigeonhole(&mut solver);
+        assert_eq!(solver.solve(0), None);
+        // Le solveur reste utilisable après l'abandon
+        assert_eq!(solver.solve(1000), Some(false));
+    }
+
+    // Formules 3-SAT pseudo-aléatoires comparées à une énumération exhaustive
+    #[test]
+    fn agrees_with_brute_force() {
+        let mut seed: u64 = 0x2545_F491_4F6C_DD1D;
+        let mut next = |bound: u64| {
+            seed = seed
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            (seed >> 33) % bound
+        };
+        for _ in 0..200 {
+            let count = 3 + next(6) as usize;
+            let clauses: Vec<Vec<(usize, bool)>> = (0..4 + next(24))
+                .map(|_| {
+                    (0..3)
+                        .map(|_| (next(count as u64) as usize, next(2) == 1))
+                        .collect()
+                })
+                .collect();
+            let expected = (0..1u32 << count).any(|assignment| {
+                clauses.iter().all(|clause| {
+                    clause
+                        .iter()
+                        .any(|(var, negated)| (assignment >> var & 1 == 1) != *negated)
+                })
+            });
+
+            let mut solver = SatSolver::new();
+            let lits = vars(&mut solver, count);
+            let to_lits = |clause: &Vec<(usize, bool)>| -> Vec<Lit> {
+                clause
+                    .iter()
+                    .map(|(var, negated)| if *negated { !lits[*var] } else { lits[*var] })
+                    .collect()
+            };
+            for clause in &clauses {
+                solver.add_clause(&to_lits(clause));
+            }
+            assert_eq!(solver.solve(10_000), Some(expected));
+            if expected {
+                for clause in &clauses {
+                    assert!(to_lits(clause).iter().any(|lit| solver.model_value(*lit)));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn at_most_allows_exactly_k_true_literals() {
+        for count in 1..6 {
+            for k in 0..=count {
+                for assignment in 0..1u32 << count {
+                    let mut solver = SatSolver::new();
+                    let lits = vars(&mut solver, count);
+                    solver.at_most(&lits, k);
+                    for (bit, lit) in lits.iter().enumerate() {
+                        let lit = if assignment >> bit & 1 == 1 {
+                            *lit
+                        } else {
+                            !*lit
+                        };
+                        solver.add_clause(&[lit]);
+                    }
+                    let expected = assignment.count_ones() as usize <= k;
+                    assert_eq!(solver.solve(1000), Some(expected));
+                }
+            }
+        }
+    }
+
+    // La sortie de la porte vaut `expected` pour chaque combinaison d'entrées, et
+    // seulement cette valeur
+    fn check_gate(
+        inputs: usize,
+        gate: impl Fn(&mut SatSolver, &[Lit]) -> Lit,
+        expected: impl Fn(&[bool]) -> bool,
+    ) {
+        for row in 0..1u32 << inputs {
+            let values: Vec<bool> = (0..inputs).map(|bit| row >> bit & 1 == 1).collect();
+            for negate_output in [false, true] {
+                let mut solver = SatSolver::new();
+                let lits = vars(&mut solver, inputs);
+                let output = gate(&mut solver, &lits);
+                for (lit, value) in lits.iter().zip(&values) {
+                    solver.add_clause(&[if *value { *lit } else { !*lit }]);
+                }
+                let wanted = expected(&values) != negate_output;
+                solver.add_clause(&[if wanted { output } else { !output }]);
+                assert_eq!(solver.solve(100), Some(!negate_output), "row {}", row);
+            }
+        }
+    }
+
+    #[test]
+    fn gate_truth_tables() {
+        check_gate(
+            3,
+            |solver, lits| solver.and_gate(lits),
+            |values| values.iter().all(|v| *v),
+        );
+        check_gate(
+            3,
+            |solver, lits| solver.or_gate(lits),
+            |values| values.iter().any(|v| *v),
+        );
+        check_gate(
+            2,
+            |solver, lits| solver.xor_gate(lits[0], lits[1]),
+            |values| values[0] != values[1],
+        );
+        check_gate(
+            3,
+            |solver, lits| solver.mux_gate(lits[0], lits[1], lits[2]),
+            |values| if values[0] { values[1] } else { values[2] },
+        );
+    }
+}
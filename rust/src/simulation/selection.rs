@@ -0,0 +1,406 @@
+use crate::components::{oriented_position, TwoInputsGate};
+use crate::js::SelectionResult;
+use crate::simulation::placement::right_offset;
+use crate::types::{MirrorDirection, Orientation, PlacedComponent, Position};
+use crate::utils::console_log;
+use crate::wire::{polyline_contains, Wire};
+use crate::{ComposantsEnum, Simulation};
+
+use wasm_bindgen::prelude::*;
+
+// Quart de tour dans le sens trigonométrique, le même que de `Right` à `Up`
+fn rotate_quarter(offset: [i32; 2]) -> [i32; 2] {
+    [-offset[1], offset[0]]
+}
+
+fn rotate_orientation(orientation: Orientation) -> Orientation {
+    match orientation {
+        Orientation::Right => Orientation::Up,
+        Orientation::Up => Orientation::Left,
+        Orientation::Left => Orientation::Down,
+        Orientation::Down => Orientation::Right,
+    }
+}
+
+#[wasm_bindgen]
+impl Simulation {
+    // Tourne les composants et wires sélectionnés de `quarter_turns` quarts de tour
+    // autour de `pivot`, dans le sens de `Right` vers `Up`
+    pub fn rotate_selection(
+        &mut self,
+        components: Vec<usize>,
+        wires: Vec<usize>,
+        pivot: Vec<i32>,
+        quarter_turns: i32,
+    ) -> Option<SelectionResult> {
+        let turns = quarter_turns.rem_euclid(4);
+        let pivot = [pivot[0], pivot[1]];
+        self.transform_selection(
+            &components,
+            &wires,
+            |p| {
+                let offset = (0..turns).fold([p[0] - pivot[0], p[1] - pivot[1]], |offset, _| {
+                    rotate_quarter(offset)
+                });
+                [pivot[0] + offset[0], pivot[1] + offset[1]]
+            },
+            |orientation| {
+                (0..turns).fold(orientation, |orientation, _| {
+                    rotate_orientation(orientation)
+                })
+            },
+        )
+    }
+
+    // Symétrie autour de la verticale (`Horizontal`) ou de l'horizontale (`Vertical`)
+    // passant par `pivot`. Les portes logiques sont symétriques et une bascule D à niveau
+    // reprend les pins de sa nouvelle orientation ; un composant à pins multiples doit
+    // les retrouver une fois reflété, sinon (bascule, multiplexeur…) la sélection est refusée
+    pub fn mirror_selection(
+        &mut self,
+        components: Vec<usize>,
+        wires: Vec<usize>,
+        pivot: Vec<i32>,
+        direction: MirrorDirection,
+    ) -> Option<SelectionResult> {
+        let pivot = [pivot[0], pivot[1]];
+        let map = |p: Position| match direction {
+            MirrorDirection::Horizontal => [2 * pivot[0] - p[0], p[1]],
+            MirrorDirection::Vertical => [p[0], 2 * pivot[1] - p[1]],
+        };
+        let orientation = |orientation| match (direction, orientation) {
+            (MirrorDirection::Horizontal, Orientation::Right) => Orientation::Left,
+            (MirrorDirection::Horizontal, Orientation::Left) => Orientation::Right,
+            (MirrorDirection::Vertical, Orientation::Up) => Orientation::Down,
+            (MirrorDirection::Vertical, Orientation::Down) => Orientation::Up,
+            _ => orientation,
+        };
+        for id in &components {
+            let Some(gate) = self.composants_map.get(id).and_then(|c| c.multi_pin_gate()) else {
+                continue;
+            };
+            let mirrored = orientation(gate.orientation);
+            let is_symmetric = gate
+                .input_positions
+                .iter()
+                .chain(&gate.output_positions)
+                .all(|pin| {
+                    let offset = [pin[0] - gate.position[0], pin[1] - gate.position[1]];
+                    let offset = right_offset(offset, gate.orientation);
+                    oriented_position(map(gate.position), offset, mirrored) == map(*pin)
+                });
+            if !is_symmetric {
+                console_log(&format!("Component {} cannot be mirrored", id));
+                return None;
+            }
+        }
+        self.transform_selection(&components, &wires, map, orientation)
+    }
+
+    pub fn translate_selection(
+        &mut self,
+        components: Vec<usize>,
+        wires: Vec<usize>,
+        offset: Vec<i32>,
+    ) -> Option<SelectionResult> {
+        let offset = [offset[0], offset[1]];
+        self.transform_selection(
+            &components,
+            &wires,
+            |p| [p[0] + offset[0], p[1] + offset[1]],
+            |orientation| orientation,
+        )
+    }
+
+    // Copie la sélection décalée de `offset`. Les copies reçoivent les ids à partir de
+    // `first_id`, composants puis wires dans l'ordre des ids d'origine ; les liaisons
+    // internes à la sélection étant géométriques, elles sont conservées. Appeler ensuite
+    // `compute_connections`
+    pub fn duplicate_selection(
+        &mut self,
+        components: Vec<usize>,
+        wires: Vec<usize>,
+        offset: Vec<i32>,
+        first_id: usize,
+    ) -> Option<SelectionResult> {
+        if !self.is_valid_selection(&components, &wires) {
+            return None;
+        }
+        let mut components = components;
+        let mut wires = wires;
+        components.sort_unstable();
+        components.dedup();
+        wires.sort_unstable();
+        wires.dedup();
+        let count = components.len() + wires.len();
+        let is_used =
+            |id: usize| self.composants_map.contains_key(&id) || self.wires_map.contains_key(&id);
+        if (first_id..first_id + count).any(is_used) {
+            console_log(&format!("Ids from {} already in use", first_id));
+            return None;
+        }
+
+        let translate = |p: Position| [p[0] + offset[0], p[1] + offset[1]];
+        let junctions = self.selection_junctions(&wires);
+        let mut copies = Vec::with_capacity(components.len());
+        for (index, id) in components.iter().enumerate() {
+            let copy_id = first_id + index;
+            let mut copy = self.composants_map[id].clone();
+            copy.map_positions(translate);
+            let element = copy.circuit_element_mut();
+            element.id = copy_id;
+            element.inputs.clear();
+            element.outputs.clear();
+            element.state = 0;
+            if let Some(groups) = copy.input_groups_mut() {
+                groups.fill(None);
+            }
+            if let Some(gate) = copy.multi_pin_gate_mut() {
+                gate.output_states.fill(0);
+            }
+            if let ComposantsEnum::SubCircuit(sub_circuit) = &mut copy {
+                sub_circuit.is_expanded = false;
+                sub_circuit.component_ids.clear();
+                sub_circuit.wire_groups.clear();
+                sub_circuit.input_ports.clear();
+                sub_circuit.output_ports.clear();
+            }
+            self.composants_map.insert(copy_id, copy);
+            copies.push(copy_id);
+        }
+        let mut wire_copies = Vec::with_capacity(wires.len());
+        for (index, id) in wires.iter().enumerate() {
+            let copy_id = first_id + components.len() + index;
+            let wire = &self.wires_map[id];
+            let positions = wire.positions.iter().map(|p| translate(*p)).collect();
+            let copy = Wire::new_bus(copy_id, positions, wire.circuit_element.width);
+            self.wires_map.insert(copy_id, copy);
+            wire_copies.push(copy_id);
+        }
+        for junction in junctions {
+            self.junctions.insert(translate(junction));
+        }
+
+        let copied_from = components.iter().chain(wires.iter()).copied().collect();
+        Some(self.selection_result(&copies, &wire_copies, copied_from))
+    }
+}
+
+impl Simulation {
+    fn is_valid_selection(&self, components: &[usize], wires: &[usize]) -> bool {
+        for id in components {
+            if !self.composants_map.contains_key(id) || self.internal_ids.contains(id) {
+                console_log(&format!("Unknown component {}", id));
+                return false;
+            }
+        }
+        for id in wires {
+            if !self.wires_map.contains_key(id) {
+                console_log(&format!("Unknown wire {}", id));
+                return false;
+            }
+        }
+        true
+    }
+
+    // Jonctions portées uniquement par des wires de la sélection
+    fn selection_junctions(&self, wires: &[usize]) -> Vec<Position> {
+        let mut junctions: Vec<Position> = self
+            .junctions
+            .iter()
+            .filter(|junction| {
+                self.wires_map.iter().all(|(id, wire)| {
+                    wires.contains(id) || !polyline_contains(&wire.positions, **junction)
+                }) && wires
+                    .iter()
+                    .any(|id| polyline_contains(&self.wires_map[id].positions, **junction))
+            })
+            .copied()
+            .collect();
+        junctions.sort_unstable();
+        junctions
+    }
+
+    fn transform_selection(
+        &mut self,
+        components: &[usize],
+        wires: &[usize],
+        map: impl Fn(Position) -> Position,
+        orientation: impl Fn(Orientation) -> Orientation,
+    ) -> Option<SelectionResult> {
+        if !self.is_valid_selection(components, wires) {
+            return None;
+        }
+        let mut components = components.to_vec();
+        let mut wires = wires.to_vec();
+        components.sort_unstable();
+        components.dedup();
+        wires.sort_unstable();
+        wires.dedup();
+
+        let junctions = self.selection_junctions(&wires);
+        for id in &components {
+            let Some(composant) = self.composants_map.get_mut(id) else {
+                continue;
+            };
+            let new_orientation = orientation(composant.orientation());
+            composant.map_positions(&map);
+            // Les autres composants déduisent leur orientation de leurs pins
+            if let Some(gate) = composant.multi_pin_gate_mut() {
+                gate.orientation = new_orientation;
+            }
+            // Les entrées d'une bascule D à niveau ne sont pas interchangeables et leur
+            // ordre ne suit pas la transformation : elles sont replacées comme à la création
+            if let ComposantsEnum::LatchGate(latch_gate) = composant {
+                let circuit_element = latch_gate.gate.circuit_element.clone();
+                let position = latch_gate.gate.position;
+                latch_gate.gate = TwoInputsGate::new(circuit_element.id, position, new_orientation);
+                latch_gate.gate.circuit_element = circuit_element;
+            }
+        }
+        for id in &wires {
+            if let Some(wire) = self.wires_map.get_mut(id) {
+                for position in wire.positions.iter_mut() {
+                    *position = map(*position);
+                }
+            }
+        }
+        // Toutes les jonctions sont retirées avant d'insérer les nouvelles, qui peuvent
+        // tomber sur une ancienne
+        for junction in &junctions {
+            self.junctions.remove(junction);
+        }
+        for junction in junctions {
+            self.junctions.insert(map(junction));
+        }
+        Some(self.selection_result(&components, &wires, Vec::new()))
+    }
+
+    fn selection_result(
+        &self,
+        components: &[usize],
+        wires: &[usize],
+        copied_from: Vec<usize>,
+    ) -> SelectionResult {
+        let components = components
+            .iter()
+            .map(|id| {
+                let composant = &self.composants_map[id];
+                PlacedComponent {
+                    id: *id,
+                    kind: composant.kind_name(),
+                    position: composant.position(),
+                    orientation: composant.orientation(),
+                    inputs: composant.input_positions().len(),
+                }
+            })
+            .collect();
+        let wires = wires
+            .iter()
+            .map(|id| (*id, self.wires_map[id].positions.clone()))
+            .collect();
+        SelectionResult::new(components, wires, copied_from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::{ClockEdge, FlipFlopKind, MirrorDirection, Orientation, Position};
+    use crate::wire::Wire;
+    use crate::Simulation;
+
+    fn sorted(mut positions: Vec<Position>) -> Vec<Position> {
+        positions.sort_unstable();
+        positions
+    }
+
+    #[test]
+    fn translation_keeps_junctions_landing_on_each_other() {
+        let mut sim = Simulation::new();
+        sim.wires_map.insert(1, Wire::new(1, vec![[-2, 0], [5, 0]]));
+        sim.junctions.insert([0, 0]);
+        sim.junctions.insert([1, 0]);
+        assert!(sim
+            .translate_selection(Vec::new(), vec![1], vec![1, 0])
+            .is_some());
+        let mut junctions: Vec<Position> = sim.junctions.iter().copied().collect();
+        junctions.sort_unstable();
+        assert_eq!(junctions, vec![[1, 0], [2, 0]]);
+    }
+
+    #[test]
+    fn mirrored_gate_matches_its_new_orientation() {
+        let mut sim = Simulation::new();
+        sim.add_and_gate(vec![10, 0], Orientation::Right, 1);
+        assert!(sim
+            .mirror_selection(vec![1], Vec::new(), vec![0, 0], MirrorDirection::Horizontal)
+            .is_some());
+        let mirrored = &sim.composants_map[&1];
+        assert_eq!(mirrored.orientation(), Orientation::Left);
+
+        let mut expected = Simulation::new();
+        expected.add_and_gate(vec![-10, 0], Orientation::Left, 1);
+        let expected = &expected.composants_map[&1];
+        assert_eq!(mirrored.output_positions(), expected.output_positions());
+        assert_eq!(
+            sorted(mirrored.input_positions()),
+            sorted(expected.input_positions())
+        );
+    }
+
+    #[test]
+    fn asymmetric_components_are_not_mirrored() {
+        let mut sim = Simulation::new();
+        sim.add_flip_flop(
+            vec![10, 0],
+            FlipFlopKind::D,
+            ClockEdge::Rising,
+            Orientation::Right,
+            1,
+        );
+        sim.wires_map.insert(2, Wire::new(2, vec![[0, 5], [3, 5]]));
+        let pins = sim.composants_map[&1].pin_positions();
+        for direction in [MirrorDirection::Horizontal, MirrorDirection::Vertical] {
+            assert!(sim
+                .mirror_selection(vec![1], vec![2], vec![0, 0], direction)
+                .is_none());
+            assert_eq!(sim.composants_map[&1].pin_positions(), pins);
+            assert_eq!(sim.wires_map[&2].positions, vec![[0, 5], [3, 5]]);
+        }
+    }
+
+    // Pins d'une bascule D à niveau transformée, comparées à celles d'une bascule créée
+    // directement à sa nouvelle place
+    fn assert_latch_pins(sim: &Simulation, position: Position, orientation: Orientation) {
+        let mut expected = Simulation::new();
+        expected.add_latch_gate(position.to_vec(), orientation, 1);
+        let (latch, expected) = (&sim.composants_map[&1], &expected.composants_map[&1]);
+        assert_eq!(latch.orientation(), orientation);
+        assert_eq!(latch.input_positions(), expected.input_positions());
+        assert_eq!(latch.output_positions(), expected.output_positions());
+    }
+
+    #[test]
+    fn transformed_latch_takes_the_pins_of_its_new_orientation() {
+        let mut sim = Simulation::new();
+        sim.add_latch_gate(vec![10, 0], Orientation::Right, 1);
+        sim.composants_map
+            .get_mut(&1)
+            .unwrap()
+            .circuit_element_mut()
+            .set_state(1);
+        assert!(sim
+            .mirror_selection(vec![1], Vec::new(), vec![0, 0], MirrorDirection::Vertical)
+            .is_some());
+        assert_latch_pins(&sim, [10, 0], Orientation::Right);
+        assert!(sim
+            .mirror_selection(vec![1], Vec::new(), vec![0, 0], MirrorDirection::Horizontal)
+            .is_some());
+        assert_latch_pins(&sim, [-10, 0], Orientation::Left);
+        assert!(sim
+            .rotate_selection(vec![1], Vec::new(), vec![-10, 0], 1)
+            .is_some());
+        assert_latch_pins(&sim, [-10, 0], Orientation::Down);
+        assert_eq!(sim.get_component_state(1), 1);
+    }
+}
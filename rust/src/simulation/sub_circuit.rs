@@ -0,0 +1,371 @@
+use std::collections::HashMap;
+
+use crate::circuit_element::CircuitElementEnum;
+use crate::utils::console_log;
+use crate::ComposantsEnum;
+use crate::Simulation;
+
+use wasm_bindgen::prelude::*;
+
+// Les composants internes des sous-circuits mis à plat prennent leurs ids à partir
+// d'ici : le front choisit les ids de ses composants en dessous, sans les connaître
+pub(crate) const INTERNAL_ID_BASE: usize = 1 << 30;
+
+fn remap_element(
+    element: &mut CircuitElementEnum,
+    component_ids: &HashMap<usize, usize>,
+    group_offset: usize,
+) {
+    match element {
+        CircuitElementEnum::WireGroup(id) => *id += group_offset,
+        CircuitElementEnum::Component(id) | CircuitElementEnum::ComponentPin(id, _) => {
+            if let Some(new_id) = component_ids.get(id) {
+                *id = *new_id;
+            }
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Simulation {
+    // La définition est une simulation non connectée contenant des ports nommés.
+    // Les pins d'une instance suivent l'ordre croissant des ids des ports
+    pub fn define_sub_circuit(&mut self, name: String, definition: Simulation) {
+        self.sub_circuit_definitions.insert(name, definition);
+    }
+
+    pub fn sub_circuit_inputs(&self, name: String) -> Vec<String> {
+        self.sub_circuit_port_labels(&name, true)
+    }
+
+    pub fn sub_circuit_outputs(&self, name: String) -> Vec<String> {
+        self.sub_circuit_port_labels(&name, false)
+    }
+
+    // Id, dans la simulation mise à plat, d'un composant de la définition d'une instance.
+    // Pour une instance imbriquée, appeler de nouveau avec l'id renvoyé
+    pub fn sub_circuit_component_id(&self, instance_id: usize, inner_id: usize) -> Option<usize> {
+        match self.composants_map.get(&instance_id) {
+            Some(ComposantsEnum::SubCircuit(sub_circuit)) => {
+                sub_circuit.component_ids.get(&inner_id).copied()
+            }
+            _ => None,
+        }
+    }
+
+    pub fn get_sub_circuit_wire_state(&self, instance_id: usize, inner_wire_id: usize) -> u64 {
+        match self.composants_map.get(&instance_id) {
+            Some(ComposantsEnum::SubCircuit(sub_circuit)) => sub_circuit
+                .wire_groups
+                .get(&inner_wire_id)
+                .map(|group| self.wire_group_state(*group))
+                .unwrap_or(0),
+            _ => 0,
+        }
+    }
+}
+
+impl Simulation {
+    pub(crate) fn port_ids(&self, inputs: bool) -> Vec<usize> {
+        let mut ids: Vec<usize> = self
+            .composants_map
+            .iter()
+            .filter(|(_, composant)| match composant {
+                ComposantsEnum::InputPort(_) => inputs,
+                ComposantsEnum::OutputPort(_) => !inputs,
+                _ => false,
+            })
+            .map(|(id, _)| *id)
+            .collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    fn sub_circuit_port_labels(&self, name: &str, inputs: bool) -> Vec<String> {
+        let Some(definition) = self.sub_circuit_definitions.get(name) else {
+            return Vec::new();
+        };
+        definition
+            .port_ids(inputs)
+            .iter()
+            .filter_map(|id| match definition.composants_map.get(id) {
+                Some(ComposantsEnum::InputPort(port)) => Some(port.port.label.clone()),
+                Some(ComposantsEnum::OutputPort(port)) => Some(port.port.label.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub(crate) fn clone_circuit(&self) -> Simulation {
+        let mut circuit = Simulation::new();
+        circuit.composants_map = self.composants_map.clone();
+        circuit.wires_map = self.wires_map.clone();
+        circuit.connection_mode = self.connection_mode;
+        circuit.junctions = self.junctions.clone();
+        circuit.sub_circuit_definitions = self
+            .sub_circuit_definitions
+            .iter()
+            .map(|(name, definition)| (name.clone(), definition.clone_circuit()))
+            .collect();
+        circuit
+    }
+
+    // Premier id libre au-dessus des composants et wires du front
+    pub(crate) fn next_free_id(&self) -> usize {
+        self.composants_map
+            .keys()
+            .chain(self.wires_map.keys())
+            .filter(|id| **id < INTERNAL_ID_BASE)
+            .max()
+            .map_or(0, |id| id + 1)
+    }
+
+    fn next_internal_id(&self) -> usize {
+        self.composants_map
+            .keys()
+            .filter(|id| **id >= INTERNAL_ID_BASE)
+            .max()
+            .map_or(INTERNAL_ID_BASE, |id| id + 1)
+    }
+
+    pub(crate) fn expand_sub_circuits(&mut self) {
+        let mut instance_ids: Vec<usize> = self
+            .composants_map
+            .iter()
+            .filter_map(|(id, composant)| match composant {
+                ComposantsEnum::SubCircuit(sub_circuit) if !sub_circuit.is_expanded => Some(*id),
+                _ => None,
+            })
+            .collect();
+        instance_ids.sort_unstable();
+
+        let mut next_id = self.next_internal_id();
+        for instance_id in instance_ids {
+            let Some(ComposantsEnum::SubCircuit(sub_circuit)) =
+                self.composants_map.get(&instance_id)
+            else {
+                continue;
+            };
+            let Some(definition) = self.sub_circuit_definitions.get(&sub_circuit.definition) else {
+                console_log(&format!("Unknown sub circuit {}", sub_circuit.definition));
+                continue;
+            };
+
+            // Les instances imbriquées sont mises à plat dans la copie
+            let mut inner = definition.clone_circuit();
+            inner.compute_connections();
+            self.merge_sub_circuit(instance_id, inner, &mut next_id);
+        }
+    }
+
+    fn merge_sub_circuit(
+        &mut self,
+        instance_id: usize,
+        mut inner: Simulation,
+        next_id: &mut usize,
+    ) {
+        let group_offset = self.wire_groups.len();
+
+        let mut inner_ids: Vec<usize> = inner.composants_map.keys().copied().collect();
+        inner_ids.sort_unstable();
+        let component_ids: HashMap<usize, usize> = inner_ids
+            .iter()
+            .map(|id| {
+                let new_id = *next_id;
+                *next_id += 1;
+                (*id, new_id)
+            })
+            .collect();
+
+        let input_ports: Vec<usize> = inner
+            .port_ids(true)
+            .iter()
+            .map(|id| component_ids[id])
+            .collect();
+        let output_ports: Vec<usize> = inner
+            .port_ids(false)
+            .iter()
+            .map(|id| component_ids[id])
+            .collect();
+
+        let mut wire_groups = HashMap::new();
+        for mut wire_group in inner.wire_groups.drain(..) {
+            wire_group.circuit_element.id += group_offset;
+            for element in wire_group
+                .circuit_element
+                .inputs
+                .iter_mut()
+                .chain(wire_group.circuit_element.outputs.iter_mut())
+            {
+                remap_element(element, &component_ids, group_offset);
+            }
+            // Les wires internes ne sont pas connus du front
+            for wire in wire_group.wires.drain(..) {
+                wire_groups.insert(wire, wire_group.circuit_element.id);
+            }
+            self.wire_groups.push(wire_group);
+        }
+
+        for (id, mut composant) in inner.composants_map.drain() {
+            let new_id = component_ids[&id];
+            let circuit_element = composant.circuit_element_mut();
+            circuit_element.id = new_id;
+            for element in circuit_element
+                .inputs
+                .iter_mut()
+                .chain(circuit_element.outputs.iter_mut())
+            {
+                remap_element(element, &component_ids, group_offset);
+            }
+            if let Some(groups) = composant.input_groups_mut() {
+                for group in groups.iter_mut().flatten() {
+                    *group += group_offset;
+                }
+            }
+            if let ComposantsEnum::SubCircuit(nested) = &mut composant {
+                for nested_id in nested.component_ids.values_mut() {
+                    *nested_id = component_ids[nested_id];
+                }
+                for group in nested.wire_groups.values_mut() {
+                    *group += group_offset;
+                }
+                for port in nested
+                    .input_ports
+                    .iter_mut()
+                    .chain(nested.output_ports.iter_mut())
+                {
+                    *port = component_ids[port];
+                }
+            }
+            self.internal_ids.insert(new_id);
+            self.composants_map.insert(new_id, composant);
+        }
+
+        let output_widths: Vec<u8> = output_ports
+            .iter()
+            .map(|id| self.composants_map[id].circuit_element().width)
+            .collect();
+
+        let Some(ComposantsEnum::SubCircuit(sub_circuit)) =
+            self.composants_map.get_mut(&instance_id)
+        else {
+            return;
+        };
+        let input_groups = sub_circuit.gate.input_groups.clone();
+        for (pin, width) in output_widths.into_iter().enumerate() {
+            if let Some(output_width) = sub_circuit.gate.output_widths.get_mut(pin) {
+                *output_width = width;
+            }
+        }
+        sub_circuit.component_ids = component_ids;
+        sub_circuit.wire_groups = wire_groups;
+        sub_circuit.input_ports = input_ports.clone();
+        sub_circuit.output_ports = output_ports;
+        sub_circuit.is_expanded = true;
+
+        // Les ports d'entrée lisent le wire group branché sur la pin de l'instance
+        for (port_id, group) in input_ports.iter().zip(input_groups) {
+            let Some(group) = group else {
+                continue;
+            };
+            if let Some(port) = self.composants_map.get_mut(port_id) {
+                port.circuit_element_mut()
+                    .inputs
+                    .push(CircuitElementEnum::WireGroup(group));
+            }
+            self.wire_groups[group]
+                .circuit_element
+                .outputs
+                .push(CircuitElementEnum::Component(*port_id));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::Orientation;
+    use crate::wire::Wire;
+    use crate::Simulation;
+
+    fn wire(sim: &mut Simulation, id: usize, positions: Vec<[i32; 2]>) {
+        sim.wires_map.insert(id, Wire::new(id, positions));
+    }
+
+    // a ^ b : ports d'entrée 1 et 2, port de sortie 3
+    fn xor_definition() -> Simulation {
+        let mut sim = Simulation::new();
+        sim.add_input_port(vec![-10, 1], "a".to_string(), 1);
+        sim.add_input_port(vec![-10, -1], "b".to_string(), 2);
+        sim.add_output_port(vec![5, 0], "s".to_string(), 3);
+        sim.add_xor_gate(vec![0, 0], Orientation::Right, 4);
+        wire(&mut sim, 10, vec![[-10, 1], [-5, 1]]);
+        wire(&mut sim, 11, vec![[-10, -1], [-5, -1]]);
+        wire(&mut sim, 12, vec![[0, 0], [5, 0]]);
+        sim
+    }
+
+    // a ^ b ^ c avec deux instances imbriquées de `xor` (4 et 5)
+    fn parity_definition() -> Simulation {
+        let mut sim = Simulation::new();
+        sim.define_sub_circuit("xor".to_string(), xor_definition());
+        sim.add_input_port(vec![-10, 1], "a".to_string(), 1);
+        sim.add_input_port(vec![-10, -1], "b".to_string(), 2);
+        sim.add_input_port(vec![-10, -5], "c".to_string(), 3);
+        sim.add_sub_circuit(vec![0, 0], "xor".to_string(), Orientation::Right, 4);
+        sim.add_sub_circuit(vec![20, 0], "xor".to_string(), Orientation::Right, 5);
+        sim.add_output_port(vec![25, 0], "p".to_string(), 6);
+        wire(&mut sim, 10, vec![[-10, 1], [-5, 1]]);
+        wire(&mut sim, 11, vec![[-10, -1], [-5, -1]]);
+        wire(&mut sim, 12, vec![[0, 0], [10, 0], [10, 1], [15, 1]]);
+        wire(&mut sim, 13, vec![[-10, -5], [12, -5], [12, -1], [15, -1]]);
+        wire(&mut sim, 14, vec![[20, 0], [25, 0]]);
+        sim
+    }
+
+    // Switches 1 à 3 -> instance 7 de `parity` -> LED 4
+    fn circuit() -> Simulation {
+        let mut sim = Simulation::new();
+        sim.define_sub_circuit("parity".to_string(), parity_definition());
+        for (index, y) in [2, 0, -2].into_iter().enumerate() {
+            sim.add_switch(vec![-10, y], 1 + index);
+            wire(&mut sim, 10 + index, vec![[-10, y], [-5, y]]);
+        }
+        sim.add_sub_circuit(vec![0, 0], "parity".to_string(), Orientation::Right, 7);
+        sim.add_led(vec![5, 0], 0, Orientation::Right, 4);
+        wire(&mut sim, 13, vec![[0, 0], [5, 0]]);
+        sim.compute_connections();
+        sim
+    }
+
+    #[test]
+    fn nested_instances_compute_the_parity() {
+        let mut sim = circuit();
+        assert_eq!(
+            sim.sub_circuit_inputs("parity".to_string()),
+            vec!["a", "b", "c"]
+        );
+        for row in 0..8u64 {
+            for bit in 0..3 {
+                sim.update_switch_state(1 + bit, (row >> bit) & 1 == 1);
+            }
+            sim.compute_frame(100, 1);
+            assert_eq!(sim.get_component_state(4), row.count_ones() as u64 % 2);
+            // Wire interne entre les deux xor : a ^ b
+            assert_eq!(
+                sim.get_sub_circuit_wire_state(7, 12),
+                (row & 1) ^ ((row >> 1) & 1)
+            );
+        }
+    }
+
+    #[test]
+    fn flattened_components_are_internal() {
+        let sim = circuit();
+        let first = sim.sub_circuit_component_id(7, 4).unwrap();
+        let nested_gate = sim.sub_circuit_component_id(first, 4).unwrap();
+        assert!(sim.internal_ids.contains(&first));
+        assert!(sim.internal_ids.contains(&nested_gate));
+        assert!(sim.next_free_id() < first);
+        assert_eq!(sim.sub_circuit_component_id(7, 99), None);
+    }
+}
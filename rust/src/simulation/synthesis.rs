@@ -0,0 +1,430 @@
+use std::collections::HashMap;
+
+use crate::components::{MultiInputsGate, OneInputGate};
+use crate::js::SynthesisResult;
+use crate::simulation::logic::{
+    factor, minimize, parse_expression, sum_of_products, Logic, MAX_SYNTHESIS_VARIABLES,
+};
+use crate::types::{LogicGateKind, Orientation, PlacedComponent, Position, SynthesisForm};
+use crate::utils::console_log;
+use crate::wire::Wire;
+use crate::Simulation;
+
+use wasm_bindgen::prelude::*;
+
+const OUTPUT_LED_COLOR: u32 = 0xff0000;
+// Distance entre les pins d'entrée et la sortie d'une porte orientée à droite
+const GATE_LENGTH: i32 = 5;
+// Écart entre deux pistes verticales d'un canal, et entre le canal et les pins
+const TRACK_SPACING: i32 = 2;
+// Demi-hauteur minimale réservée à un composant, et écart entre deux composants
+const MIN_HALF_HEIGHT: i32 = 2;
+const ROW_GAP: i32 = 2;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum CellKind {
+    Switch(usize),
+    Constant(bool),
+    Not,
+    Gate(LogicGateKind),
+    Led,
+}
+
+struct Cell {
+    kind: CellKind,
+    inputs: Vec<usize>,
+}
+
+impl Cell {
+    // Position de la pin d'entrée `pin` du composant placé à `position`, orienté à droite
+    fn input_position(&self, position: Position, pin: usize) -> Position {
+        match self.kind {
+            CellKind::Not => OneInputGate::new(0, position, Orientation::Right).input_position,
+            CellKind::Gate(_) => {
+                let inputs = self.inputs.len() as u8;
+                MultiInputsGate::new(0, position, Orientation::Right, inputs).input_positions[pin]
+            }
+            _ => position,
+        }
+    }
+}
+
+// Portes à créer, une seule par couple (type, entrées)
+#[derive(Default)]
+struct Netlist {
+    cells: Vec<Cell>,
+    cells_by_key: HashMap<(CellKind, Vec<usize>), usize>,
+}
+
+impl Netlist {
+    fn cell(&mut self, kind: CellKind, inputs: Vec<usize>) -> usize {
+        let key = (kind, inputs);
+        if let Some(index) = self.cells_by_key.get(&key) {
+            return *index;
+        }
+        self.cells.push(Cell {
+            kind,
+            inputs: key.1.clone(),
+        });
+        self.cells_by_key.insert(key, self.cells.len() - 1);
+        self.cells.len() - 1
+    }
+
+    fn build(&mut self, logic: &Logic) -> usize {
+        match logic {
+            Logic::Constant(value) => self.cell(CellKind::Constant(*value), Vec::new()),
+            Logic::Variable(index) => self.cell(CellKind::Switch(*index), Vec::new()),
+            Logic::Not(inner) => {
+                let input = self.build(inner);
+                self.cell(CellKind::Not, vec![input])
+            }
+            Logic::And(terms) => self.gate(LogicGateKind::And, terms),
+            Logic::Or(terms) => self.gate(LogicGateKind::Or, terms),
+            Logic::Xor(terms) => self.gate(LogicGateKind::Xor, terms),
+        }
+    }
+
+    // Au-delà du nombre maximal d'entrées, la porte devient un arbre de portes
+    fn gate(&mut self, kind: LogicGateKind, terms: &[Logic]) -> usize {
+        let max_inputs = MultiInputsGate::MAX_INPUTS as usize;
+        let mut inputs: Vec<usize> = terms.iter().map(|term| self.build(term)).collect();
+        while inputs.len() > max_inputs {
+            inputs = inputs
+                .chunks(max_inputs)
+                .map(|chunk| match chunk {
+                    [input] => *input,
+                    _ => self.cell(CellKind::Gate(kind), chunk.to_vec()),
+                })
+                .collect();
+        }
+        self.cell(CellKind::Gate(kind), inputs)
+    }
+}
+
+// Liaison d'une sortie vers la pin d'entrée `pin` d'une autre cellule
+struct Connection {
+    source: usize,
+    sink: usize,
+    pin: usize,
+}
+
+#[wasm_bindgen]
+impl Simulation {
+    // Génère le circuit d'une expression (voir `parse_expression` pour la syntaxe) :
+    // un switch par variable, par ordre alphabétique, et une LED de sortie. Les
+    // composants et wires reçoivent les ids à partir de `first_id` ; appeler ensuite
+    // `compute_connections`
+    pub fn synthesize_expression(
+        &mut self,
+        expression: String,
+        form: SynthesisForm,
+        position: Vec<i32>,
+        first_id: usize,
+    ) -> Option<SynthesisResult> {
+        let (logic, variables) = parse_expression(&expression)?;
+        if variables.len() > MAX_SYNTHESIS_VARIABLES {
+            console_log(&format!("Too many variables: {}", variables.len()));
+            return None;
+        }
+        let ones: Vec<u32> = (0..1u32 << variables.len())
+            .filter(|row| logic.eval(*row, variables.len()))
+            .collect();
+        self.synthesize(
+            variables,
+            &ones,
+            &[],
+            form,
+            [position[0], position[1]],
+            first_id,
+        )
+    }
+
+    // Même chose à partir d'une table de vérité : `outputs[row]` vaut 0, 1 ou, pour
+    // toute autre valeur, indifférent. La première variable est le bit de poids fort de `row`
+    pub fn synthesize_truth_table(
+        &mut self,
+        variables: Vec<String>,
+        outputs: Vec<u8>,
+        form: SynthesisForm,
+        position: Vec<i32>,
+        first_id: usize,
+    ) -> Option<SynthesisResult> {
+        if variables.len() > MAX_SYNTHESIS_VARIABLES || outputs.len() != 1 << variables.len() {
+            console_log("Invalid truth table");
+            return None;
+        }
+        let rows_with = |value: u8| -> Vec<u32> {
+            (0..outputs.len() as u32)
+                .filter(|row| match outputs[*row as usize] {
+                    0 | 1 => outputs[*row as usize] == value,
+                    _ => value > 1,
+                })
+                .collect()
+        };
+        let (ones, dont_cares) = (rows_with(1), rows_with(2));
+        self.synthesize(
+            variables,
+            &ones,
+            &dont_cares,
+            form,
+            [position[0], position[1]],
+            first_id,
+        )
+    }
+}
+
+impl Simulation {
+    fn synthesize(
+        &mut self,
+        variables: Vec<String>,
+        ones: &[u32],
+        dont_cares: &[u32],
+        form: SynthesisForm,
+        origin: Position,
+        first_id: usize,
+    ) -> Option<SynthesisResult> {
+        let cubes = minimize(variables.len(), ones, dont_cares);
+        let logic = match form {
+            SynthesisForm::SumOfProducts => sum_of_products(&cubes),
+            SynthesisForm::Factored => factor(&cubes),
+        };
+
+        let mut netlist = Netlist::default();
+        for index in 0..variables.len() {
+            netlist.cell(CellKind::Switch(index), Vec::new());
+        }
+        let root = netlist.build(&logic);
+        netlist.cell(CellKind::Led, vec![root]);
+        let cells = netlist.cells;
+
+        let connections: Vec<Connection> = cells
+            .iter()
+            .enumerate()
+            .flat_map(|(sink, cell)| {
+                cell.inputs
+                    .iter()
+                    .enumerate()
+                    .map(move |(pin, source)| Connection {
+                        source: *source,
+                        sink,
+                        pin,
+                    })
+            })
+            .collect();
+        let id_count = cells.len() + connections.len();
+        let is_used =
+            |id: usize| self.composants_map.contains_key(&id) || self.wires_map.contains_key(&id);
+        if (first_id..first_id + id_count).any(is_used) {
+            console_log(&format!("Ids from {} already in use", first_id));
+            return None;
+        }
+
+        // Colonnes par profondeur logique, une rangée par cellule
+        let mut levels = vec![0; cells.len()];
+        for (index, cell) in cells.iter().enumerate() {
+            levels[index] = cell
+                .inputs
+                .iter()
+                .map(|input| levels[*input] + 1)
+                .max()
+                .unwrap_or(0);
+        }
+        let mut order: Vec<usize> = (0..cells.len()).collect();
+        order.sort_by_key(|index| (levels[*index], *index));
+        let half_height = |cell: &Cell| match cell.kind {
+            CellKind::Gate(_) => MIN_HALF_HEIGHT.max(cell.inputs.len() as i32 - 1),
+            _ => MIN_HALF_HEIGHT,
+        };
+        let mut rows = vec![0; cells.len()];
+        let mut y = origin[1];
+        for (rank, index) in order.iter().enumerate() {
+            if rank > 0 {
+                y += half_height(&cells[order[rank - 1]]) + ROW_GAP + half_height(&cells[*index]);
+            }
+            rows[*index] = y;
+        }
+
+        // Canal de pistes verticales à gauche de chaque colonne, une piste par liaison
+        let max_level = levels.iter().copied().max().unwrap_or(0);
+        let mut columns = vec![origin[0]; max_level + 1];
+        let mut tracks = vec![0; connections.len()];
+        for level in 1..=max_level {
+            let mut incoming: Vec<usize> = (0..connections.len())
+                .filter(|index| levels[connections[*index].sink] == level)
+                .collect();
+            incoming.sort_by_key(|index| {
+                let connection = &connections[*index];
+                (
+                    rows[connection.source],
+                    rows[connection.sink],
+                    connection.pin,
+                )
+            });
+            for (rank, index) in incoming.iter().enumerate() {
+                tracks[*index] = columns[level - 1] + TRACK_SPACING * (rank as i32 + 1);
+            }
+            let pins = columns[level - 1] + TRACK_SPACING * (incoming.len() as i32 + 1);
+            let is_led_column = cells
+                .iter()
+                .enumerate()
+                .any(|(index, cell)| levels[index] == level && cell.kind == CellKind::Led);
+            columns[level] = if is_led_column {
+                pins
+            } else {
+                pins + GATE_LENGTH
+            };
+        }
+
+        let mut components = Vec::with_capacity(cells.len());
+        for (index, cell) in cells.iter().enumerate() {
+            let id = first_id + index;
+            let position = vec![columns[levels[index]], rows[index]];
+            let kind = match cell.kind {
+                CellKind::Switch(_) => {
+                    self.add_switch(position.clone(), id);
+                    "Switch"
+                }
+                CellKind::Constant(true) => {
+                    self.add_constant_high(position.clone(), id);
+                    "ConstantHigh"
+                }
+                CellKind::Constant(false) => {
+                    self.add_constant_low(position.clone(), id);
+                    "ConstantLow"
+                }
+                CellKind::Not => {
+                    self.add_not_gate(position.clone(), Orientation::Right, id);
+                    "NotGate"
+                }
+                CellKind::Gate(kind) => {
+                    let inputs = cell.inputs.len() as u8;
+                    self.add_logic_gate(position.clone(), kind, inputs, Orientation::Right, id);
+                    self.composants_map[&id].kind_name()
+                }
+                CellKind::Led => {
+                    self.add_led(position.clone(), OUTPUT_LED_COLOR, Orientation::Right, id);
+                    "Led"
+                }
+            };
+            components.push(PlacedComponent {
+                id,
+                kind,
+                position: [position[0], position[1]],
+                orientation: Orientation::Right,
+                inputs: cell.inputs.len(),
+            });
+        }
+
+        // Une ligne horizontale par sortie, prolongée jusqu'à sa piste la plus à droite ;
+        // chaque autre liaison part d'un sommet de cette ligne
+        let mut wires = Vec::with_capacity(connections.len());
+        let mut next_id = first_id + cells.len();
+        for source in 0..cells.len() {
+            let mut outgoing: Vec<usize> = (0..connections.len())
+                .filter(|index| connections[*index].source == source)
+                .collect();
+            outgoing.sort_by_key(|index| tracks[*index]);
+            let Some(last) = outgoing.pop() else {
+                continue;
+            };
+            let start: Position = [columns[levels[source]], rows[source]];
+            let branch = |index: usize| -> Vec<Position> {
+                let connection = &connections[index];
+                let sink = connection.sink;
+                let position = [columns[levels[sink]], rows[sink]];
+                let pin = cells[sink].input_position(position, connection.pin);
+                vec![[tracks[index], start[1]], [tracks[index], pin[1]], pin]
+            };
+            let mut trunk = vec![start];
+            trunk.extend(outgoing.iter().map(|index| [tracks[*index], start[1]]));
+            trunk.extend(branch(last));
+            let mut paths = vec![trunk];
+            paths.extend(outgoing.iter().map(|index| branch(*index)));
+            for positions in paths {
+                self.wires_map
+                    .insert(next_id, Wire::new(next_id, positions.clone()));
+                wires.push((next_id, positions));
+                next_id += 1;
+            }
+        }
+
+        let expression = logic.to_text(&variables);
+        let inputs = (0..variables.len()).map(|index| first_id + index).collect();
+        let output = first_id + cells.len() - 1;
+        Some(SynthesisResult::new(
+            variables, inputs, output, expression, components, wires, next_id,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::js::SynthesisResult;
+    use crate::simulation::logic::parse_expression;
+    use crate::types::SynthesisForm;
+    use crate::Simulation;
+
+    const FORMS: [SynthesisForm; 2] = [SynthesisForm::SumOfProducts, SynthesisForm::Factored];
+
+    // Valeur de la LED de sortie pour chaque ligne de la table de vérité
+    fn simulated_rows(sim: &mut Simulation, result: &SynthesisResult) -> Vec<bool> {
+        sim.compute_connections();
+        let inputs = result.inputs();
+        (0..1u32 << inputs.len())
+            .map(|row| {
+                for (index, id) in inputs.iter().enumerate() {
+                    let bit = (row >> (inputs.len() - 1 - index)) & 1 == 1;
+                    sim.update_switch_state(*id, bit);
+                }
+                sim.compute_frame(100, 1);
+                sim.get_component_state(result.output()) != 0
+            })
+            .collect()
+    }
+
+    #[test]
+    fn expression_circuit_matches_every_row() {
+        let expression = "A & B | !C";
+        let (logic, variables) = parse_expression(expression).unwrap();
+        for form in FORMS {
+            let mut sim = Simulation::new();
+            let result = sim
+                .synthesize_expression(expression.into(), form, vec![0, 0], 1)
+                .unwrap();
+            let rows = simulated_rows(&mut sim, &result);
+            for (row, value) in rows.iter().enumerate() {
+                assert_eq!(
+                    *value,
+                    logic.eval(row as u32, variables.len()),
+                    "row {}",
+                    row
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn truth_table_dont_cares_are_free() {
+        // A | B une fois la ligne indifférente 11 mise à 1, au lieu d'un OU exclusif
+        let outputs = vec![0, 1, 1, 2];
+        for form in FORMS {
+            let mut sim = Simulation::new();
+            let result = sim
+                .synthesize_truth_table(
+                    vec!["A".into(), "B".into()],
+                    outputs.clone(),
+                    form,
+                    vec![0, 0],
+                    1,
+                )
+                .unwrap();
+            assert_eq!(result.gate_count(), 1);
+            let rows = simulated_rows(&mut sim, &result);
+            for (row, value) in rows.iter().enumerate() {
+                if outputs[row] <= 1 {
+                    assert_eq!(*value, outputs[row] == 1, "row {}", row);
+                }
+            }
+        }
+    }
+}
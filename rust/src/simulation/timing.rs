@@ -0,0 +1,353 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use crate::types::ComponentKind;
+use crate::utils::console_log;
+use crate::{ComposantsEnum, PendingStates, Simulation};
+
+use wasm_bindgen::prelude::*;
+
+pub const DEFAULT_SUB_TICKS_PER_TICK: u32 = 10;
+pub const MAX_WAVEFORM_SAMPLES: usize = 65536;
+
+// Sources, afficheurs et composants de câblage : sans délai par défaut
+fn has_zero_delay(composant: &ComposantsEnum) -> bool {
+    match composant {
+        ComposantsEnum::Switch(_)
+        | ComposantsEnum::Button(_)
+        | ComposantsEnum::DipSwitch(_)
+        | ComposantsEnum::Constant(_)
+        | ComposantsEnum::Clock(_)
+        | ComposantsEnum::Keyboard(_)
+        | ComposantsEnum::InputPort(_)
+        | ComposantsEnum::OutputPort(_)
+        | ComposantsEnum::SubCircuit(_)
+        | ComposantsEnum::Splitter(_)
+        | ComposantsEnum::Merger(_)
+        | ComposantsEnum::Pull(_)
+        | ComposantsEnum::Tunnel(_)
+        | ComposantsEnum::Led(_)
+        | ComposantsEnum::SevenSegment(_)
+        | ComposantsEnum::PixelMatrix(_)
+        | ComposantsEnum::Terminal(_) => true,
+        ComposantsEnum::OrGate(_)
+        | ComposantsEnum::AndGate(_)
+        | ComposantsEnum::XorGate(_)
+        | ComposantsEnum::NotGate(_)
+        | ComposantsEnum::BufferGate(_)
+        | ComposantsEnum::LatchGate(_)
+        | ComposantsEnum::TimerGate(_)
+        | ComposantsEnum::FlipFlop(_)
+        | ComposantsEnum::NandGate(_)
+        | ComposantsEnum::NorGate(_)
+        | ComposantsEnum::XnorGate(_)
+        | ComposantsEnum::Rom(_)
+        | ComposantsEnum::Ram(_)
+        | ComposantsEnum::Register(_)
+        | ComposantsEnum::Counter(_)
+        | ComposantsEnum::ShiftRegister(_)
+        | ComposantsEnum::Multiplexer(_)
+        | ComposantsEnum::Demultiplexer(_)
+        | ComposantsEnum::Decoder(_)
+        | ComposantsEnum::PriorityEncoder(_)
+        | ComposantsEnum::Adder(_)
+        | ComposantsEnum::Comparator(_)
+        | ComposantsEnum::Alu(_) => false,
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub enum TimedUpdate {
+    State(u64),
+    Pins(Vec<u64>),
+    Sampled(Vec<u64>),
+}
+
+pub struct WaveformProbe {
+    pub wire: usize,
+    pub wire_group: usize,
+    pub last_state: u64,
+}
+
+// Modèle de délais de propagation. Quand il est actif, chaque changement de sortie
+// d'un composant est planifié `délai` sous-ticks plus tard sur une roue d'évènements
+// (délai de transport : les impulsions plus courtes que le délai sont conservées),
+// et les wire groups se résolvent sans délai. Un tick simulé couvre `sub_ticks_per_tick`
+// sous-ticks. Sans modèle, chaque itération de `tick` compte pour un sous-tick
+pub struct TimingModel {
+    pub enabled: bool,
+    pub sub_ticks_per_tick: u32,
+    pub kind_delays: HashMap<ComponentKind, u32>,
+    pub component_delays: HashMap<usize, u32>,
+    pub time: u64,
+    pub wheel: BTreeMap<u64, Vec<(usize, TimedUpdate)>>,
+    // Dernière valeur planifiée pour chaque composant
+    pub projected: HashMap<usize, TimedUpdate>,
+    pub probes: Vec<WaveformProbe>,
+    // (sous-tick, wire, état)
+    pub waveform: VecDeque<(u64, usize, u64)>,
+}
+
+impl Default for TimingModel {
+    fn default() -> Self {
+        TimingModel {
+            enabled: false,
+            sub_ticks_per_tick: DEFAULT_SUB_TICKS_PER_TICK,
+            kind_delays: HashMap::new(),
+            component_delays: HashMap::new(),
+            time: 0,
+            wheel: BTreeMap::new(),
+            projected: HashMap::new(),
+            probes: Vec::new(),
+            waveform: VecDeque::new(),
+        }
+    }
+}
+
+impl TimingModel {
+    pub fn delay(&self, id: usize, composant: &ComposantsEnum) -> u32 {
+        if let Some(delay) = self.component_delays.get(&id) {
+            return *delay;
+        }
+        match self.kind_delays.get(&composant.kind()) {
+            Some(delay) => *delay,
+            None if has_zero_delay(composant) => 0,
+            None => 1,
+        }
+    }
+
+    // Oublie les évènements planifiés, sans toucher à la configuration des délais
+    pub fn clear_events(&mut self) {
+        self.wheel.clear();
+        self.projected.clear();
+    }
+}
+
+#[wasm_bindgen]
+impl Simulation {
+    pub fn set_timing_enabled(&mut self, enabled: bool) {
+        self.timing.enabled = enabled;
+        self.timing.clear_events();
+    }
+
+    pub fn is_timing_enabled(&self) -> bool {
+        self.timing.enabled
+    }
+
+    pub fn set_sub_ticks_per_tick(&mut self, sub_ticks: u32) {
+        self.timing.sub_ticks_per_tick = sub_ticks.max(1);
+    }
+
+    pub fn set_kind_delay(&mut self, kind: ComponentKind, delay: u32) {
+        self.timing.kind_delays.insert(kind, delay);
+    }
+
+    pub fn set_component_delay(&mut self, component_index: usize, delay: u32) {
+        self.timing.component_delays.insert(component_index, delay);
+    }
+
+    pub fn clear_component_delay(&mut self, component_index: usize) {
+        self.timing.component_delays.remove(&component_index);
+    }
+
+    pub fn get_component_delay(&self, component_index: usize) -> u32 {
+        match self.composants_map.get(&component_index) {
+            Some(composant) => self.timing.delay(component_index, composant),
+            None => 0,
+        }
+    }
+
+    pub fn get_sub_tick(&self) -> u64 {
+        self.timing.time
+    }
+
+    // Enregistre chaque changement d'état du wire, transitoires compris.
+    // À appeler après `compute_connections`
+    pub fn add_waveform_probe(&mut self, wire_index: usize) -> bool {
+        let Some(wire_group) = self
+            .wire_groups
+            .iter()
+            .find(|wire_group| wire_group.wires.contains(&wire_index))
+        else {
+            console_log("Invalid wire index");
+            return false;
+        };
+        self.timing.probes.push(WaveformProbe {
+            wire: wire_index,
+            wire_group: wire_group.circuit_element.id,
+            last_state: wire_group.circuit_element.state,
+        });
+        true
+    }
+
+    pub fn remove_waveform_probe(&mut self, wire_index: usize) {
+        self.timing.probes.retain(|probe| probe.wire != wire_index);
+    }
+
+    // Échantillons enregistrés depuis le dernier appel, à plat : [sous-tick, wire, état]*
+    pub fn drain_waveform(&mut self) -> Vec<u64> {
+        self.timing
+            .waveform
+            .drain(..)
+            .flat_map(|(time, wire, state)| [time, wire as u64, state])
+            .collect()
+    }
+}
+
+impl Simulation {
+    pub(crate) fn record_waveform(&mut self) {
+        let time = self.timing.time;
+        for probe in self.timing.probes.iter_mut() {
+            let state = match self.wire_groups.get(probe.wire_group) {
+                Some(wire_group) => wire_group.circuit_element.state,
+                None => continue,
+            };
+            if state != probe.last_state {
+                probe.last_state = state;
+                if self.timing.waveform.len() >= MAX_WAVEFORM_SAMPLES {
+                    self.timing.waveform.pop_front();
+                }
+                self.timing.waveform.push_back((time, probe.wire, state));
+            }
+        }
+    }
+
+    fn resolve_wire_groups(&mut self) {
+        let new_states: Vec<u64> = self
+            .wire_groups
+            .iter()
+            .map(|wire_group| wire_group.compute_next_state(self).0)
+            .collect();
+        for (wire_group, state) in self.wire_groups.iter_mut().zip(new_states) {
+            wire_group.circuit_element.set_state(state);
+        }
+        self.record_waveform();
+    }
+
+    // Planifie les composants dont la valeur calculée diffère de la dernière planifiée
+    fn schedule_timed_updates(&mut self) {
+        let pending = self.compute_pending_states();
+        let updates = pending
+            .composant_states
+            .into_iter()
+            .map(|(id, (state, _))| (id, TimedUpdate::State(state)))
+            .chain(
+                pending
+                    .pin_states
+                    .into_iter()
+                    .map(|(id, outputs)| (id, TimedUpdate::Pins(outputs))),
+            )
+            .chain(
+                pending
+                    .sampled_inputs
+                    .into_iter()
+                    .map(|(id, inputs)| (id, TimedUpdate::Sampled(inputs))),
+            );
+
+        for (id, update) in updates {
+            if self.timing.projected.get(&id) == Some(&update) {
+                continue;
+            }
+            let delay = match self.composants_map.get(&id) {
+                Some(composant) => self.timing.delay(id, composant),
+                None => continue,
+            };
+            self.timing.projected.insert(id, update.clone());
+            self.timing
+                .wheel
+                .entry(self.timing.time + delay as u64)
+                .or_default()
+                .push((id, update));
+        }
+    }
+
+    // Déroule un tick simulé avec le modèle de délais
+    pub(crate) fn run_timed(&mut self, max_depth: u32) -> bool {
+        let end = self.timing.time + self.timing.sub_ticks_per_tick as u64;
+        let mut counter: u32 = 0;
+
+        loop {
+            self.resolve_wire_groups();
+            self.schedule_timed_updates();
+
+            let Some(time) = self
+                .timing
+                .wheel
+                .first_key_value()
+                .map(|(time, _)| *time)
+                .filter(|time| *time <= end)
+            else {
+                break;
+            };
+            counter += 1;
+            if counter == max_depth {
+                // Circuit instable : le temps reste au dernier évènement appliqué, les
+                // évènements restants seront appliqués au tick suivant sans remonter le temps
+                return true;
+            }
+
+            self.timing.time = time;
+            let mut pending = PendingStates {
+                composant_states: Vec::new(),
+                pin_states: Vec::new(),
+                sampled_inputs: Vec::new(),
+                is_different: true,
+            };
+            for (id, update) in self.timing.wheel.remove(&time).unwrap_or_default() {
+                match update {
+                    TimedUpdate::State(state) => pending.composant_states.push((id, (state, true))),
+                    TimedUpdate::Pins(outputs) => pending.pin_states.push((id, outputs)),
+                    TimedUpdate::Sampled(inputs) => pending.sampled_inputs.push((id, inputs)),
+                }
+            }
+            self.apply_pending_states(pending);
+        }
+
+        self.timing.time = end;
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::Orientation;
+    use crate::wire::Wire;
+    use crate::Simulation;
+
+    // A AND NOT A : le retard de l'inverseur laisse passer une impulsion parasite
+    // quand le switch 1 monte. Le wire 13 est en sortie de la porte AND
+    fn hazard_circuit() -> Simulation {
+        let mut sim = Simulation::new();
+        sim.add_switch(vec![0, 0], 1);
+        sim.add_not_gate(vec![20, 0], Orientation::Right, 2);
+        sim.add_and_gate(vec![40, 0], Orientation::Right, 3);
+        sim.wires_map
+            .insert(10, Wire::new(10, vec![[0, 0], [15, 0]]));
+        sim.wires_map
+            .insert(11, Wire::new(11, vec![[0, 0], [35, 1]]));
+        sim.wires_map
+            .insert(12, Wire::new(12, vec![[20, 0], [35, -1]]));
+        sim.wires_map
+            .insert(13, Wire::new(13, vec![[40, 0], [50, 0]]));
+        sim.compute_connections();
+        sim
+    }
+
+    #[test]
+    fn event_wheel_keeps_the_glitch_of_a_slow_inverter() {
+        let mut sim = hazard_circuit();
+        sim.set_timing_enabled(true);
+        sim.set_component_delay(2, 3);
+        assert_eq!(sim.get_component_delay(2), 3);
+        assert_eq!(sim.get_component_delay(3), 1);
+        assert!(sim.add_waveform_probe(13));
+        sim.compute_frame(100, 1);
+        assert_eq!(sim.get_sub_tick(), 10);
+        assert!(sim.drain_waveform().is_empty());
+
+        sim.update_switch_state(1, true);
+        sim.compute_frame(100, 1);
+        assert_eq!(sim.get_sub_tick(), 20);
+        assert_eq!(sim.drain_waveform(), vec![11, 13, 1, 14, 13, 0]);
+        assert_eq!(sim.get_component_state(3), 0);
+    }
+}
@@ -0,0 +1,314 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::circuit_element::CircuitElementEnum;
+use crate::js::TimingReport;
+use crate::{ComposantsEnum, Simulation};
+
+use wasm_bindgen::prelude::*;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum TimingRole {
+    // Début de chemin : sources et composants pilotés par l'hôte
+    Source,
+    // Début et fin de chemin
+    Sequential,
+    Combinational,
+    // Fin de chemin : afficheurs et ports de sortie
+    Sink,
+    Ignored,
+}
+
+pub struct TimingPath {
+    // Ids des composants, du début à la fin du chemin
+    pub ids: Vec<usize>,
+    pub delay: u64,
+    pub slack: i64,
+}
+
+impl Simulation {
+    fn timing_role(&self, id: usize, composant: &ComposantsEnum) -> TimingRole {
+        let is_internal = self.internal_ids.contains(&id);
+        match composant {
+            ComposantsEnum::Switch(_)
+            | ComposantsEnum::Button(_)
+            | ComposantsEnum::DipSwitch(_)
+            | ComposantsEnum::Constant(_)
+            | ComposantsEnum::Clock(_)
+            | ComposantsEnum::Keyboard(_) => TimingRole::Source,
+            ComposantsEnum::InputPort(_) if !is_internal => TimingRole::Source,
+            // La RAM est traitée comme séquentielle, lecture comprise
+            ComposantsEnum::FlipFlop(_)
+            | ComposantsEnum::Register(_)
+            | ComposantsEnum::Counter(_)
+            | ComposantsEnum::ShiftRegister(_)
+            | ComposantsEnum::Ram(_)
+            | ComposantsEnum::LatchGate(_)
+            | ComposantsEnum::TimerGate(_) => TimingRole::Sequential,
+            ComposantsEnum::Led(_)
+            | ComposantsEnum::SevenSegment(_)
+            | ComposantsEnum::PixelMatrix(_)
+            | ComposantsEnum::Terminal(_) => TimingRole::Sink,
+            ComposantsEnum::OutputPort(_) if !is_internal => TimingRole::Sink,
+            ComposantsEnum::Pull(_) | ComposantsEnum::Tunnel(_) => TimingRole::Ignored,
+            _ => TimingRole::Combinational,
+        }
+    }
+
+    // Composants lisant une sortie de chaque composant. Une instance de sous-circuit
+    // mise à plat lit ses ports de sortie internes et non ses wire groups d'entrée
+    fn timing_successors(&self) -> HashMap<usize, Vec<usize>> {
+        let mut instance_of_port = HashMap::new();
+        for (id, composant) in self.composants_map.iter() {
+            if let ComposantsEnum::SubCircuit(sub_circuit) = composant {
+                for port in sub_circuit.output_ports.iter() {
+                    instance_of_port.insert(*port, *id);
+                }
+            }
+        }
+        let is_expanded_instance = |id: &usize| {
+            matches!(
+                self.composants_map.get(id),
+                Some(ComposantsEnum::SubCircuit(sub_circuit)) if sub_circuit.is_expanded
+            )
+        };
+
+        let mut successors: HashMap<usize, Vec<usize>> = HashMap::new();
+        for (id, composant) in self.composants_map.iter() {
+            let readers = successors.entry(*id).or_default();
+            for output in composant.circuit_element().outputs.iter() {
+                let CircuitElementEnum::WireGroup(group) = output else {
+                    continue;
+                };
+                let Some(wire_group) = self.wire_groups.get(*group) else {
+                    continue;
+                };
+                for reader in wire_group.circuit_element.outputs.iter() {
+                    if let CircuitElementEnum::Component(reader) = reader {
+                        if !is_expanded_instance(reader) && !readers.contains(reader) {
+                            readers.push(*reader);
+                        }
+                    }
+                }
+            }
+            if let Some(instance) = instance_of_port.get(id) {
+                readers.push(*instance);
+            }
+        }
+        successors
+    }
+
+    // Période d'horloge en sous-ticks : celle de l'horloge la plus rapide, un tick sinon
+    pub(crate) fn clock_period(&self) -> u64 {
+        let sub_ticks = self.timing.sub_ticks_per_tick as u64;
+        self.composants_map
+            .values()
+            .filter_map(|composant| match composant {
+                ComposantsEnum::Clock(clock) => {
+                    Some((clock.high_ticks + clock.low_ticks) as u64 * sub_ticks)
+                }
+                _ => None,
+            })
+            .min()
+            .unwrap_or(sub_ticks)
+    }
+
+    // Chemins combinatoires les plus longs, un par point d'arrivée, triés par délai
+    // décroissant, et composants pris dans une boucle combinatoire
+    pub(crate) fn timing_paths(&self) -> (Vec<TimingPath>, Vec<usize>) {
+        let successors = self.timing_successors();
+        let roles: HashMap<usize, TimingRole> = self
+            .composants_map
+            .iter()
+            .map(|(id, composant)| (*id, self.timing_role(*id, composant)))
+            .collect();
+        let delay = |id: usize| self.timing.delay(id, &self.composants_map[&id]) as u64;
+
+        // Arrivée au plus tard sur les entrées : (délai, composant amont)
+        let mut input_arrivals: HashMap<usize, (u64, usize)> = HashMap::new();
+        let mut output_arrivals: HashMap<usize, u64> = HashMap::new();
+        let mut pending_inputs: HashMap<usize, usize> = HashMap::new();
+        for (id, readers) in successors.iter() {
+            if roles[id] != TimingRole::Combinational {
+                continue;
+            }
+            for reader in readers {
+                *pending_inputs.entry(*reader).or_default() += 1;
+            }
+        }
+
+        // Les débuts de chemin passent avant les composants combinatoires sans amont
+        // combinatoire, pour que ceux-ci connaissent leur arrivée au moment d'être traités
+        let mut ids: Vec<usize> = self.composants_map.keys().copied().collect();
+        ids.sort_unstable();
+        let mut ready: VecDeque<usize> = ids
+            .iter()
+            .filter(|id| matches!(roles[id], TimingRole::Source | TimingRole::Sequential))
+            .copied()
+            .collect();
+        for id in ready.iter() {
+            output_arrivals.insert(*id, delay(*id));
+        }
+        ready.extend(ids.iter().filter(|id| {
+            roles[id] == TimingRole::Combinational && !pending_inputs.contains_key(id)
+        }));
+
+        while let Some(id) = ready.pop_front() {
+            let arrival = match roles[&id] {
+                TimingRole::Combinational => {
+                    let arrival =
+                        input_arrivals.get(&id).map_or(0, |(arrival, _)| *arrival) + delay(id);
+                    output_arrivals.insert(id, arrival);
+                    arrival
+                }
+                _ => output_arrivals[&id],
+            };
+            for reader in successors[&id].iter() {
+                let role = roles[reader];
+                if role == TimingRole::Ignored || role == TimingRole::Source {
+                    continue;
+                }
+                let best = input_arrivals.entry(*reader).or_insert((arrival, id));
+                if arrival > best.0 {
+                    *best = (arrival, id);
+                }
+                if role == TimingRole::Combinational && roles[&id] == TimingRole::Combinational {
+                    let pending = pending_inputs.get_mut(reader).unwrap();
+                    *pending -= 1;
+                    if *pending == 0 {
+                        ready.push_back(*reader);
+                    }
+                }
+            }
+        }
+
+        // Composants jamais traités : on retire ceux qui ne font que suivre une boucle
+        let mut loops: Vec<usize> = pending_inputs
+            .iter()
+            .filter(|(_, pending)| **pending > 0)
+            .map(|(id, _)| *id)
+            .collect();
+        loop {
+            let before = loops.len();
+            let remaining = loops.clone();
+            loops.retain(|id| {
+                successors[id]
+                    .iter()
+                    .any(|reader| remaining.contains(reader))
+            });
+            if loops.len() == before {
+                break;
+            }
+        }
+        loops.sort_unstable();
+
+        let period = self.clock_period() as i64;
+        let mut paths: Vec<TimingPath> = ids
+            .iter()
+            .filter(|id| matches!(roles[id], TimingRole::Sequential | TimingRole::Sink))
+            .filter_map(|id| {
+                let (arrival, mut current) = *input_arrivals.get(id)?;
+                let mut path = vec![*id, current];
+                while let Some((_, previous)) = input_arrivals.get(&current) {
+                    if !matches!(roles[&current], TimingRole::Combinational) {
+                        break;
+                    }
+                    current = *previous;
+                    path.push(current);
+                }
+                path.reverse();
+                Some(TimingPath {
+                    ids: path,
+                    delay: arrival,
+                    slack: period - arrival as i64,
+                })
+            })
+            .collect();
+        paths.sort_by(|a, b| b.delay.cmp(&a.delay).then(a.ids.cmp(&b.ids)));
+        (paths, loops)
+    }
+}
+
+#[wasm_bindgen]
+impl Simulation {
+    // Analyse statique du graphe construit par `compute_connections`, avec les délais
+    // du modèle de timing (qu'il soit actif ou non). Les délais sont en sous-ticks
+    pub fn timing_report(&self, max_paths: usize) -> TimingReport {
+        let (mut paths, loops) = self.timing_paths();
+        let critical_delay = paths.first().map_or(0, |path| path.delay);
+        paths.truncate(max_paths);
+        TimingReport::new(
+            paths,
+            loops,
+            critical_delay,
+            self.clock_period(),
+            self.timing.sub_ticks_per_tick,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::Orientation;
+    use crate::Simulation;
+
+    // Additionneur 4 bits à propagation de retenue, placé et routé. Bit `i` : XOR `x`,
+    // AND `g`, XOR somme `s`, AND `p`, OR retenue `c`, d'ids `10 + 10 * i` à `14 + 10 * i`
+    fn ripple_carry_adder() -> Simulation {
+        let mut sim = Simulation::new();
+        let mut nets = Vec::new();
+        sim.add_switch(vec![0, 0], 9);
+        let mut carry = (9, 0);
+        for bit in 0..4 {
+            let (a, b, base) = (1 + bit, 5 + bit, 10 + 10 * bit);
+            sim.add_switch(vec![0, 0], a);
+            sim.add_switch(vec![0, 0], b);
+            sim.add_xor_gate(vec![0, 0], Orientation::Right, base);
+            sim.add_and_gate(vec![0, 0], Orientation::Right, base + 1);
+            sim.add_xor_gate(vec![0, 0], Orientation::Right, base + 2);
+            sim.add_and_gate(vec![0, 0], Orientation::Right, base + 3);
+            sim.add_or_gate(vec![0, 0], Orientation::Right, base + 4);
+            sim.add_led(vec![0, 0], 0, Orientation::Right, 100 + bit);
+            nets.push(vec![(a, 0), (base, 0), (base + 1, 0)]);
+            nets.push(vec![(b, 0), (base, 1), (base + 1, 1)]);
+            nets.push(vec![(base, 2), (base + 2, 0), (base + 3, 0)]);
+            nets.push(vec![carry, (base + 2, 1), (base + 3, 1)]);
+            nets.push(vec![(base + 1, 2), (base + 4, 0)]);
+            nets.push(vec![(base + 3, 2), (base + 4, 1)]);
+            nets.push(vec![(base + 2, 2), (100 + bit, 0)]);
+            carry = (base + 4, 2);
+        }
+        sim.add_led(vec![0, 0], 0, Orientation::Right, 104);
+        nets.push(vec![carry, (104, 0)]);
+        let placement = sim.place_components(&nets, [0, 0], 200);
+        assert!(placement.failed().is_empty());
+        sim.compute_connections();
+        sim
+    }
+
+    #[test]
+    fn ripple_carry_critical_path_is_the_carry_chain() {
+        let sim = ripple_carry_adder();
+        let (paths, loops) = sim.timing_paths();
+        assert!(loops.is_empty());
+        let critical = &paths[0];
+        // Retenue `c(i+1) = c(i) + 2` à partir de `c1 = 3`
+        assert_eq!(critical.delay, 9);
+        assert_eq!(critical.ids.last(), Some(&104));
+        for bit in 0..4 {
+            assert!(critical.ids.contains(&(14 + 10 * bit)));
+        }
+        for bit in 1..4 {
+            assert!(critical.ids.contains(&(13 + 10 * bit)));
+        }
+    }
+
+    #[test]
+    fn summary_ignores_max_paths() {
+        let sim = ripple_carry_adder();
+        let report = sim.timing_report(0);
+        assert_eq!(report.critical_delay(), 9);
+        assert_eq!(report.worst_slack(), 10 - 9);
+        assert_eq!(report.min_clock_period_ticks(), 1);
+        assert!(report.max_frequency().is_finite());
+    }
+}
@@ -3,6 +3,7 @@ use wasm_bindgen::prelude::*;
 pub type Position = [i32; 2];
 
 #[wasm_bindgen]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum Orientation {
     Up,
     Right,
@@ -11,17 +12,205 @@ pub enum Orientation {
 }
 
 pub struct OldWireGroup {
-    pub index: usize,
-    pub state: bool,
+    pub state: u64,
 }
 
 pub struct OldComponents {
     pub id: usize,
-    pub state: bool,
+    pub state: u64,
 }
 
 #[wasm_bindgen]
 pub struct ChangedElement {
     pub id: usize,
     pub state: bool,
+    // Exposés par des getters : un `u64` arriverait en BigInt côté JS
+    pub(crate) value: u64,
+    pub(crate) width: u8,
+}
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ClockEdge {
+    Rising,
+    Falling,
+}
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FlipFlopKind {
+    D,
+    T,
+    JK,
+    SR,
+}
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum LogicGateKind {
+    And,
+    Or,
+    Xor,
+    Nand,
+    Nor,
+    Xnor,
+}
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ShiftRegisterMode {
+    Siso,
+    Sipo,
+    Piso,
+}
+
+// Codes d'opération de l'ALU, dans l'ordre de la valeur présente sur la pin OP
+#[wasm_bindgen]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AluOperation {
+    Add,
+    Sub,
+    And,
+    Or,
+    Xor,
+    ShiftLeft,
+    ShiftRight,
+    ShiftRightArithmetic,
+}
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SevenSegmentMode {
+    Segments,
+    Hex,
+}
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PixelMatrixMode {
+    RowColumn,
+    Framebuffer,
+}
+
+// État visuel d'un afficheur, transmis au front dans les TickResults
+pub enum DisplayData {
+    Led {
+        value: u64,
+        color: u32,
+    },
+    // bit 0 = segment a, ..., bit 6 = segment g, bit 7 = point
+    SevenSegment {
+        segments: u8,
+    },
+    // bit c de `pixels[r]` = pixel de la ligne r, colonne c
+    PixelMatrix {
+        rows: u8,
+        columns: u8,
+        pixels: Vec<u64>,
+    },
+}
+
+pub struct ChangedDisplay {
+    pub id: usize,
+    pub data: DisplayData,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PullKind {
+    Up,
+    Down,
+}
+
+// Règle de connexion entre wires. `Endpoints` : deux wires sont reliés quand l'extrémité
+// de l'un touche l'autre ou qu'ils passent tous deux par une jonction ; un simple
+// croisement ne les relie pas. `Legacy` : ils sont reliés dès qu'un de leurs points coïncide.
+// `Legacy` est le mode par défaut, celui des sauvegardes existantes
+#[wasm_bindgen]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ConnectionMode {
+    Endpoints,
+    Legacy,
+}
+
+// Forme du circuit produit par la synthèse logique : somme de produits minimale
+// sur deux niveaux, ou forme factorisée sur plusieurs niveaux
+#[wasm_bindgen]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SynthesisForm {
+    SumOfProducts,
+    Factored,
+}
+
+// Composant créé par l'hôte Rust, décrit pour que le front puisse l'afficher
+pub struct PlacedComponent {
+    pub id: usize,
+    pub kind: &'static str,
+    pub position: Position,
+    pub orientation: Orientation,
+    // Nombre de pins d'entrée des portes logiques
+    pub inputs: usize,
+}
+
+// Portes cibles de l'optimiseur : types conservés, ou uniquement des NAND ou des NOR
+#[wasm_bindgen]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GateMapping {
+    Keep,
+    NandOnly,
+    NorOnly,
+}
+
+// Symétrie d'une sélection : `Horizontal` échange la gauche et la droite,
+// `Vertical` le haut et le bas
+#[wasm_bindgen]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MirrorDirection {
+    Horizontal,
+    Vertical,
+}
+
+// Type de composant, pour configurer les délais de propagation par type
+#[wasm_bindgen]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ComponentKind {
+    OrGate,
+    AndGate,
+    Switch,
+    XorGate,
+    NotGate,
+    BufferGate,
+    LatchGate,
+    TimerGate,
+    Splitter,
+    Merger,
+    InputPort,
+    OutputPort,
+    SubCircuit,
+    Clock,
+    FlipFlop,
+    NandGate,
+    NorGate,
+    XnorGate,
+    Rom,
+    Ram,
+    Register,
+    Counter,
+    ShiftRegister,
+    Multiplexer,
+    Demultiplexer,
+    Decoder,
+    PriorityEncoder,
+    Adder,
+    Comparator,
+    Alu,
+    Button,
+    DipSwitch,
+    Led,
+    SevenSegment,
+    PixelMatrix,
+    Keyboard,
+    Terminal,
+    Constant,
+    Pull,
+    Tunnel,
 }
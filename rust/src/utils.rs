@@ -1,6 +1,13 @@
-use wasm_bindgen::prelude::*;
-use web_sys::console;
+pub fn console_log(_s: &str) {
+    // web_sys::console::log_1(&wasm_bindgen::JsValue::from_str(_s));
+}
+
+pub const MAX_WIDTH: u8 = 64;
 
-pub fn console_log(s: &str) {
-    // console::log_1(&JsValue::from_str(s));
+pub fn width_mask(width: u8) -> u64 {
+    if width >= MAX_WIDTH {
+        u64::MAX
+    } else {
+        (1u64 << width) - 1
+    }
 }
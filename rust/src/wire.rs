@@ -1,7 +1,9 @@
 use crate::circuit_element::CircuitElement;
-use crate::types::Position;
+use crate::simulation::FaultSite;
+use crate::types::{Position, PullKind};
 use crate::Simulation;
 
+#[derive(Clone)]
 pub struct Wire {
     pub circuit_element: CircuitElement,
     pub positions: Vec<Position>,
@@ -14,12 +16,32 @@ impl Wire {
             positions,
         }
     }
+
+    pub fn new_bus(id: usize, positions: Vec<Position>, width: u8) -> Self {
+        let mut wire = Wire::new(id, positions);
+        wire.circuit_element.width = width;
+        wire
+    }
+}
+
+// Le point est-il sur un des segments de la polyligne ?
+pub fn polyline_contains(positions: &[Position], point: Position) -> bool {
+    if positions.len() == 1 {
+        return positions[0] == point;
+    }
+    positions.windows(2).any(|segment| {
+        let ([x1, y1], [x2, y2], [x, y]) = (segment[0], segment[1], point);
+        let cross = (x2 - x1) as i64 * (y - y1) as i64 - (y2 - y1) as i64 * (x - x1) as i64;
+        cross == 0 && x1.min(x2) <= x && x <= x1.max(x2) && y1.min(y2) <= y && y <= y1.max(y2)
+    })
 }
 
+#[derive(Clone)]
 pub struct WireGroup {
     pub circuit_element: CircuitElement,
     pub positions: Vec<Position>,
     pub wires: Vec<usize>,
+    pub pull: Option<PullKind>,
 }
 
 impl WireGroup {
@@ -28,20 +50,49 @@ impl WireGroup {
             circuit_element: CircuitElement::new(id),
             wires: Vec::new(),
             positions: Vec::new(),
+            pull: None,
         }
     }
 
-    pub fn add_wire(&mut self, wire: usize, positions: Vec<Position>) {
+    pub fn add_wire(&mut self, wire: usize, positions: Vec<Position>, width: u8) {
         self.positions.extend(positions);
-        self.wires.push(wire)
+        self.wires.push(wire);
+        // Le groupe prend la largeur du plus large de ses wires
+        if width > self.circuit_element.width {
+            self.circuit_element.width = width;
+        }
+    }
+
+    // Reprend les wires d'un autre groupe, avant que les composants ne soient connectés
+    pub fn absorb(&mut self, other: WireGroup) {
+        self.positions.extend(other.positions);
+        self.wires.extend(other.wires);
+        self.circuit_element.width = self.circuit_element.width.max(other.circuit_element.width);
+        self.pull = self.pull.or(other.pull);
+    }
+
+    // Ni pilote ni résistance de tirage : le groupe lit 0 mais sa valeur n'est pas
+    // définie, contrairement à un groupe tiré vers le bas
+    pub fn is_floating(&self) -> bool {
+        self.circuit_element.inputs.is_empty() && self.pull.is_none()
     }
 
-    pub fn compute_next_state(&self, sim: &Simulation) -> (bool, bool) {
-        let new_state = self
-            .circuit_element
-            .inputs
-            .iter()
-            .any(|input| input.get_state(sim));
+    pub fn compute_next_state(&self, sim: &Simulation) -> (u64, bool) {
+        // Un groupe collé garde sa valeur forcée quels que soient ses pilotes
+        let forced = sim.forced_state(FaultSite::WireGroup(self.circuit_element.id));
+        // Sans pilote, le groupe prend la valeur de sa résistance de tirage
+        let new_state = match (forced, self.circuit_element.inputs.is_empty(), self.pull) {
+            (Some(state), _, _) => state,
+            (None, true, Some(PullKind::Up)) => self.circuit_element.mask(),
+            (None, true, Some(PullKind::Down) | None) => 0,
+            _ => {
+                self.circuit_element
+                    .inputs
+                    .iter()
+                    .fold(0, |acc, input| acc | input.get_state(sim))
+                    & self.circuit_element.mask()
+            }
+        };
         let is_different = new_state != self.circuit_element.state;
         (new_state, is_different)
     }
@@ -18,6 +18,15 @@ import { Switch } from "../scene/elements/component/switch";
 import type { Wire } from "../scene/elements/wire";
 import { ComposantTypes, ElementTypes } from "../utils/types";
 
+// Élément de TickResults.wires / TickResults.components
+type ChangedElement = {
+	index: number;
+	state: boolean;
+	// Valeur du bus en number ; bigValue n'est présent qu'au-delà de 32 bits
+	value: number;
+	bigValue?: bigint;
+};
+
 export class Simulation {
 	public scene: SimulationScene;
 	public db: SimulationDb;
@@ -143,7 +152,7 @@ export class Simulation {
 		if (tickResult.wires.length === 0 && tickResult.components.length === 0) {
 			return;
 		}
-		tickResult.wires.forEach((wire: { index: number; state: boolean }) => {
+		tickResult.wires.forEach((wire: ChangedElement) => {
 			const wireMesh = this.wires[wire.index];
 			if (!wireMesh) {
 				console.error("wire not found", wire.index, this.wires);
@@ -151,11 +160,9 @@ export class Simulation {
 			}
 			this.wires[wire.index].setState(wire.state);
 		});
-		tickResult.components.forEach(
-			(component: { index: number; state: boolean }) => {
-				this.components[component.index].setState(component.state);
-			},
-		);
+		tickResult.components.forEach((component: ChangedElement) => {
+			this.components[component.index].setState(component.state);
+		});
 		tickResult.free();
 	}
 